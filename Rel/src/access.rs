@@ -0,0 +1,34 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage_types::{DocKey, ViewGrantKey};
+
+fn grant_key(token_id: u32, grantee: &Address) -> DocKey {
+    DocKey::ViewGrant(ViewGrantKey {
+        token_id,
+        grantee: grantee.clone(),
+    })
+}
+
+// Timestamp until which `grantee` may view the document content, if granted.
+pub fn read_grant(e: &Env, token_id: u32, grantee: &Address) -> Option<u64> {
+    e.storage().persistent().get(&grant_key(token_id, grantee))
+}
+
+pub fn write_grant(e: &Env, token_id: u32, grantee: &Address, expires_at: u64) {
+    e.storage()
+        .persistent()
+        .set(&grant_key(token_id, grantee), &expires_at);
+}
+
+pub fn remove_grant(e: &Env, token_id: u32, grantee: &Address) {
+    e.storage()
+        .persistent()
+        .remove(&grant_key(token_id, grantee));
+}
+
+pub fn has_valid_grant(e: &Env, token_id: u32, viewer: &Address) -> bool {
+    match read_grant(e, token_id, viewer) {
+        Some(expires_at) => e.ledger().timestamp() < expires_at,
+        None => false,
+    }
+}
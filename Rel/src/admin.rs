@@ -1,19 +1,115 @@
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{panic_with_error, vec, Address, Env, Vec};
 
 use crate::storage_types::DataKey;
+use crate::{Error, ExtendedError};
 
-pub fn has_administrator(e: &Env) -> bool {
-    let key = DataKey::Admin;
-    e.storage().instance().has(&key)
-}
+use petal_common::admin as shared;
+pub use petal_common::admin::{
+    has_administrator, is_renounced, read_renounceable, write_administrator, write_renounceable,
+};
 
+// Once the admin has been renounced every admin-gated entrypoint fails here.
 pub fn read_administrator(e: &Env) -> Address {
-    let key = DataKey::Admin;
-    e.storage().instance().get(&key).unwrap_or_else(|| panic!("Admin not found"))
-    // e.storage().instance().get(&key).unwrap()
+    if is_renounced(e) {
+        panic_with_error!(e, ExtendedError::AdminRenounced)
+    }
+    shared::read_administrator(e)
+}
+
+// Compares against the admin without failing once it has been renounced.
+pub fn is_administrator(e: &Env, id: &Address) -> bool {
+    has_administrator(e) && shared::read_administrator(e) == *id
+}
+
+// Contracts initialised before multi-admin support have a single admin and
+// a threshold of one. A renounced contract has no admins at all.
+pub fn read_admins(e: &Env) -> Vec<Address> {
+    let key = DataKey::Admins;
+    e.storage().instance().get(&key).unwrap_or_else(|| {
+        if has_administrator(e) {
+            vec![e, shared::read_administrator(e)]
+        } else {
+            Vec::new(e)
+        }
+    })
+}
+
+pub fn read_threshold(e: &Env) -> u32 {
+    let key = DataKey::Threshold;
+    e.storage().instance().get(&key).unwrap_or(1)
+}
+
+pub fn is_admin(e: &Env, id: &Address) -> bool {
+    read_admins(e).contains(id)
 }
 
-pub fn write_administrator(e: &Env, id: &Address) {
-    let key = DataKey::Admin;
-    e.storage().instance().set(&key, id);
+// A repeated address would count twice towards the threshold, so the set
+// must be distinct before the threshold is checked against its size.
+pub fn check_admins(admins: &Vec<Address>, threshold: u32) -> Result<(), soroban_sdk::Error> {
+    for (i, admin) in admins.iter().enumerate() {
+        if admins.first_index_of(&admin) != Some(i as u32) {
+            return Err(ExtendedError::DuplicateAdmin.into());
+        }
+    }
+    if threshold == 0 || threshold > admins.len() {
+        return Err(Error::InvalidThreshold.into());
+    }
+    Ok(())
+}
+
+// The first admin stays the primary one used by single-admin entrypoints.
+pub fn write_admins(e: &Env, admins: &Vec<Address>, threshold: u32) {
+    if let Err(error) = check_admins(admins, threshold) {
+        panic_with_error!(e, error)
+    }
+    let primary = admins.first().unwrap();
+
+    write_administrator(e, &primary);
+    e.storage().instance().set(&DataKey::Admins, admins);
+    e.storage().instance().set(&DataKey::Threshold, &threshold);
+}
+
+// Sensitive operations may only skip the proposal flow while one approval is
+// enough anyway.
+pub fn require_single_admin(e: &Env) -> Address {
+    if read_threshold(e) > 1 {
+        panic_with_error!(e, Error::ApprovalRequired)
+    }
+    let admin = read_administrator(e);
+    admin.require_auth();
+    admin
+}
+
+// Renouncing drops every admin at once, so it is only allowed while a
+// single approval is enough and the admin has opted in beforehand. The
+// request has to be confirmed in a later ledger.
+pub fn request_renounce(e: &Env) -> Address {
+    if !read_renounceable(e) {
+        panic_with_error!(e, ExtendedError::RenounceDisabled)
+    }
+    let admin = require_single_admin(e);
+
+    shared::write_renounce_request(e);
+    admin
+}
+
+pub fn cancel_renounce(e: &Env) -> Address {
+    let admin = read_administrator(e);
+    admin.require_auth();
+
+    shared::clear_renounce_request(e);
+    admin
+}
+
+pub fn renounce(e: &Env) -> Address {
+    if !shared::is_renounce_confirmable(e) {
+        panic_with_error!(e, ExtendedError::RenounceNotRequested)
+    }
+    let admin = require_single_admin(e);
+
+    e.storage().instance().remove(&DataKey::Admins);
+    e.storage().instance().remove(&DataKey::Threshold);
+    shared::clear_renounce_request(e);
+    shared::clear_administrator(e);
+    admin
 }
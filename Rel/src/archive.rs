@@ -0,0 +1,63 @@
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::audit::read_audit_len;
+use crate::document::read_signings;
+use crate::history;
+use crate::storage_types::{AuditKey, DataKey, DocKey};
+use crate::{certificate, event, ArchivedDocument, Certificate, ExtendedError, CONTRACT_VERSION};
+
+pub fn read_archive(e: &Env, token_id: u32) -> Option<ArchivedDocument> {
+    let key = DataKey::Archived(token_id);
+    e.storage().persistent().get(&key)
+}
+
+// The contract version whose `Certificate` layout the archived hash covers.
+// Only recorded from version 4 on.
+pub fn read_archive_version(e: &Env, token_id: u32) -> Option<u32> {
+    let key = DocKey::ArchiveVersion(token_id);
+    e.storage().persistent().get(&key)
+}
+
+// Publishes the full certificate one last time, then drops everything but the
+// owner, creator, status and document hash, which stay for ownership, the
+// hash index and permissions. Documents still in the legacy maps would fall
+// back to those once their per-token entries are gone, so they have to be
+// migrated first.
+pub fn archive(e: &Env, token_id: u32, snapshot: Certificate) -> ArchivedDocument {
+    if read_archive(e, token_id).is_some() {
+        panic_with_error!(e, ExtendedError::AlreadyArchived)
+    }
+    let storage = e.storage().persistent();
+    if !storage.has(&DataKey::Signings(token_id)) {
+        panic_with_error!(e, ExtendedError::MigrationPending)
+    }
+
+    let archived = ArchivedDocument {
+        certificate_hash: certificate::hash(e, snapshot.clone()),
+        completed_at: snapshot.completed_at,
+        archived_at: e.ledger().timestamp(),
+    };
+    event::archived(e, token_id, snapshot);
+
+    for index in 0..read_audit_len(e, token_id) {
+        storage.remove(&DataKey::AuditEntry(AuditKey { token_id, index }));
+    }
+    storage.remove(&DataKey::AuditLen(token_id));
+    storage.remove(&DataKey::Uri(token_id));
+    if let Some(signings) = read_signings(e, token_id) {
+        history::clear(e, token_id, &signings);
+    }
+    storage.remove(&DataKey::Signings(token_id));
+    storage.remove(&DataKey::Deadline(token_id));
+    storage.remove(&DataKey::CompletedAt(token_id));
+    storage.remove(&DataKey::Reminder(token_id));
+    storage.remove(&DataKey::ReminderSent(token_id));
+    storage.remove(&DataKey::RequiredAttestation(token_id));
+    storage.remove(&DataKey::DocGracePeriod(token_id));
+    storage.remove(&DataKey::SignerDeadlines(token_id));
+    storage.remove(&DataKey::FeePaid(token_id));
+
+    storage.set(&DataKey::Archived(token_id), &archived);
+    storage.set(&DocKey::ArchiveVersion(token_id), &CONTRACT_VERSION);
+    archived
+}
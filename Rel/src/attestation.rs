@@ -0,0 +1,50 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::admin::is_administrator;
+use crate::storage_types::DataKey;
+use crate::Error;
+
+pub fn is_attestor(e: &Env, attestor: &Address) -> bool {
+    let key = DataKey::Attestor(attestor.clone());
+    e.storage().persistent().get(&key).unwrap_or(false)
+}
+
+pub fn write_attestor(e: &Env, attestor: &Address, enabled: bool) {
+    let key = DataKey::Attestor(attestor.clone());
+    if enabled {
+        e.storage().persistent().set(&key, &true);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+// Unattested addresses are at level 0.
+pub fn read_attestation(e: &Env, id: &Address) -> u32 {
+    let key = DataKey::Attestation(id.clone());
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn attest(e: &Env, attestor: &Address, id: &Address, level: u32) {
+    if !is_administrator(e, attestor) && !is_attestor(e, attestor) {
+        panic_with_error!(e, Error::NotAnAttestor)
+    }
+    let key = DataKey::Attestation(id.clone());
+    e.storage().persistent().set(&key, &level);
+}
+
+pub fn read_required_level(e: &Env, token_id: u32) -> u32 {
+    let key = DataKey::RequiredAttestation(token_id);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn write_required_level(e: &Env, token_id: u32, level: u32) {
+    let key = DataKey::RequiredAttestation(token_id);
+    e.storage().persistent().set(&key, &level);
+}
+
+pub fn check_attested(e: &Env, token_id: u32, signer: &Address) -> Result<(), Error> {
+    if read_attestation(e, signer) < read_required_level(e, token_id) {
+        return Err(Error::SignerNotAttested);
+    }
+    Ok(())
+}
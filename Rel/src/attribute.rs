@@ -0,0 +1,46 @@
+use soroban_sdk::{Env, Map, String, Symbol};
+
+use crate::storage_types::{DocKey, MAX_ATTRIBUTES, MAX_ATTRIBUTE_LEN};
+use crate::{Error, ExtendedError};
+
+pub fn read_attributes(e: &Env, token_id: u32) -> Map<Symbol, String> {
+    let key = DocKey::Attributes(token_id);
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Map::new(e))
+}
+
+fn write_attributes(e: &Env, token_id: u32, attributes: &Map<Symbol, String>) {
+    let key = DocKey::Attributes(token_id);
+    if attributes.is_empty() {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, attributes);
+    }
+}
+
+// Overwriting an existing key doesn't count against the limit.
+pub fn set_attribute(
+    e: &Env,
+    token_id: u32,
+    name: Symbol,
+    value: String,
+) -> Result<(), soroban_sdk::Error> {
+    if value.len() > MAX_ATTRIBUTE_LEN {
+        return Err(Error::StringTooLong.into());
+    }
+    let mut attributes = read_attributes(e, token_id);
+    if !attributes.contains_key(name.clone()) && attributes.len() >= MAX_ATTRIBUTES {
+        return Err(ExtendedError::TooManyAttributes.into());
+    }
+    attributes.set(name, value);
+    write_attributes(e, token_id, &attributes);
+    Ok(())
+}
+
+pub fn remove_attribute(e: &Env, token_id: u32, name: Symbol) {
+    let mut attributes = read_attributes(e, token_id);
+    attributes.remove(name);
+    write_attributes(e, token_id, &attributes);
+}
@@ -0,0 +1,37 @@
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::storage_types::{AuditKey, DataKey};
+use crate::{AuditAction, AuditEntry};
+
+// Entries are stored under their own keys so appending never rewrites the
+// existing log, however long it grows.
+pub fn read_audit_len(e: &Env, token_id: u32) -> u32 {
+    let key = DataKey::AuditLen(token_id);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn append(e: &Env, token_id: u32, action: AuditAction, actor: &Address) {
+    let index = read_audit_len(e, token_id);
+    let entry = AuditEntry {
+        action,
+        actor: actor.clone(),
+        timestamp: e.ledger().timestamp(),
+    };
+    let key = DataKey::AuditEntry(AuditKey { token_id, index });
+    e.storage().persistent().set(&key, &entry);
+
+    let key = DataKey::AuditLen(token_id);
+    e.storage().persistent().set(&key, &(index + 1));
+}
+
+pub fn read_audit_log(e: &Env, token_id: u32, start: u32, limit: u32) -> Vec<AuditEntry> {
+    let end = read_audit_len(e, token_id).min(start.saturating_add(limit));
+    let mut entries = Vec::new(e);
+    for index in start..end {
+        let key = DataKey::AuditEntry(AuditKey { token_id, index });
+        if let Some(entry) = e.storage().persistent().get(&key) {
+            entries.push_back(entry);
+        }
+    }
+    entries
+}
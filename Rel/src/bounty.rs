@@ -0,0 +1,100 @@
+use soroban_sdk::{panic_with_error, token, Address, Env};
+
+use crate::fee::read_fee_token;
+use crate::storage_types::{AccessKey, ConfigKey};
+use crate::{event, BountyAction, Error};
+
+// Rewards are paid in the fee token out of a pool the admin tops up. The pool
+// is tracked on its own, per token, so keepers are never paid out of
+// collected fees or in a token that was never put into the pool.
+pub fn read_pool(e: &Env, token: &Address) -> i128 {
+    let key = ConfigKey::BountyBalance(token.clone());
+    let pool = e.storage().instance().get(&key).unwrap_or(0);
+    if read_fee_token(e).as_ref() == Some(token) {
+        pool + read_legacy_pool(e)
+    } else {
+        pool
+    }
+}
+
+fn write_pool(e: &Env, token: &Address, amount: i128) {
+    if read_fee_token(e).as_ref() == Some(token) {
+        e.storage().instance().remove(&ConfigKey::BountyPool);
+    }
+    let key = ConfigKey::BountyBalance(token.clone());
+    e.storage().instance().set(&key, &amount);
+}
+
+// A pool funded before balances were kept per token has no token recorded.
+// It is counted towards the current fee token, and tagged with it before the
+// fee token changes.
+fn read_legacy_pool(e: &Env) -> i128 {
+    let key = ConfigKey::BountyPool;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn settle_legacy_pool(e: &Env) {
+    if let Some(fee_token) = read_fee_token(e) {
+        if read_legacy_pool(e) != 0 {
+            write_pool(e, &fee_token, read_pool(e, &fee_token));
+        }
+    }
+}
+
+pub fn read_reward(e: &Env, action: BountyAction) -> i128 {
+    let key = ConfigKey::BountyReward(action);
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_reward(e: &Env, action: BountyAction, reward: i128) {
+    if reward < 0 {
+        panic_with_error!(e, Error::InvalidFeeAmount)
+    }
+    let key = ConfigKey::BountyReward(action);
+    e.storage().instance().set(&key, &reward);
+}
+
+pub fn read_earnings(e: &Env, keeper: &Address) -> i128 {
+    let key = AccessKey::KeeperEarnings(keeper.clone());
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+fn fee_token(e: &Env) -> Address {
+    match read_fee_token(e) {
+        Some(fee_token) => fee_token,
+        None => panic_with_error!(e, Error::FeeTokenNotSet),
+    }
+}
+
+pub fn fund(e: &Env, from: &Address, amount: i128) {
+    if amount <= 0 {
+        panic_with_error!(e, Error::InvalidFeeAmount)
+    }
+    let fee_token = fee_token(e);
+    token::Client::new(e, &fee_token).transfer(from, e.current_contract_address(), &amount);
+    write_pool(e, &fee_token, read_pool(e, &fee_token) + amount);
+}
+
+// Pays `units` rewards for `action` from the current fee token's pool, or
+// whatever is left in it if that is less. Returns the amount paid.
+pub fn pay(e: &Env, action: BountyAction, keeper: &Address, units: u32) -> i128 {
+    let owed = read_reward(e, action).saturating_mul(units as i128);
+    if owed <= 0 {
+        return 0;
+    }
+    let fee_token = fee_token(e);
+    let pool = read_pool(e, &fee_token);
+    let amount = owed.min(pool);
+    if amount <= 0 {
+        return 0;
+    }
+
+    token::Client::new(e, &fee_token).transfer(&e.current_contract_address(), keeper, &amount);
+    write_pool(e, &fee_token, pool - amount);
+    let key = AccessKey::KeeperEarnings(keeper.clone());
+    e.storage()
+        .persistent()
+        .set(&key, &(read_earnings(e, keeper) + amount));
+    event::bounty_paid(e, action, keeper.clone(), amount);
+    amount
+}
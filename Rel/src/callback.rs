@@ -0,0 +1,36 @@
+use soroban_sdk::{vec, Env, IntoVal, Val};
+
+use crate::storage_types::DataKey;
+use crate::{event, Callback, DocumentStatus};
+
+pub fn read_callback(e: &Env, token_id: u32) -> Option<Callback> {
+    let key = DataKey::Callback(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_callback(e: &Env, token_id: u32, callback: &Callback) {
+    let key = DataKey::Callback(token_id);
+    e.storage().persistent().set(&key, callback);
+}
+
+// Called once the document has reached its final status and every write for
+// that transition is done. The registration is dropped before the call so a
+// reentrant callback sees the final state and cannot fire it twice. A failing
+// callback is reported through an event rather than undoing the transition.
+pub fn notify(e: &Env, token_id: u32, status: DocumentStatus) {
+    let callback = match read_callback(e, token_id) {
+        Some(callback) => callback,
+        None => return,
+    };
+    e.storage()
+        .persistent()
+        .remove(&DataKey::Callback(token_id));
+
+    let args = vec![e, token_id.into_val(e), status.into_val(e)];
+    let result = e.try_invoke_contract::<Val, soroban_sdk::Error>(
+        &callback.contract,
+        &callback.function,
+        args,
+    );
+    event::callback(e, token_id, callback, matches!(result, Ok(Ok(_))));
+}
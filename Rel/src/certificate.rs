@@ -0,0 +1,77 @@
+use soroban_sdk::{xdr::ToXdr, Address, BytesN, Env, Map, Vec};
+
+use crate::audit::{read_audit_len, read_audit_log};
+use crate::document::{read_deadline, read_document_hash, read_hash_algo, read_signings};
+use crate::status::read_completed_at;
+use crate::witness::read_witnesses;
+use crate::{AuditAction, Certificate, CertificateSigner, DocumentStatus, Error};
+
+// When each signer last signed or rejected, taken from the audit log.
+fn signature_times(e: &Env, token_id: u32) -> Map<Address, u64> {
+    let mut times = Map::new(e);
+    let log = read_audit_log(e, token_id, 0, read_audit_len(e, token_id));
+    for entry in log.iter() {
+        if matches!(
+            entry.action,
+            AuditAction::Signed
+                | AuditAction::SignedLate
+                | AuditAction::Rejected
+                | AuditAction::Witnessed
+                | AuditAction::WitnessDeclined
+        ) {
+            times.set(entry.actor, entry.timestamp);
+        }
+    }
+    times
+}
+
+pub fn build(e: &Env, token_id: u32, status: DocumentStatus) -> Result<Certificate, Error> {
+    let document_hash = match read_document_hash(e, token_id) {
+        Some(hash) => hash,
+        None => return Err(Error::HashNotFound),
+    };
+    let deadline = match read_deadline(e, token_id) {
+        Some(deadline) => deadline,
+        None => return Err(Error::DeadlineNotFound),
+    };
+    let signings = match read_signings(e, token_id) {
+        Some(signings) => signings,
+        None => return Err(Error::DocumentSigningsIsEmpty),
+    };
+
+    let times = signature_times(e, token_id);
+    let listed_witnesses = read_witnesses(e, token_id);
+    let mut signers = Vec::new(e);
+    let mut witnesses = Vec::new(e);
+    for (signer, signature) in signings.iter() {
+        let entry = CertificateSigner {
+            signed_at: times.get(signer.clone()),
+            signer: signer.clone(),
+            status: signature,
+        };
+        if listed_witnesses.contains(&signer) {
+            witnesses.push_back(entry);
+        } else {
+            signers.push_back(entry);
+        }
+    }
+
+    Ok(Certificate {
+        token_id,
+        document_hash,
+        hash_algo: read_hash_algo(e, token_id),
+        signers,
+        witnesses,
+        deadline,
+        status,
+        completed_at: read_completed_at(e, token_id),
+        contract: e.current_contract_address(),
+        network_id: e.ledger().network_id(),
+    })
+}
+
+// Hash of the certificate's XDR, so an off-chain rendering can be checked
+// against the chain without trusting whoever produced it.
+pub fn hash(e: &Env, certificate: Certificate) -> BytesN<32> {
+    e.crypto().sha256(&certificate.to_xdr(e)).into()
+}
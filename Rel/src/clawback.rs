@@ -0,0 +1,49 @@
+use soroban_sdk::{panic_with_error, Address, Env, String};
+
+use crate::audit;
+use crate::document::write_owner;
+use crate::escrow::{self, read_escrow};
+use crate::ownership;
+use crate::status::{read_status, write_status};
+use crate::storage_types::DataKey;
+use crate::token_contract::{self, read_token_contract};
+use crate::{event, AuditAction, Clawback, DocumentStatus, Error, ExtendedError, PetalDocuments};
+
+pub fn read_clawback_reason(e: &Env, token_id: u32) -> Option<String> {
+    let key = DataKey::ClawbackReason(token_id);
+    e.storage().persistent().get(&key)
+}
+
+// Hands the document to a custodian and stops any further signing. Only
+// documents that didn't complete can be clawed back, and any escrow goes back
+// to the depositor since the payee can no longer be owed it.
+pub fn clawback(e: &Env, admin: &Address, clawback: Clawback) {
+    let token_id = clawback.token_id;
+    if !PetalDocuments::require_minted(e, token_id) {
+        panic_with_error!(e, Error::TokenNotMinted)
+    }
+    if !matches!(
+        read_status(e, token_id),
+        DocumentStatus::Pending | DocumentStatus::Rejected | DocumentStatus::Expired
+    ) {
+        panic_with_error!(e, ExtendedError::DocumentNotClawbackable)
+    }
+
+    match read_token_contract(e) {
+        Some(token_contract) => {
+            token_contract::set_owner(e, &token_contract, token_id, &clawback.to);
+        }
+        None => write_owner(e, token_id, &clawback.to),
+    }
+    // The custodian holds the document alone.
+    ownership::clear_co_owners(e, token_id);
+
+    write_status(e, token_id, DocumentStatus::ClawedBack);
+    if read_escrow(e, token_id).is_some() {
+        escrow::refund(e, token_id);
+    }
+    let key = DataKey::ClawbackReason(token_id);
+    e.storage().persistent().set(&key, &clawback.reason);
+    audit::append(e, token_id, AuditAction::ClawedBack, admin);
+    event::clawback_document(e, token_id, clawback.to, clawback.reason);
+}
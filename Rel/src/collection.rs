@@ -0,0 +1,110 @@
+use soroban_sdk::{panic_with_error, Address, Env, String, Vec};
+
+use crate::storage_types::{CollectionItemKey, CollectionKey};
+use crate::{Collection, ExtendedError};
+
+// Groups of documents, e.g. everything belonging to one deal. A document is
+// in at most one collection; assigning it again moves it.
+pub fn create(e: &Env, owner: &Address, name: String) -> u32 {
+    let storage = e.storage().persistent();
+    let collection_id: u32 = storage.get(&CollectionKey::NextCollectionId).unwrap_or(1);
+    let collection = Collection {
+        owner: owner.clone(),
+        name,
+        size: 0,
+    };
+    storage.set(&CollectionKey::Collection(collection_id), &collection);
+    storage.set(&CollectionKey::NextCollectionId, &(collection_id + 1));
+    collection_id
+}
+
+pub fn read_collection(e: &Env, collection_id: u32) -> Option<Collection> {
+    let key = CollectionKey::Collection(collection_id);
+    e.storage().persistent().get(&key)
+}
+
+fn write_collection(e: &Env, collection_id: u32, collection: &Collection) {
+    let key = CollectionKey::Collection(collection_id);
+    e.storage().persistent().set(&key, collection);
+}
+
+pub fn read_document_collection(e: &Env, token_id: u32) -> Option<u32> {
+    let key = CollectionKey::DocCollection(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn assign(e: &Env, token_id: u32, collection_id: u32) {
+    let mut collection = match read_collection(e, collection_id) {
+        Some(collection) => collection,
+        None => panic_with_error!(e, ExtendedError::CollectionNotFound),
+    };
+    match read_document_collection(e, token_id) {
+        Some(current) if current == collection_id => return,
+        Some(current) => remove(e, token_id, current),
+        None => {}
+    }
+
+    let storage = e.storage().persistent();
+    let index = collection.size;
+    let item = CollectionKey::CollectionItem(CollectionItemKey {
+        collection_id,
+        index,
+    });
+    storage.set(&item, &token_id);
+    storage.set(&CollectionKey::CollectionSlot(token_id), &index);
+    storage.set(&CollectionKey::DocCollection(token_id), &collection_id);
+    collection.size += 1;
+    write_collection(e, collection_id, &collection);
+}
+
+// Swap-remove, as for the status index lists.
+fn remove(e: &Env, token_id: u32, collection_id: u32) {
+    let mut collection = match read_collection(e, collection_id) {
+        Some(collection) => collection,
+        None => return,
+    };
+    let storage = e.storage().persistent();
+    let index: u32 = match storage.get(&CollectionKey::CollectionSlot(token_id)) {
+        Some(index) => index,
+        None => return,
+    };
+    let last = collection.size - 1;
+    let last_key = CollectionKey::CollectionItem(CollectionItemKey {
+        collection_id,
+        index: last,
+    });
+    if index != last {
+        let moved: u32 = storage.get(&last_key).unwrap();
+        let key = CollectionKey::CollectionItem(CollectionItemKey {
+            collection_id,
+            index,
+        });
+        storage.set(&key, &moved);
+        storage.set(&CollectionKey::CollectionSlot(moved), &index);
+    }
+    storage.remove(&last_key);
+    storage.remove(&CollectionKey::CollectionSlot(token_id));
+    storage.remove(&CollectionKey::DocCollection(token_id));
+    collection.size = last;
+    write_collection(e, collection_id, &collection);
+}
+
+pub fn read_documents(e: &Env, collection_id: u32, page: u32, limit: u32) -> Vec<u32> {
+    let mut token_ids = Vec::new(e);
+    let size = match read_collection(e, collection_id) {
+        Some(collection) => collection.size,
+        None => return token_ids,
+    };
+    let start = page.saturating_mul(limit);
+    let end = size.min(start.saturating_add(limit));
+    for index in start..end {
+        let key = CollectionKey::CollectionItem(CollectionItemKey {
+            collection_id,
+            index,
+        });
+        if let Some(token_id) = e.storage().persistent().get(&key) {
+            token_ids.push_back(token_id);
+        }
+    }
+    token_ids
+}
@@ -0,0 +1,88 @@
+use soroban_sdk::{panic_with_error, token, Address, Env};
+
+use crate::fee::{add_collected, creation_fee_for, read_fee_token};
+use crate::storage_types::{AccessKey, DocKey};
+use crate::{event, stats, Error, ExtendedError};
+
+// A credit covers the creation fee of one mint, whatever the fee is when it
+// is used.
+pub fn read_credits(e: &Env, addr: &Address) -> u32 {
+    let key = AccessKey::Credits(addr.clone());
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+fn write_credits(e: &Env, addr: &Address, credits: u32) {
+    let key = AccessKey::Credits(addr.clone());
+    if credits == 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &credits);
+    }
+}
+
+fn add_credits(e: &Env, addr: &Address, credits: u32) {
+    match read_credits(e, addr).checked_add(credits) {
+        Some(total) => write_credits(e, addr, total),
+        None => panic_with_error!(e, ExtendedError::InvalidCreditAmount),
+    }
+}
+
+// Credits cost the buyer's creation fee each at the time of purchase. With
+// no fee to prepay they can't be bought, or they would cover later fees for
+// free.
+pub fn buy(e: &Env, buyer: &Address, credits: u32) -> i128 {
+    if credits == 0 {
+        panic_with_error!(e, ExtendedError::InvalidCreditAmount)
+    }
+    let price = creation_fee_for(e, buyer);
+    if price == 0 {
+        panic_with_error!(e, Error::InvalidFeeAmount)
+    }
+    let total = match price.checked_mul(credits as i128) {
+        Some(total) => total,
+        None => panic_with_error!(e, Error::InvalidFeeAmount),
+    };
+    let fee_token = match read_fee_token(e) {
+        Some(fee_token) => fee_token,
+        None => panic_with_error!(e, Error::FeeTokenNotSet),
+    };
+
+    token::Client::new(e, &fee_token).transfer(buyer, e.current_contract_address(), &total);
+    add_credits(e, buyer, credits);
+    add_collected(e, &fee_token, total);
+    stats::record_fee(e, total);
+    event::credits_added(e, buyer.clone(), credits, total);
+    total
+}
+
+pub fn grant(e: &Env, to: &Address, credits: u32) {
+    if credits == 0 {
+        panic_with_error!(e, ExtendedError::InvalidCreditAmount)
+    }
+    add_credits(e, to, credits);
+    event::credits_added(e, to.clone(), credits, 0);
+}
+
+// Uses one of the payer's credits for `token_id`, if they have any.
+pub fn consume(e: &Env, token_id: u32, payer: &Address) -> bool {
+    let credits = read_credits(e, payer);
+    if credits == 0 {
+        return false;
+    }
+    write_credits(e, payer, credits - 1);
+    let key = DocKey::PaidWithCredit(token_id);
+    e.storage().persistent().set(&key, &true);
+    event::credit_used(e, token_id, payer.clone());
+    true
+}
+
+// Gives the credit used for `token_id` back to `to`.
+pub fn restore(e: &Env, token_id: u32, to: &Address) -> bool {
+    let key = DocKey::PaidWithCredit(token_id);
+    if !e.storage().persistent().has(&key) {
+        return false;
+    }
+    e.storage().persistent().remove(&key);
+    add_credits(e, to, 1);
+    true
+}
@@ -0,0 +1,67 @@
+use soroban_sdk::{Address, Env, Map};
+
+use crate::document::{read_deadline, read_signings};
+use crate::storage_types::DataKey;
+use crate::witness::read_witnesses;
+use crate::{DeadlineKind, SignatureStatus};
+
+// Optional per-signer deadlines overriding the document deadline, e.g. a
+// shorter one for internal approvers than for the counterparty.
+pub fn read_signer_deadlines(e: &Env, token_id: u32) -> Map<Address, u64> {
+    let key = DataKey::SignerDeadlines(token_id);
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Map::new(e))
+}
+
+pub fn write_signer_deadlines(e: &Env, token_id: u32, deadlines: &Map<Address, u64>) {
+    let key = DataKey::SignerDeadlines(token_id);
+    e.storage().persistent().set(&key, deadlines);
+}
+
+pub fn applicable_deadline(
+    e: &Env,
+    token_id: u32,
+    signer: &Address,
+) -> Option<(u64, DeadlineKind)> {
+    match read_signer_deadlines(e, token_id).get(signer.clone()) {
+        Some(deadline) => Some((deadline, DeadlineKind::Signer)),
+        None => read_deadline(e, token_id).map(|deadline| (deadline, DeadlineKind::Document)),
+    }
+}
+
+// The document can't complete once any party still waiting has missed
+// their deadline, so the earliest of those decides when it expires.
+// Witnesses don't hold up completion, so their deadlines don't count.
+pub fn earliest_open_deadline(e: &Env, token_id: u32) -> Option<(u64, DeadlineKind)> {
+    let signer_deadlines = read_signer_deadlines(e, token_id);
+    let document_deadline = read_deadline(e, token_id);
+    if signer_deadlines.is_empty() {
+        return document_deadline.map(|deadline| (deadline, DeadlineKind::Document));
+    }
+
+    let mut earliest: Option<(u64, DeadlineKind)> = None;
+    let signings = read_signings(e, token_id).unwrap_or_else(|| Map::new(e));
+    let witnesses = read_witnesses(e, token_id);
+    for (signer, status) in signings.iter() {
+        if status != SignatureStatus::Waiting || witnesses.contains(&signer) {
+            continue;
+        }
+        let applicable = match signer_deadlines.get(signer) {
+            Some(deadline) => (deadline, DeadlineKind::Signer),
+            None => match document_deadline {
+                Some(deadline) => (deadline, DeadlineKind::Document),
+                None => continue,
+            },
+        };
+        let is_earlier = match &earliest {
+            Some((deadline, _)) => applicable.0 < *deadline,
+            None => true,
+        };
+        if is_earlier {
+            earliest = Some(applicable);
+        }
+    }
+    earliest
+}
@@ -0,0 +1,64 @@
+use soroban_sdk::{Address, Env};
+
+use crate::admin::is_administrator;
+use crate::storage_types::{ConfigKey, DocKey};
+use crate::{Arbitration, Dispute};
+
+// The open dispute on a document, if any. Resolving it removes the entry;
+// the outcome stays in the audit log.
+pub fn read_dispute(e: &Env, token_id: u32) -> Option<Dispute> {
+    let key = DocKey::Dispute(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_dispute(e: &Env, token_id: u32, dispute: &Dispute) {
+    let key = DocKey::Dispute(token_id);
+    e.storage().persistent().set(&key, dispute);
+}
+
+pub fn remove_dispute(e: &Env, token_id: u32) {
+    let key = DocKey::Dispute(token_id);
+    e.storage().persistent().remove(&key);
+}
+
+pub fn read_arbitration(e: &Env, token_id: u32) -> Option<Arbitration> {
+    let key = DocKey::Arbitration(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_arbitration(e: &Env, token_id: u32, arbitration: &Arbitration) {
+    let key = DocKey::Arbitration(token_id);
+    e.storage().persistent().set(&key, arbitration);
+}
+
+pub fn read_document_arbiter(e: &Env, token_id: u32) -> Option<Address> {
+    let key = DocKey::Arbiter(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_document_arbiter(e: &Env, token_id: u32, arbiter: &Address) {
+    let key = DocKey::Arbiter(token_id);
+    e.storage().persistent().set(&key, arbiter);
+}
+
+// A document's own arbiter replaces the admin and the contract arbiter.
+pub fn may_resolve(e: &Env, token_id: u32, caller: &Address) -> bool {
+    match read_document_arbiter(e, token_id) {
+        Some(arbiter) => *caller == arbiter,
+        None => is_administrator(e, caller) || Some(caller.clone()) == read_arbiter(e),
+    }
+}
+
+// Resolves disputes alongside the admin.
+pub fn read_arbiter(e: &Env) -> Option<Address> {
+    let key = ConfigKey::Arbiter;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_arbiter(e: &Env, arbiter: Option<Address>) {
+    let key = ConfigKey::Arbiter;
+    match arbiter {
+        Some(arbiter) => e.storage().instance().set(&key, &arbiter),
+        None => e.storage().instance().remove(&key),
+    }
+}
@@ -0,0 +1,232 @@
+use soroban_sdk::{
+    panic_with_error, Address, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
+};
+
+use petal_common::OwnerStore;
+
+use crate::migration::read_migration_cursor;
+use crate::storage_types::{
+    DataKey, DocKey, DEADLINES, DOCSIGN, MAX_EXTRA_HASHES, OWNERS, T2DHASH, URIS,
+};
+use crate::{DocumentHash, ExtendedError, HashAlgo, SignatureStatus};
+
+// Documents minted before per-token keys were introduced stay in the legacy
+// maps until `migrate` has moved them, so every read falls back to those.
+pub(crate) fn read_legacy<V>(e: &Env, map: &Symbol, token_id: u32) -> Option<V>
+where
+    V: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    e.storage()
+        .persistent()
+        .get::<Symbol, Map<u32, V>>(map)
+        .and_then(|legacy| legacy.get(token_id))
+}
+
+fn read_or_legacy<V>(e: &Env, key: DataKey, map: &Symbol, token_id: u32) -> Option<V>
+where
+    V: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    match e.storage().persistent().get(&key) {
+        Some(value) => Some(value),
+        None => read_legacy(e, map, token_id),
+    }
+}
+
+pub fn read_owner(e: &Env, token_id: u32) -> Option<Address> {
+    match OwnerStore::new(e).get(token_id) {
+        Some(owner) => Some(owner),
+        None => read_legacy(e, &OWNERS, token_id),
+    }
+}
+
+pub fn write_owner(e: &Env, token_id: u32, owner: &Address) {
+    OwnerStore::new(e).set(token_id, owner);
+}
+
+pub fn read_creator(e: &Env, token_id: u32) -> Option<Address> {
+    let key = DataKey::Creator(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_creator(e: &Env, token_id: u32, creator: &Address) {
+    let key = DataKey::Creator(token_id);
+    e.storage().persistent().set(&key, creator);
+}
+
+pub fn read_uri(e: &Env, token_id: u32) -> Option<String> {
+    read_or_legacy(e, DataKey::Uri(token_id), &URIS, token_id)
+}
+
+pub fn write_uri(e: &Env, token_id: u32, uri: &String) {
+    let key = DataKey::Uri(token_id);
+    e.storage().persistent().set(&key, uri);
+}
+
+pub fn read_document_hash(e: &Env, token_id: u32) -> Option<String> {
+    read_or_legacy(e, DataKey::DocumentHash(token_id), &T2DHASH, token_id)
+}
+
+pub fn read_hash_algo(e: &Env, token_id: u32) -> HashAlgo {
+    let key = DocKey::HashAlgo(token_id);
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(HashAlgo::Unspecified)
+}
+
+pub fn write_hash_algo(e: &Env, token_id: u32, algo: HashAlgo) {
+    let key = DocKey::HashAlgo(token_id);
+    if algo == HashAlgo::Unspecified {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &algo);
+    }
+}
+
+// Also keeps the hash -> token index in step, dropping the entry for a hash
+// the token is moving away from.
+pub fn write_document_hash(e: &Env, token_id: u32, document_hash: &String) {
+    if let Some(previous) = read_document_hash(e, token_id) {
+        if previous != *document_hash && read_token_by_hash(e, &previous) == Some(token_id) {
+            let key = DataKey::TokenByHash(previous);
+            e.storage().persistent().remove(&key);
+        }
+    }
+    let key = DataKey::DocumentHash(token_id);
+    e.storage().persistent().set(&key, document_hash);
+    let key = DataKey::TokenByHash(document_hash.clone());
+    e.storage().persistent().set(&key, &token_id);
+}
+
+pub fn read_extra_hashes(e: &Env, token_id: u32) -> Vec<DocumentHash> {
+    let key = DocKey::ExtraHashes(token_id);
+    e.storage().persistent().get(&key).unwrap_or(Vec::new(e))
+}
+
+// Extra hashes are indexed like the minted one, so `get_token_by_hash` finds
+// the document through any of them.
+pub fn add_extra_hash(e: &Env, token_id: u32, entry: &DocumentHash) -> Result<(), ExtendedError> {
+    let mut hashes = read_extra_hashes(e, token_id);
+    if read_document_hash(e, token_id).as_ref() == Some(&entry.hash)
+        || hashes.iter().any(|existing| existing.hash == entry.hash)
+    {
+        return Err(ExtendedError::DuplicateDocumentHash);
+    }
+    if hashes.len() >= MAX_EXTRA_HASHES {
+        return Err(ExtendedError::TooManyHashes);
+    }
+    hashes.push_back(entry.clone());
+    e.storage()
+        .persistent()
+        .set(&DocKey::ExtraHashes(token_id), &hashes);
+    let key = DataKey::TokenByHash(entry.hash.clone());
+    e.storage().persistent().set(&key, &token_id);
+    Ok(())
+}
+
+// Whether `document_hash` is the minted hash or one of the extra ones.
+pub fn has_document_hash(e: &Env, token_id: u32, document_hash: &String) -> bool {
+    read_document_hash(e, token_id).as_ref() == Some(document_hash)
+        || read_extra_hashes(e, token_id)
+            .iter()
+            .any(|entry| entry.hash == *document_hash)
+}
+
+pub fn read_token_by_hash(e: &Env, document_hash: &String) -> Option<u32> {
+    let key = DataKey::TokenByHash(document_hash.clone());
+    if let Some(token_id) = e.storage().persistent().get(&key) {
+        return Some(token_id);
+    }
+    let legacy: Option<Map<u32, String>> = e.storage().persistent().get(&T2DHASH);
+    legacy.and_then(|hashes| {
+        hashes
+            .iter()
+            .find(|(_, hash)| hash == document_hash)
+            .map(|(token_id, _)| token_id)
+    })
+}
+
+// Off by default; when on, a hash can only be minted under one token.
+pub fn read_unique_hashes(e: &Env) -> bool {
+    let key = DataKey::UniqueHashes;
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+pub fn write_unique_hashes(e: &Env, enabled: bool) {
+    let key = DataKey::UniqueHashes;
+    e.storage().instance().set(&key, &enabled);
+}
+
+pub fn is_duplicate_hash(e: &Env, token_id: u32, document_hash: &String) -> bool {
+    match read_token_by_hash(e, document_hash) {
+        Some(existing) => existing != token_id,
+        None => false,
+    }
+}
+
+pub fn check_unique_hash(e: &Env, token_id: u32, document_hash: &String) {
+    if read_unique_hashes(e) && is_duplicate_hash(e, token_id, document_hash) {
+        panic_with_error!(e, ExtendedError::DuplicateDocumentHash)
+    }
+}
+
+pub fn read_deadline(e: &Env, token_id: u32) -> Option<u64> {
+    read_or_legacy(e, DataKey::Deadline(token_id), &DEADLINES, token_id)
+}
+
+pub fn write_deadline(e: &Env, token_id: u32, deadline: u64) {
+    let key = DataKey::Deadline(token_id);
+    e.storage().persistent().set(&key, &deadline);
+}
+
+pub fn read_signings(e: &Env, token_id: u32) -> Option<Map<Address, SignatureStatus>> {
+    read_or_legacy(e, DataKey::Signings(token_id), &DOCSIGN, token_id)
+}
+
+pub fn write_signings(e: &Env, token_id: u32, signings: &Map<Address, SignatureStatus>) {
+    let key = DataKey::Signings(token_id);
+    e.storage().persistent().set(&key, signings);
+}
+
+// Every document gets an index entry so the map-returning getters can still
+// list all tokens without a monolithic map.
+pub fn add_to_index(e: &Env, token_id: u32) {
+    let count = read_token_count(e);
+    e.storage()
+        .persistent()
+        .set(&DataKey::TokenByIndex(count), &token_id);
+    e.storage()
+        .instance()
+        .set(&DataKey::TokenCount, &(count + 1));
+}
+
+pub fn read_token_count(e: &Env) -> u32 {
+    let key = DataKey::TokenCount;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+// Indexed tokens first, then the legacy ones `migrate` has not reached yet.
+pub fn read_token_at(e: &Env, index: u32) -> Option<u32> {
+    let key = DataKey::TokenByIndex(index);
+    e.storage().persistent().get(&key)
+}
+
+pub fn read_token_ids(e: &Env) -> Vec<u32> {
+    let mut token_ids = Vec::new(e);
+    for index in 0..read_token_count(e) {
+        let key = DataKey::TokenByIndex(index);
+        if let Some(token_id) = e.storage().persistent().get(&key) {
+            token_ids.push_back(token_id);
+        }
+    }
+
+    let legacy: Option<Map<u32, Map<Address, SignatureStatus>>> =
+        e.storage().persistent().get(&DOCSIGN);
+    if let Some(legacy) = legacy {
+        let legacy_ids = legacy.keys();
+        for index in read_migration_cursor(e)..legacy_ids.len() {
+            token_ids.push_back(legacy_ids.get_unchecked(index));
+        }
+    }
+    token_ids
+}
@@ -0,0 +1,81 @@
+use soroban_sdk::{panic_with_error, token, Address, Env};
+
+use crate::storage_types::{DataKey, DocKey};
+use crate::{event, Error, Escrow, EscrowDeposit, ExtendedError};
+
+pub fn read_escrow(e: &Env, token_id: u32) -> Option<Escrow> {
+    let key = DataKey::Escrow(token_id);
+    e.storage().persistent().get(&key)
+}
+
+fn remove_escrow(e: &Env, token_id: u32) {
+    let key = DataKey::Escrow(token_id);
+    e.storage().persistent().remove(&key);
+}
+
+pub fn deposit(e: &Env, token_id: u32, depositor: &Address, terms: EscrowDeposit) {
+    if terms.amount <= 0 {
+        panic_with_error!(e, Error::InvalidEscrowAmount)
+    }
+
+    token::Client::new(e, &terms.token).transfer(
+        depositor,
+        e.current_contract_address(),
+        &terms.amount,
+    );
+
+    let escrow = Escrow {
+        depositor: depositor.clone(),
+        token: terms.token,
+        amount: terms.amount,
+        payee: terms.payee,
+    };
+    let key = DataKey::Escrow(token_id);
+    e.storage().persistent().set(&key, &escrow);
+
+    event::escrow_deposit(e, token_id, escrow);
+}
+
+pub fn release(e: &Env, token_id: u32) -> Escrow {
+    let escrow = match read_escrow(e, token_id) {
+        Some(escrow) => escrow,
+        None => panic_with_error!(e, Error::EscrowNotFound),
+    };
+    remove_escrow(e, token_id);
+
+    token::Client::new(e, &escrow.token).transfer(
+        &e.current_contract_address(),
+        &escrow.payee,
+        &escrow.amount,
+    );
+    event::escrow_claim(e, token_id, escrow.payee.clone(), escrow.amount);
+    escrow
+}
+
+pub fn refund(e: &Env, token_id: u32) -> Escrow {
+    let escrow = match read_escrow(e, token_id) {
+        Some(escrow) => escrow,
+        None => panic_with_error!(e, Error::EscrowNotFound),
+    };
+    remove_escrow(e, token_id);
+    let key = DocKey::EscrowRefunded(token_id);
+    e.storage().persistent().set(&key, &true);
+
+    token::Client::new(e, &escrow.token).transfer(
+        &e.current_contract_address(),
+        &escrow.depositor,
+        &escrow.amount,
+    );
+    event::escrow_refund(e, token_id, escrow.depositor.clone(), escrow.amount);
+    escrow
+}
+
+// Once the escrow went back to the depositor, the document can't take
+// another round of signing: completing it would leave the payee unpaid.
+pub fn check_not_refunded(e: &Env, token_id: u32) -> Result<(), ExtendedError> {
+    let key = DocKey::EscrowRefunded(token_id);
+    if e.storage().persistent().has(&key) {
+        return Err(ExtendedError::EscrowRefunded);
+    }
+    Ok(())
+}
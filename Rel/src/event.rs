@@ -1,36 +1,609 @@
-use soroban_sdk::{symbol_short, Address, Env, Symbol, U256};
+// `publish` is deprecated in favour of `#[contractevent]`, but the macro
+// derives its own topic layout and indexers already decode the one below.
+#![allow(deprecated)]
 
-pub(crate) fn approve(e: &Env, from: Address, to: Address, amount: i128, expiration_ledger: u32) {
-    let topics = (Symbol::new(e, "approve"), from, to);
-    e.events().publish(topics, (amount, expiration_ledger));
+use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec, U256};
+
+use crate::{
+    ArbitrationOutcome, BountyAction, Callback, Certificate, ContractStats, DeadlineKind,
+    DocumentHash, DocumentOp, Escrow, OrgRole, SignatureStatus,
+};
+
+// Bumped whenever a topic layout or data struct below changes. It is always
+// the last topic, so indexers can pick the right decoder before reading the
+// payload. Every document event has the token id as its second topic, so
+// RPC filters can follow a single document.
+pub const EVENT_SCHEMA_VERSION: u32 = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct MintData {
+    pub to: Address,
+    pub token_id: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct AmountData {
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct CreditsData {
+    pub credits: u32,
+    pub paid: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SetFeeTokenData {
+    pub fee_token: Address,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct FeeData {
+    pub fee_token: Address,
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct CompletedData {
+    pub completed_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct RejectedData {
+    pub signer: Address,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ExpiredData {
+    pub deadline: u64,
+    pub kind: DeadlineKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignedLateData {
+    pub deadline: u64,
+    pub kind: DeadlineKind,
+    pub signed_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct RerequestedData {
+    pub creator: Address,
+    pub count: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ReopenedData {
+    pub new_deadline: u64,
+    pub reset_rejected: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct CollectionData {
+    pub collection_id: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DisputeData {
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ReminderData {
+    pub waiting_signers: Vec<Address>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ActorData {
+    pub actor: Address,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ExecutedData {
+    pub proposal_id: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ClawbackData {
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct PollCreatedData {
+    pub end_time: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct CallbackData {
+    pub function: Symbol,
+    pub success: bool,
 }
 
-pub(crate) fn transfer(e: &Env, from: Address, to: Address, amount: i128) {
-    let topics = (symbol_short!("transfer"), from, to);
-    e.events().publish(topics, amount);
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct VoteData {
+    pub support: bool,
+    pub weight: i128,
 }
 
 pub(crate) fn mint(e: &Env, to: &Address, token_id: u32) {
-    let topics = (symbol_short!("mint"), token_id);
-    e.events().publish(topics, token_id);
+    let topics = (symbol_short!("mint"), token_id, EVENT_SCHEMA_VERSION);
+    let data = MintData {
+        to: to.clone(),
+        token_id,
+    };
+    e.events().publish(topics, data);
+}
+
+pub(crate) fn fee_paid(e: &Env, token_id: u32, payer: Address, fee_token: Address, amount: i128) {
+    let topics = (
+        symbol_short!("fee_paid"),
+        token_id,
+        payer,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, FeeData { fee_token, amount });
+}
+
+pub(crate) fn fee_refunded(e: &Env, token_id: u32, to: Address, fee_token: Address, amount: i128) {
+    let topics = (
+        symbol_short!("fee_rfnd"),
+        token_id,
+        to,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, FeeData { fee_token, amount });
+}
+
+pub(crate) fn cancelled(e: &Env, token_id: u32, creator: Address) {
+    let topics = (symbol_short!("cancel"), token_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, ActorData { actor: creator });
+}
+
+pub(crate) fn bounty_paid(e: &Env, action: BountyAction, keeper: Address, amount: i128) {
+    let topics = (
+        symbol_short!("bounty"),
+        action,
+        keeper,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, AmountData { amount });
+}
+
+pub(crate) fn referral_credited(e: &Env, token_id: u32, referrer: Address, amount: i128) {
+    let topics = (
+        symbol_short!("referral"),
+        token_id,
+        referrer,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, AmountData { amount });
+}
+
+pub(crate) fn referral_claimed(e: &Env, referrer: Address, amount: i128) {
+    let topics = (symbol_short!("ref_claim"), referrer, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, AmountData { amount });
+}
+
+// `paid` is zero for credits granted by the admin.
+pub(crate) fn credits_added(e: &Env, to: Address, credits: u32, paid: i128) {
+    let topics = (symbol_short!("credits"), to, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, CreditsData { credits, paid });
+}
+
+pub(crate) fn credit_used(e: &Env, token_id: u32, payer: Address) {
+    let topics = (
+        Symbol::new(e, "credit_used"),
+        token_id,
+        payer,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn set_fee_token(e: &Env, admin: Address, fee_token: Address) {
+    let topics = (Symbol::new(e, "set_fee_token"), admin, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, SetFeeTokenData { fee_token });
+}
+
+pub(crate) fn minter_added(e: &Env, admin: Address, minter: Address) {
+    let topics = (Symbol::new(e, "minter_added"), admin, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, ActorData { actor: minter });
+}
+
+pub(crate) fn minter_removed(e: &Env, admin: Address, minter: Address) {
+    let topics = (
+        Symbol::new(e, "minter_removed"),
+        admin,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ActorData { actor: minter });
+}
+
+pub(crate) fn renounce_admin(e: &Env, admin: Address) {
+    let topics = (
+        Symbol::new(e, "renounce_admin"),
+        admin,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn renounce_requested(e: &Env, admin: Address) {
+    let topics = (
+        Symbol::new(e, "renounce_requested"),
+        admin,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn renounce_cancelled(e: &Env, admin: Address) {
+    let topics = (
+        Symbol::new(e, "renounce_cancelled"),
+        admin,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn set_minter_allowlist(e: &Env, admin: Address, enabled: bool) {
+    let topics = (
+        Symbol::new(e, "set_minter_allowlist"),
+        admin,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, enabled);
+}
+
+pub(crate) fn set_creation_fee(e: &Env, admin: Address, fee: i128) {
+    let topics = (
+        Symbol::new(e, "set_creation_fee"),
+        admin,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, AmountData { amount: fee });
+}
+
+pub(crate) fn completed(e: &Env, token_id: u32) {
+    let topics = (symbol_short!("complete"), token_id, EVENT_SCHEMA_VERSION);
+    let data = CompletedData {
+        completed_at: e.ledger().timestamp(),
+    };
+    e.events().publish(topics, data);
+}
+
+pub(crate) fn signed_late(
+    e: &Env,
+    token_id: u32,
+    signer: Address,
+    deadline: u64,
+    kind: DeadlineKind,
+) {
+    let topics = (
+        symbol_short!("late_sign"),
+        token_id,
+        signer,
+        EVENT_SCHEMA_VERSION,
+    );
+    let data = SignedLateData {
+        deadline,
+        kind,
+        signed_at: e.ledger().timestamp(),
+    };
+    e.events().publish(topics, data);
+}
+
+pub(crate) fn signed(e: &Env, token_id: u32, signer: Address) {
+    let topics = (
+        symbol_short!("sign"),
+        token_id,
+        signer,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn rejected(e: &Env, token_id: u32, signer: Address) {
+    let topics = (symbol_short!("reject"), token_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, RejectedData { signer });
+}
+
+pub(crate) fn expired(e: &Env, token_id: u32, deadline: u64, kind: DeadlineKind) {
+    let topics = (symbol_short!("expire"), token_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, ExpiredData { deadline, kind });
+}
+
+pub(crate) fn reopened(
+    e: &Env,
+    token_id: u32,
+    actor: Address,
+    new_deadline: u64,
+    reset_rejected: bool,
+) {
+    let topics = (
+        symbol_short!("reopen"),
+        token_id,
+        actor,
+        EVENT_SCHEMA_VERSION,
+    );
+    let data = ReopenedData {
+        new_deadline,
+        reset_rejected,
+    };
+    e.events().publish(topics, data);
+}
+
+pub(crate) fn rerequested(e: &Env, token_id: u32, signer: Address, creator: Address, count: u32) {
+    let topics = (
+        symbol_short!("rerequest"),
+        token_id,
+        signer,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events()
+        .publish(topics, RerequestedData { creator, count });
+}
+
+// `status` is what the witness recorded: signed, signed late or rejected.
+pub(crate) fn witnessed(e: &Env, token_id: u32, witness: Address, status: SignatureStatus) {
+    let topics = (
+        symbol_short!("witnessed"),
+        token_id,
+        witness,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, status);
+}
+
+pub(crate) fn revoked(e: &Env, token_id: u32, signer: Address) {
+    let topics = (
+        symbol_short!("revoked"),
+        token_id,
+        signer,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn updated(e: &Env, token_id: u32, actor: Address, op: DocumentOp) {
+    let topics = (
+        symbol_short!("updated"),
+        token_id,
+        actor,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, op);
+}
+
+pub(crate) fn view_granted(e: &Env, token_id: u32, grantee: Address, expires_at: u64) {
+    let topics = (
+        symbol_short!("view_gnt"),
+        token_id,
+        grantee,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, expires_at);
+}
+
+pub(crate) fn view_revoked(e: &Env, token_id: u32, grantee: Address, actor: Address) {
+    let topics = (
+        symbol_short!("view_rvk"),
+        token_id,
+        grantee,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ActorData { actor });
+}
+
+pub(crate) fn disputed(e: &Env, token_id: u32, raised_by: Address, reason: String) {
+    let topics = (
+        symbol_short!("dispute"),
+        token_id,
+        raised_by,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, DisputeData { reason });
+}
+
+pub(crate) fn dispute_resolved(
+    e: &Env,
+    token_id: u32,
+    resolver: Address,
+    outcome: ArbitrationOutcome,
+) {
+    let topics = (
+        symbol_short!("resolved"),
+        token_id,
+        resolver,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, outcome);
+}
+
+pub(crate) fn linked(e: &Env, parent_id: u32, child_id: u32, relation: Symbol) {
+    let topics = (
+        symbol_short!("linked"),
+        parent_id,
+        child_id,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, relation);
+}
+
+pub(crate) fn sign_note(e: &Env, token_id: u32, signer: Address, note: String) {
+    let topics = (
+        symbol_short!("sign_note"),
+        token_id,
+        signer,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, note);
+}
+
+pub(crate) fn hash_added(e: &Env, token_id: u32, actor: Address, entry: DocumentHash) {
+    let topics = (
+        symbol_short!("hash_add"),
+        token_id,
+        actor,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, entry);
+}
+
+pub(crate) fn attribute_set(e: &Env, token_id: u32, name: Symbol, value: Option<String>) {
+    let topics = (
+        symbol_short!("attr_set"),
+        token_id,
+        name,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, value);
+}
+
+pub(crate) fn collection_created(e: &Env, collection_id: u32, owner: Address) {
+    let topics = (
+        Symbol::new(e, "collection"),
+        collection_id,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ActorData { actor: owner });
+}
+
+pub(crate) fn org_created(e: &Env, org_id: u32, creator: Address) {
+    let topics = (symbol_short!("org"), org_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, ActorData { actor: creator });
+}
+
+pub(crate) fn org_member_set(e: &Env, org_id: u32, member: Address, role: OrgRole) {
+    let topics = (
+        Symbol::new(e, "org_member_set"),
+        org_id,
+        member,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, role);
+}
+
+pub(crate) fn org_member_removed(e: &Env, org_id: u32, member: Address) {
+    let topics = (
+        Symbol::new(e, "org_member_removed"),
+        org_id,
+        member,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ());
+}
+
+pub(crate) fn assigned_to_collection(e: &Env, token_id: u32, collection_id: u32) {
+    let topics = (symbol_short!("assigned"), token_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, CollectionData { collection_id });
+}
+
+pub(crate) fn metrics(e: &Env, stats: ContractStats) {
+    let topics = (symbol_short!("metrics"), EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, stats);
+}
+
+pub(crate) fn escrow_deposit(e: &Env, token_id: u32, escrow: Escrow) {
+    let topics = (symbol_short!("esc_dep"), token_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, escrow);
+}
+
+pub(crate) fn escrow_claim(e: &Env, token_id: u32, payee: Address, amount: i128) {
+    let topics = (
+        symbol_short!("esc_claim"),
+        token_id,
+        payee,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, AmountData { amount });
+}
+
+pub(crate) fn escrow_refund(e: &Env, token_id: u32, depositor: Address, amount: i128) {
+    let topics = (
+        symbol_short!("esc_rfnd"),
+        token_id,
+        depositor,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, AmountData { amount });
+}
+
+pub(crate) fn reminder(e: &Env, token_id: u32, waiting_signers: Vec<Address>) {
+    let topics = (symbol_short!("reminder"), token_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, ReminderData { waiting_signers });
+}
+
+pub(crate) fn proposed(e: &Env, proposal_id: u32, proposer: Address) {
+    let topics = (symbol_short!("proposed"), proposal_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, ActorData { actor: proposer });
+}
+
+pub(crate) fn approved(e: &Env, proposal_id: u32, admin: Address) {
+    let topics = (symbol_short!("approved"), proposal_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, ActorData { actor: admin });
+}
+
+pub(crate) fn executed(e: &Env, proposal_id: u32) {
+    let topics = (symbol_short!("executed"), proposal_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, ExecutedData { proposal_id });
+}
+
+pub(crate) fn clawback_document(e: &Env, token_id: u32, custodian: Address, reason: String) {
+    let topics = (
+        Symbol::new(e, "clawback_document"),
+        token_id,
+        custodian,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ClawbackData { reason });
 }
 
-pub(crate) fn clawback(e: &Env, admin: Address, from: Address, amount: i128) {
-    let topics = (symbol_short!("clawback"), admin, from);
-    e.events().publish(topics, amount);
+pub(crate) fn poll_created(e: &Env, token_id: u32, end_time: u64) {
+    let topics = (symbol_short!("poll"), token_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, PollCreatedData { end_time });
 }
 
-pub(crate) fn set_authorized(e: &Env, admin: Address, id: Address, authorize: bool) {
-    let topics = (Symbol::new(e, "set_authorized"), admin, id);
-    e.events().publish(topics, authorize);
+pub(crate) fn voted(e: &Env, token_id: u32, voter: Address, support: bool, weight: i128) {
+    let topics = (symbol_short!("vote"), token_id, voter, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, VoteData { support, weight });
 }
 
-pub(crate) fn set_admin(e: &Env, admin: Address, new_admin: Address) {
-    let topics = (symbol_short!("set_admin"), admin);
-    e.events().publish(topics, new_admin);
+pub(crate) fn callback(e: &Env, token_id: u32, callback: Callback, success: bool) {
+    let topics = (
+        symbol_short!("callback"),
+        token_id,
+        callback.contract,
+        EVENT_SCHEMA_VERSION,
+    );
+    let data = CallbackData {
+        function: callback.function,
+        success,
+    };
+    e.events().publish(topics, data);
 }
 
-pub(crate) fn burn(e: &Env, from: Address, amount: i128) {
-    let topics = (symbol_short!("burn"), from);
-    e.events().publish(topics, amount);
+pub(crate) fn archived(e: &Env, token_id: u32, snapshot: Certificate) {
+    let topics = (symbol_short!("archived"), token_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, snapshot);
 }
@@ -0,0 +1,276 @@
+use soroban_sdk::{panic_with_error, token, vec, Address, Bytes, Env, IntoVal, Map, Symbol, Vec};
+
+use crate::admin::is_administrator;
+use crate::storage_types::{AccessKey, ConfigKey, DataKey, CREATION_FEE};
+use crate::token_contract::read_token_contract;
+use crate::{credit, event, referral, stats, Error, ExtendedError, FeeDiscount};
+
+pub fn read_fee_token(e: &Env) -> Option<Address> {
+    let key = DataKey::FeeToken;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_fee_token(e: &Env, fee_token: &Address) {
+    // Anything implementing the token interface will answer `decimals`.
+    let decimals = e.try_invoke_contract::<u32, soroban_sdk::Error>(
+        fee_token,
+        &Symbol::new(e, "decimals"),
+        vec![e],
+    );
+    if !matches!(decimals, Ok(Ok(_))) {
+        panic_with_error!(e, Error::InvalidFeeToken)
+    }
+
+    let key = DataKey::FeeToken;
+    e.storage().instance().set(&key, fee_token);
+}
+
+// Native amounts have 7 decimals: one XLM is 10^7 stroops.
+pub(crate) const STROOPS_PER_XLM: i128 = 10_000_000;
+
+pub(crate) const MAX_BPS: u32 = 10_000;
+
+// The native asset contract's address derives from the XDR of
+// `Asset::Native`, a zero discriminant, so no configuration is needed to find
+// it on any network.
+pub fn native_fee_token(e: &Env) -> Address {
+    let native_asset = Bytes::from_array(e, &[0; 4]);
+    e.deployer()
+        .with_stellar_asset(native_asset)
+        .deployed_address()
+}
+
+pub fn is_native_fee_token(e: &Env) -> bool {
+    read_fee_token(e) == Some(native_fee_token(e))
+}
+
+pub fn xlm_to_stroops(xlm: i128) -> Result<i128, Error> {
+    if xlm < 0 {
+        return Err(Error::InvalidFeeAmount);
+    }
+    xlm.checked_mul(STROOPS_PER_XLM)
+        .ok_or(Error::InvalidFeeAmount)
+}
+
+// Whole XLM fees only make sense once the fee token is the native one.
+pub fn check_native(e: &Env) -> Result<(), ExtendedError> {
+    if !is_native_fee_token(e) {
+        return Err(ExtendedError::FeeTokenNotNative);
+    }
+    Ok(())
+}
+
+// What the contract holds of `fee_token` as fees proper, as opposed to
+// escrow, bounty funds or referral shares kept in the same token.
+pub fn read_collected(e: &Env, fee_token: &Address) -> i128 {
+    let key = ConfigKey::CollectedFees(fee_token.clone());
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+// `amount` is negative for fees paid back out.
+pub fn add_collected(e: &Env, fee_token: &Address, amount: i128) {
+    let key = ConfigKey::CollectedFees(fee_token.clone());
+    let collected = read_collected(e, fee_token) + amount;
+    e.storage().instance().set(&key, &collected);
+}
+
+pub fn read_creation_fee(e: &Env) -> i128 {
+    let key = ConfigKey::CreationFee;
+    let instance = e.storage().instance();
+    instance
+        .get(&key)
+        .or_else(|| instance.get(&CREATION_FEE))
+        .unwrap_or(0)
+}
+
+pub fn write_creation_fee(e: &Env, fee: i128) {
+    if fee < 0 {
+        panic_with_error!(e, Error::InvalidFeeAmount)
+    }
+    let key = ConfigKey::CreationFee;
+    e.storage().instance().set(&key, &fee);
+    e.storage().instance().remove(&CREATION_FEE);
+}
+
+pub fn read_fee_schedule(e: &Env) -> Map<u32, i128> {
+    let key = ConfigKey::FeeSchedule;
+    e.storage().instance().get(&key).unwrap_or(Map::new(e))
+}
+
+pub fn write_fee_schedule(e: &Env, schedule: &Map<u32, i128>) {
+    if schedule.values().iter().any(|fee| fee < 0) {
+        panic_with_error!(e, Error::InvalidFeeAmount)
+    }
+    let key = ConfigKey::FeeSchedule;
+    e.storage().instance().set(&key, schedule);
+}
+
+pub fn read_fee_tier(e: &Env, addr: &Address) -> Option<u32> {
+    let key = AccessKey::FeeTier(addr.clone());
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_fee_tier(e: &Env, addr: &Address, tier: Option<u32>) {
+    let key = AccessKey::FeeTier(addr.clone());
+    match tier {
+        Some(tier) => e.storage().persistent().set(&key, &tier),
+        None => e.storage().persistent().remove(&key),
+    }
+}
+
+pub fn read_discount_token(e: &Env) -> Option<Address> {
+    let key = ConfigKey::DiscountToken;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_discount_token(e: &Env, discount_token: &Address) {
+    let key = ConfigKey::DiscountToken;
+    e.storage().instance().set(&key, discount_token);
+}
+
+pub fn read_fee_discounts(e: &Env) -> Vec<FeeDiscount> {
+    let key = ConfigKey::FeeDiscounts;
+    e.storage()
+        .instance()
+        .get(&key)
+        .unwrap_or_else(|| Vec::new(e))
+}
+
+pub fn check_fee_discounts(discounts: &Vec<FeeDiscount>) -> Result<(), ExtendedError> {
+    for discount in discounts.iter() {
+        if discount.min_balance <= 0 || discount.discount_bps > MAX_BPS {
+            return Err(ExtendedError::InvalidDiscount);
+        }
+    }
+    Ok(())
+}
+
+pub fn write_fee_discounts(e: &Env, discounts: &Vec<FeeDiscount>) {
+    let key = ConfigKey::FeeDiscounts;
+    e.storage().instance().set(&key, discounts);
+}
+
+// Falls back to the workspace token contract like polls do. Holders only
+// lose their discount, never the mint, if no token is set or its `balance`
+// call fails.
+fn holder_discount_bps(e: &Env, payer: &Address) -> u32 {
+    let discounts = read_fee_discounts(e);
+    if discounts.is_empty() {
+        return 0;
+    }
+    let discount_token = match read_discount_token(e).or_else(|| read_token_contract(e)) {
+        Some(discount_token) => discount_token,
+        None => return 0,
+    };
+    let balance = match e.try_invoke_contract::<i128, soroban_sdk::Error>(
+        &discount_token,
+        &Symbol::new(e, "balance"),
+        vec![e, payer.into_val(e)],
+    ) {
+        Ok(Ok(balance)) => balance,
+        _ => return 0,
+    };
+    discounts
+        .iter()
+        .filter(|discount| balance >= discount.min_balance)
+        .map(|discount| discount.discount_bps)
+        .max()
+        .unwrap_or(0)
+}
+
+// Addresses without a tier, or whose tier is missing from the schedule, pay
+// the public creation fee. Token holders then get the best discount they
+// qualify for, rounded in the contract's favour.
+pub fn creation_fee_for(e: &Env, payer: &Address) -> i128 {
+    let fee = read_fee_tier(e, payer)
+        .and_then(|tier| read_fee_schedule(e).get(tier))
+        .unwrap_or_else(|| read_creation_fee(e));
+    if fee == 0 {
+        return 0;
+    }
+    fee - bps_of(fee, holder_discount_bps(e, payer))
+}
+
+// `bps` basis points of `amount`, rounded down, without overflowing for any
+// `bps` up to 100%.
+pub(crate) fn bps_of(amount: i128, bps: u32) -> i128 {
+    let bps = bps as i128;
+    let scale = MAX_BPS as i128;
+    amount / scale * bps + amount % scale * bps / scale
+}
+
+pub fn charge_creation_fee(e: &Env, token_id: u32, payer: &Address) {
+    let fee = creation_fee_for(e, payer);
+    if fee == 0 || is_administrator(e, payer) {
+        return;
+    }
+    if credit::consume(e, token_id, payer) {
+        return;
+    }
+    let fee_token = match read_fee_token(e) {
+        Some(fee_token) => fee_token,
+        None => panic_with_error!(e, Error::FeeTokenNotSet),
+    };
+
+    token::Client::new(e, &fee_token).transfer(payer, e.current_contract_address(), &fee);
+    let key = DataKey::FeePaid(token_id);
+    e.storage()
+        .persistent()
+        .set(&key, &(fee_token.clone(), fee));
+    add_collected(e, &fee_token, fee);
+    stats::record_fee(e, fee);
+    event::fee_paid(e, token_id, payer.clone(), fee_token, fee);
+}
+
+// The token and amount charged when `token_id` was minted. The token is kept
+// because the admin may switch fee tokens before the fee is refunded.
+pub fn read_fee_paid(e: &Env, token_id: u32) -> Option<(Address, i128)> {
+    let key = DataKey::FeePaid(token_id);
+    e.storage().persistent().get(&key)
+}
+
+// Returns the fee charged when `token_id` was minted, if any, to `to` in the
+// token it was paid in, less a referral share that was already claimed. A
+// credit used instead goes back to `to` as a credit. Fails once the fee has
+// been withdrawn, rather than paying it out of escrow or the bounty pool.
+pub fn refund_creation_fee(
+    e: &Env,
+    token_id: u32,
+    to: &Address,
+) -> Result<i128, soroban_sdk::Error> {
+    if credit::restore(e, token_id, to) {
+        return Ok(0);
+    }
+    let (fee_token, fee) = match read_fee_paid(e, token_id) {
+        Some(fee_paid) => fee_paid,
+        None => return Ok(0),
+    };
+    e.storage().persistent().remove(&DataKey::FeePaid(token_id));
+    // A referral share the referrer already claimed is not paid twice.
+    let fee = fee - referral::reverse(e, token_id);
+    if fee > read_collected(e, &fee_token) {
+        return Err(ExtendedError::InsufficientFees.into());
+    }
+
+    token::Client::new(e, &fee_token).transfer(&e.current_contract_address(), to, &fee);
+    add_collected(e, &fee_token, -fee);
+    stats::record_fee(e, -fee);
+    event::fee_refunded(e, token_id, to.clone(), fee_token, fee);
+    Ok(fee)
+}
+
+pub fn withdraw(e: &Env, to: &Address, amount: i128) -> Result<(), soroban_sdk::Error> {
+    let fee_token = match read_fee_token(e) {
+        Some(fee_token) => fee_token,
+        None => return Err(Error::FeeTokenNotSet.into()),
+    };
+    if amount <= 0 {
+        return Err(Error::InvalidFeeAmount.into());
+    }
+    if amount > read_collected(e, &fee_token) {
+        return Err(ExtendedError::InsufficientFees.into());
+    }
+    add_collected(e, &fee_token, -amount);
+    token::Client::new(e, &fee_token).transfer(&e.current_contract_address(), to, &amount);
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use soroban_sdk::Env;
+
+use crate::storage_types::DataKey;
+
+// Seconds after the deadline during which signatures are still accepted, but
+// recorded as `SignedLate`. Documents can override the contract default.
+pub fn read_default_grace_period(e: &Env) -> u64 {
+    let key = DataKey::GracePeriod;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_default_grace_period(e: &Env, seconds: u64) {
+    let key = DataKey::GracePeriod;
+    e.storage().instance().set(&key, &seconds);
+}
+
+pub fn write_grace_period(e: &Env, token_id: u32, seconds: u64) {
+    let key = DataKey::DocGracePeriod(token_id);
+    e.storage().persistent().set(&key, &seconds);
+}
+
+pub fn read_grace_period(e: &Env, token_id: u32) -> u64 {
+    let key = DataKey::DocGracePeriod(token_id);
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| read_default_grace_period(e))
+}
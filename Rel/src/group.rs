@@ -0,0 +1,38 @@
+use soroban_sdk::{panic_with_error, Address, Env, Symbol, Vec};
+
+use crate::admin::is_administrator;
+use crate::storage_types::DataKey;
+use crate::{Error, SignerGroup};
+
+pub fn read_group(e: &Env, name: Symbol) -> Option<SignerGroup> {
+    let key = DataKey::SignerGroup(name);
+    e.storage().persistent().get(&key)
+}
+
+// Groups can be replaced by whoever created them or by the admin, and keep
+// their original owner either way.
+pub fn write_group(e: &Env, caller: &Address, name: Symbol, signers: Vec<Address>) {
+    if signers.is_empty() {
+        panic_with_error!(e, Error::SignersListEmpty)
+    }
+    let owner = match read_group(e, name.clone()) {
+        Some(group) => {
+            if group.owner != *caller && !is_administrator(e, caller) {
+                panic_with_error!(e, Error::NotGroupOwner)
+            }
+            group.owner
+        }
+        None => caller.clone(),
+    };
+
+    let group = SignerGroup { owner, signers };
+    let key = DataKey::SignerGroup(name);
+    e.storage().persistent().set(&key, &group);
+}
+
+pub fn expand_group(e: &Env, name: Symbol) -> Vec<Address> {
+    match read_group(e, name) {
+        Some(group) => group.signers,
+        None => panic_with_error!(e, Error::GroupNotFound),
+    }
+}
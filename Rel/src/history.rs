@@ -0,0 +1,36 @@
+use soroban_sdk::{Address, Env, Map, Vec};
+
+use crate::storage_types::{DocKey, SignerKey, MAX_SIGNER_HISTORY};
+use crate::{SignatureStatus, StatusChange};
+
+// Oldest first. Only the last `MAX_SIGNER_HISTORY` changes are kept.
+pub fn read_history(e: &Env, token_id: u32, signer: Address) -> Vec<StatusChange> {
+    let key = DocKey::SignerHistory(SignerKey { token_id, signer });
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Vec::new(e))
+}
+
+pub fn record(e: &Env, token_id: u32, signer: &Address, status: SignatureStatus) {
+    let mut history = read_history(e, token_id, signer.clone());
+    if history.len() >= MAX_SIGNER_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(StatusChange {
+        status,
+        timestamp: e.ledger().timestamp(),
+    });
+    let key = DocKey::SignerHistory(SignerKey {
+        token_id,
+        signer: signer.clone(),
+    });
+    e.storage().persistent().set(&key, &history);
+}
+
+pub fn clear(e: &Env, token_id: u32, signings: &Map<Address, SignatureStatus>) {
+    for signer in signings.keys().iter() {
+        let key = DocKey::SignerHistory(SignerKey { token_id, signer });
+        e.storage().persistent().remove(&key);
+    }
+}
@@ -2,358 +2,2832 @@
 
 mod storage_types;
 use crate::storage_types::{
-    INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK, INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+    ConfigKey, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK, INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+    MAX_NOTE_LEN,
 };
 
-mod erc_functions;
-use crate::erc_functions::exists;
+mod event;
+
+mod access;
+
+mod admin;
+use crate::admin::{
+    has_administrator, is_administrator, is_renounced, read_administrator, read_admins,
+    read_threshold, require_single_admin, write_administrator,
+};
+
+mod token_contract;
+use crate::token_contract::{read_token_contract, write_token_contract};
+
+mod fee;
+use crate::fee::{
+    charge_creation_fee, read_creation_fee, read_fee_token, refund_creation_fee,
+    write_creation_fee, write_fee_token,
+};
+
+mod status;
+use crate::status::{read_status, read_tokens_by_status, write_completed_at, write_status};
+
+mod escrow;
+use crate::escrow::read_escrow;
+
+mod reminder;
+use crate::reminder::{
+    mark_reminder_sent, read_reminder, read_reminder_interval, write_reminder,
+    write_reminder_interval,
+};
+
+mod attribute;
+
+mod audit;
+use crate::audit::read_audit_log;
+
+mod stats;
+use crate::stats::read_stats;
+
+mod group;
+use crate::group::{expand_group, read_group, write_group};
+
+mod template;
+use crate::template::{read_template, take_next_token_id, write_template};
+
+mod proposal;
+use crate::proposal::read_proposal;
+
+mod clawback;
+use crate::clawback::read_clawback_reason;
+
+mod certificate;
+
+mod archive;
+use crate::archive::read_archive;
+
+mod grace;
+use crate::grace::{read_default_grace_period, read_grace_period, write_default_grace_period};
+
+mod deadline;
+
+mod dispute;
+
+mod collection;
+
+mod link;
+
+mod minters;
+
+mod ownership;
+
+mod ratelimit;
+
+mod credit;
+
+mod lockup;
+
+mod org;
+
+mod referral;
+
+mod reopen;
+
+mod rerequest;
+use crate::rerequest::{read_max_rerequests, read_requires_amendment};
+
+mod revocation;
+
+mod signing;
+use crate::signing::Signing;
+
+mod history;
+
+mod witness;
+
+mod bounty;
+
+mod callback;
+use crate::callback::read_callback;
+
+mod attestation;
+use crate::attestation::{read_attestation, read_required_level, write_attestor};
+
+mod limits;
+use crate::limits::{
+    read_max_batch_size, read_string_limits, write_max_batch_size, write_string_limits,
+};
+
+mod uri;
+use crate::uri::{read_uri_prefixes, write_uri_prefixes};
+
+mod poll;
+use crate::poll::{read_poll, read_voting_token, write_voting_token};
+
+mod document;
+use crate::document::{
+    add_to_index, read_creator, read_deadline, read_document_hash, read_owner, read_signings,
+    read_token_by_hash, read_token_ids, read_unique_hashes, read_uri, write_creator,
+    write_deadline, write_document_hash, write_owner, write_signings, write_unique_hashes,
+    write_uri,
+};
+
+mod migration;
+use crate::migration::migration_status;
+
+mod test;
+pub mod testutils;
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, log, panic_with_error, symbol_short,
+    Address, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec,
+};
+
+#[contract]
+pub struct PetalDocuments;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    TokenNotMinted = 1,
+    DocumentSigningsIsEmpty = 2,
+    NotASigner = 3,
+    AlreadySigned = 4,
+    SignerDoesNotExist = 5,
+    DocumentHashesIsEmpty = 6,
+    DocumentHashesDoesNotMatchTokenHash = 7,
+    HashNotFound = 8,
+    DeadlinesIsEmpty = 9,
+    DeadlinePassed = 10,
+    DeadlineNotFound = 11,
+    SignatureExpired = 12,
+    TokenAlreadyMinted = 13,
+    TokenDoesNotExist = 14,
+    SignersListEmpty = 15,
+    TokenContractUnavailable = 16,
+    InvalidFeeToken = 17,
+    FeeTokenNotSet = 18,
+    InvalidFeeAmount = 19,
+    InvalidEscrowAmount = 20,
+    EscrowNotFound = 21,
+    EscrowNotReleasable = 22,
+    EscrowNotRefundable = 23,
+    DocumentNotPending = 24,
+    InvalidReminder = 25,
+    ReminderNotSet = 26,
+    ReminderNotDue = 27,
+    ReminderRateLimited = 28,
+    GroupNotFound = 29,
+    NotGroupOwner = 30,
+    TemplateNotFound = 31,
+    NotAnAdmin = 32,
+    InvalidThreshold = 33,
+    ProposalNotFound = 34,
+    ProposalExpired = 35,
+    AlreadyApproved = 36,
+    ApprovalRequired = 37,
+    VotingTokenNotSet = 38,
+    PollAlreadyExists = 39,
+    InvalidPollEnd = 40,
+    PollNotFound = 41,
+    PollClosed = 42,
+    AlreadyVoted = 43,
+    NoVotingPower = 44,
+    NotPollCreator = 45,
+    InvalidUri = 46,
+    StringTooLong = 47,
+    NotAnAttestor = 48,
+    SignerNotAttested = 49,
+    InvalidStatus = 50,
+}
+
+// A contract error enum is limited to 50 cases, so errors added after `Error`
+// filled up continue its numbering here. Codes never overlap between the two.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ExtendedError {
+    DuplicateDocumentHash = 51,
+    DocumentNotCompleted = 52,
+    AlreadyArchived = 53,
+    MigrationPending = 54,
+    BatchTooLarge = 55,
+    NotDocumentCreator = 56,
+    DocumentNotReopenable = 57,
+    InvalidDeadline = 58,
+    SignaturesRecorded = 59,
+    NotAnOwner = 60,
+    CollectionNotFound = 61,
+    NotCollectionOwner = 62,
+    RateLimited = 63,
+    NotAllowedMinter = 64,
+    DuplicateSigner = 65,
+    DisputeAlreadyOpen = 66,
+    NoOpenDispute = 67,
+    NotAParty = 68,
+    NotAnArbiter = 69,
+    InvalidLink = 70,
+    TooManyLinks = 71,
+    TooManyAttributes = 72,
+    ContractSealed = 73,
+    RenounceDisabled = 74,
+    AdminRenounced = 75,
+    RenounceNotRequested = 76,
+    InvalidHashLength = 77,
+    TooManyHashes = 78,
+    NonceMismatch = 79,
+    SignerNotRejected = 80,
+    TooManyRerequests = 81,
+    AmendmentRequired = 82,
+    FeeTokenNotNative = 83,
+    InvalidDiscount = 84,
+    InvalidReferralShare = 85,
+    SelfReferral = 86,
+    InvalidCreditAmount = 87,
+    OrgNotFound = 88,
+    NotOrgMember = 89,
+    OrgRoleTooLow = 90,
+    LastOrgAdmin = 91,
+    SignatureNotRevocable = 92,
+    RevocationWindowClosed = 93,
+    InsufficientFees = 94,
+    EscrowRefunded = 95,
+    DuplicateAdmin = 96,
+    SnapshotsUnsupported = 97,
+    DocumentNotClawbackable = 98,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum SignatureStatus {
+    NotASigner,
+    Rejected,
+    Signed,
+    Waiting,
+    // Signed after the deadline, within the grace period.
+    SignedLate,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum DocumentStatus {
+    Pending,
+    Completed,
+    Rejected,
+    Expired,
+    ClawedBack,
+    Cancelled,
+    Voided,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct EscrowDeposit {
+    pub token: Address,
+    pub amount: i128,
+    pub payee: Address,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Escrow {
+    pub depositor: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub payee: Address,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum AuditAction {
+    Minted,
+    SignersChanged,
+    Signed,
+    Rejected,
+    DeadlineExtended,
+    Amended,
+    ClawedBack,
+    SignedLate,
+    Reopened,
+    Cancelled,
+    Disputed,
+    DisputeRestored,
+    DisputeUpheld,
+    Voided,
+    HashAdded,
+    Rerequested,
+    SignatureRevoked,
+    Witnessed,
+    WitnessDeclined,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct AuditEntry {
+    pub action: AuditAction,
+    pub actor: Address,
+    pub timestamp: u64,
+}
+
+// Everything needed to recreate a document on another deployment. `status`
+// is the recorded one, so a pending document past its deadline stays
+// pending here.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentState {
+    pub token_id: u32,
+    pub owner: Address,
+    pub creator: Address,
+    pub uri: String,
+    pub document_hash: String,
+    pub hash_algo: HashAlgo,
+    pub extra_hashes: Vec<DocumentHash>,
+    pub deadline: u64,
+    pub status: DocumentStatus,
+    pub signings: Map<Address, SignatureStatus>,
+}
+
+// One page of `export_state`. The export is complete once `next_cursor`
+// reaches `total`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct StateChunk {
+    pub documents: Vec<DocumentState>,
+    pub next_cursor: u32,
+    pub total: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentDetails {
+    pub owner: Address,
+    pub uri: String,
+    pub document_hash: String,
+    pub deadline: u64,
+    pub status: DocumentStatus,
+    pub signers: Map<Address, SignatureStatus>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct CertificateSigner {
+    pub signer: Address,
+    pub status: SignatureStatus,
+    // Ledger timestamp of the signature or rejection, if one was recorded.
+    pub signed_at: Option<u64>,
+}
+
+// Canonical proof of a document's signing state for off-chain rendering.
+// The network passphrase itself is not visible on chain, so the network is
+// identified by its id, the sha256 of the passphrase.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Certificate {
+    pub token_id: u32,
+    pub document_hash: String,
+    pub hash_algo: HashAlgo,
+    // Parties only; witnesses are listed separately.
+    pub signers: Vec<CertificateSigner>,
+    pub witnesses: Vec<CertificateSigner>,
+    pub deadline: u64,
+    pub status: DocumentStatus,
+    pub completed_at: Option<u64>,
+    pub contract: Address,
+    pub network_id: BytesN<32>,
+}
+
+// Byte lengths above which URIs and document hashes are refused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct StringLimits {
+    pub max_uri_len: u32,
+    pub max_hash_len: u32,
+}
+
+// Deployment settings in one read, for tooling that checks a fresh instance.
+// The TTL fields are the instance threshold and extension in ledgers.
+// `fee_token` holds at most one address, since SDK 20 can't convert
+// `Option<Address>` struct fields in tests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Config {
+    pub version: u32,
+    pub event_schema_version: u32,
+    pub fee_token: Vec<Address>,
+    pub creation_fee: i128,
+    pub string_limits: StringLimits,
+    pub max_batch_size: u32,
+    pub instance_ttl_threshold: u32,
+    pub instance_ttl_extend_to: u32,
+    pub sealed: bool,
+    pub admin_renounced: bool,
+    pub max_rerequests: u32,
+    pub rerequest_needs_amendment: bool,
+}
+
+// What a referrer has brought in, in one fee token. `earned` drops again when
+// a referred document's fee is refunded before the share was claimed;
+// otherwise the refund is short by the claimed share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ReferralAccount {
+    pub referrals: u32,
+    pub earned: i128,
+    pub claimed: i128,
+}
+
+// Holders of at least `min_balance` of the discount token pay
+// `discount_bps` basis points less creation fee.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct FeeDiscount {
+    pub min_balance: i128,
+    pub discount_bps: u32,
+}
+
+// The public creation fee in native XLM, also split into whole XLM and the
+// remaining stroops so clients don't have to convert.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct NativeFeeQuote {
+    pub fee_token: Address,
+    pub stroops: i128,
+    pub xlm: i128,
+    pub remainder_stroops: i128,
+}
+
+// What is left of a document after `archive_document`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ArchivedDocument {
+    pub certificate_hash: BytesN<32>,
+    pub completed_at: Option<u64>,
+    pub archived_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct ContractStats {
+    pub total_minted: u32,
+    pub total_completed: u32,
+    pub total_expired: u32,
+    pub total_signatures: u32,
+    pub total_fees_collected: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignerGroup {
+    pub owner: Address,
+    pub signers: Vec<Address>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Template {
+    pub default_deadline_offset: u64,
+    pub required_signers: Vec<Address>,
+    pub metadata: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct AdminSet {
+    pub admins: Vec<Address>,
+    pub threshold: u32,
+}
+
+// `tier` of `None` moves `addr` back to the public fee.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct FeeTierChange {
+    pub addr: Address,
+    pub tier: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct FeeWithdrawal {
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct CreditGrant {
+    pub to: Address,
+    pub credits: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Clawback {
+    pub token_id: u32,
+    pub to: Address,
+    pub reason: String,
+}
+
+// Sensitive operations that need `threshold` admins to approve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum ProposalAction {
+    SetAdmins(AdminSet),
+    SetCreationFee(i128),
+    SetFeeToken(Address),
+    SetFeeSchedule(Map<u32, i128>),
+    SetFeeTier(FeeTierChange),
+    SetFeeDiscounts(Vec<FeeDiscount>),
+    SetDiscountToken(Address),
+    SetReferralShare(u32),
+    GrantCredits(CreditGrant),
+    // Funded by the admin whose approval executes the proposal.
+    FundBountyPool(i128),
+    WithdrawFees(FeeWithdrawal),
+    Clawback(Clawback),
+    Upgrade(BytesN<32>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Proposal {
+    pub action: ProposalAction,
+    pub approvals: Vec<Address>,
+    pub expiration_ledger: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct MigrationStatus {
+    pub migrated: u32,
+    pub remaining: u32,
+    pub complete: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Poll {
+    pub voting_token: Address,
+    pub snapshot_ledger: u32,
+    pub end_time: u64,
+    pub votes_for: i128,
+    pub votes_against: i128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum PollOutcome {
+    Open,
+    Approved,
+    Vetoed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct PollResult {
+    pub outcome: PollOutcome,
+    pub votes_for: i128,
+    pub votes_against: i128,
+}
+
+// Invoked as `function(doc_id: u32, status: DocumentStatus)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Callback {
+    pub contract: Address,
+    pub function: Symbol,
+}
+
+// Optional extras for `safe_mint`, each given at most once.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub enum MintOption {
+    Escrow(EscrowDeposit),
+    // Ledger timestamp after which `emit_reminder` may be called.
+    Reminder(u64),
+    // Named signer group, added to the explicit `signers` list.
+    Group(Symbol),
+    // Minimum attestation level every signer needs before signing.
+    AttestationLevel(u32),
+    // Contract to notify once the document is completed or expires.
+    Callback(Callback),
+    // Seconds after the deadline during which signatures are still taken,
+    // overriding the contract default.
+    GracePeriod(u64),
+    // Deadlines for individual signers, overriding the document deadline.
+    SignerDeadlines(Map<Address, u64>),
+    // Digest behind `document_hash`, whose length has to match it.
+    HashAlgo(HashAlgo),
+    // Owners next to the minting address, e.g. the parties of a joint venture.
+    CoOwners(Vec<Address>),
+    // Whether one owner or all of them must authorize owner-only operations.
+    OwnerAuth(OwnerAuth),
+    // The only address allowed to resolve disputes on this document.
+    Arbiter(Address),
+    // Partner credited with the referral share of the creation fee.
+    Referrer(Address),
+    // Organization to mint under; the minter has to be one of its members.
+    Org(u32),
+    // Seconds after signing during which a signer may revoke their signature.
+    RevocationWindow(u64),
+    // Signers who only witness: they sign like parties, but the document
+    // completes without them.
+    Witnesses(Vec<Address>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Dispute {
+    pub raised_by: Address,
+    pub reason: String,
+    pub raised_at: u64,
+}
+
+// `Restored` rejects the dispute and the document stands. `Upheld` accepts
+// it without touching the document; `Voided` accepts it and voids the
+// document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum ArbitrationOutcome {
+    Restored,
+    Upheld,
+    Voided,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Arbitration {
+    pub arbiter: Address,
+    pub outcome: ArbitrationOutcome,
+    pub resolved_at: u64,
+}
+
+// A directed edge between two documents, e.g. an amendment (`child_id`)
+// of an agreement (`parent_id`) with relation `amends`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentLink {
+    pub parent_id: u32,
+    pub child_id: u32,
+    pub relation: Symbol,
+}
+
+// One change to a pending document, applied through `multicall`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum DocumentOp {
+    ExtendDeadline(u64),
+    AddSigner(Address),
+    SetMetaUri(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentCall {
+    pub doc_id: u32,
+    pub op: DocumentOp,
+}
+
+// Keeper entrypoints that earn a bounty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum BountyAction {
+    Sweep,
+    Reminder,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum RateAction {
+    Mint,
+    Sign,
+}
+
+// At most `max_actions` per address within `window_ledgers` ledgers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct RateLimit {
+    pub max_actions: u32,
+    pub window_ledgers: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct RateWindow {
+    pub start_ledger: u32,
+    pub count: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Collection {
+    pub owner: Address,
+    pub name: String,
+    pub size: u32,
+}
+
+// Roles are ordered; each includes what the ones before it may do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[contracttype]
+pub enum OrgRole {
+    Member,
+    Manager,
+    Admin,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Organization {
+    pub name: String,
+    pub creator: Address,
+    pub members: u32,
+    pub admins: u32,
+    pub documents: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum OwnerAuth {
+    Any,
+    All,
+}
+
+// A content hash besides the one the document was minted with, e.g. of the
+// XML data layer next to the PDF.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct DocumentHash {
+    pub algo: HashAlgo,
+    pub hash: String,
+}
+
+// Digest a document hash was produced with. Hashes are hex encoded, and
+// Blake2b is the 512-bit variant. Documents minted without
+// `MintOption::HashAlgo` are `Unspecified` and keep any hash length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum HashAlgo {
+    Unspecified,
+    Sha256,
+    Keccak256,
+    Blake2b,
+}
+
+// Which deadline a signer was held to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[contracttype]
+pub enum DeadlineKind {
+    Document,
+    Signer,
+}
+
+// Everything a client shows for `user` on one page, from a single
+// simulated call. `status_counts` covers every document in the other lists.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Dashboard {
+    pub owned: Vec<u32>,
+    pub awaiting_signature: Vec<u32>,
+    pub signed: Vec<u32>,
+    pub status_counts: Map<DocumentStatus, u32>,
+}
+
+// Input to `sign`. A non-empty `note` is published with the signature, and
+// `expected_nonce` has to match `get_nonces` for the signer so a replayed
+// request fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignRequest {
+    pub doc_id: u32,
+    pub hash: String,
+    pub status: SignatureStatus,
+    pub note: String,
+    pub expected_nonce: u32,
+}
+
+// One entry of `get_signer_history`: the status a signer moved to and when.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct StatusChange {
+    pub status: SignatureStatus,
+    pub timestamp: u64,
+}
+
+// What `sign` returns: the outcome for this signer and document only.
+// `remaining_signers` counts parties still waiting, not witnesses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct SignReceipt {
+    pub token_id: u32,
+    pub signer: Address,
+    pub status: SignatureStatus,
+    pub remaining_signers: u32,
+    pub completed: bool,
+}
+
+#[derive(Clone, Debug)]
+#[contracttype]
+pub struct SignedMessage {
+    pub deadline: u64,
+    pub description: String,
+    pub document_hash: String,
+    pub document_uri: String,
+    pub signer: Address,
+    pub status: SignatureStatus,
+    pub token_id: u32,
+    pub nonce: u32,
+}
+
+// Bumped on breaking changes to the contract interface. Version 2 replaced
+// the map returned by `sign_document` with a `SignReceipt`; version 3 added
+// the hash algorithm to `Certificate`; version 4 added its witnesses. The
+// certificate hash kept for an archived document covers the layout of the
+// version it was archived under, see `get_archive_version`.
+pub const CONTRACT_VERSION: u32 = 4;
+
+fn initialize(e: &Env, admin: &Address, token_contract: Option<Address>) {
+    if has_administrator(e) || is_renounced(e) {
+        panic!("already initialized")
+    }
+
+    write_administrator(e, admin);
+
+    // When a token contract is configured, ownership and URIs live there
+    // and this contract only keeps the signing state.
+    if let Some(token_contract) = token_contract {
+        write_token_contract(e, &token_contract);
+    }
+}
+
+#[contractimpl]
+impl PetalDocuments {
+    // Runs at deploy time, so no other transaction can initialize the
+    // instance first.
+    pub fn __constructor(e: Env, admin: Address, token_contract: Option<Address>) {
+        initialize(&e, &admin, token_contract);
+    }
+
+    // Kept for deploy scripts written before the constructor. New instances
+    // are set up by `__constructor`, so this always panics on them.
+    // `_token_id` is unused; it stays in the signature so those scripts and
+    // the deployer's init args keep working.
+    pub fn init(e: Env, admin: Address, _token_id: u32, token_contract: Option<Address>) {
+        initialize(&e, &admin, token_contract);
+    }
+
+    pub fn sign(e: Env, signer: Address, request: SignRequest) -> SignReceipt {
+        if request.note.len() > MAX_NOTE_LEN {
+            panic_with_error!(&e, Error::StringTooLong)
+        }
+        if signing::read_nonce(&e, &signer) != request.expected_nonce {
+            panic_with_error!(&e, ExtendedError::NonceMismatch)
+        }
+        // The note and nonce are part of what the signer authorizes, so
+        // neither can be swapped on a signed request.
+        let auth_args = (
+            request.doc_id,
+            request.hash.clone(),
+            request.status.clone(),
+            request.note.clone(),
+            request.expected_nonce,
+        )
+            .into_val(&e);
+        let receipt = Self::record_signature(
+            e.clone(),
+            request.hash,
+            signer.clone(),
+            request.status,
+            request.doc_id,
+            auth_args,
+        );
+        if request.note.len() > 0 {
+            event::sign_note(&e, request.doc_id, signer, request.note);
+        }
+        receipt
+    }
+
+    // Deprecated: positional form of `sign` without the nonce check or a
+    // note. Kept for one release.
+    pub fn sign_document(
+        e: Env,
+        document_hash: String,
+        signer: Address,
+        status: SignatureStatus,
+        token_id: u32,
+    ) -> SignReceipt {
+        let auth_args = (token_id, document_hash.clone(), status.clone()).into_val(&e);
+        Self::record_signature(e, document_hash, signer, status, token_id, auth_args)
+    }
+
+    fn record_signature(
+        e: Env,
+        document_hash: String,
+        signer: Address,
+        status: SignatureStatus,
+        token_id: u32,
+        auth_args: Vec<Val>,
+    ) -> SignReceipt {
+        if !Self::require_minted(&e, token_id) {
+            panic_with_error!(&e, Error::TokenNotMinted)
+        }
+
+        if let Err(error) = signing::check_input_status(&status) {
+            panic_with_error!(&e, error)
+        }
+        let mut signing = Signing::load(&e, token_id, &signer);
+        let (late, deadline, kind) = signing.validate(&e, &signer, &document_hash);
+        // The authorization covers the exact signing action, so a signature
+        // made for another call in the same transaction can't be reused
+        // here. Works for custom account contracts too: the host calls the
+        // signer's `__check_auth`, and signing nonces are kept per address.
+        signer.require_auth_for_args(auth_args);
+        Self::check_rate_limit(&e, RateAction::Sign, &signer);
+        let status = match status {
+            SignatureStatus::Signed if late => SignatureStatus::SignedLate,
+            status => status,
+        };
+        signing.record(signer.clone(), status.clone());
+        signing.store(&e);
+        history::record(&e, token_id, &signer, status.clone());
+        signing::bump_nonce(&e, &signer);
+
+        // A witness signing or declining leaves the document's status alone.
+        if signing.is_witness(&signer) {
+            let action = match status {
+                SignatureStatus::Rejected => AuditAction::WitnessDeclined,
+                _ => AuditAction::Witnessed,
+            };
+            audit::append(&e, token_id, action, &signer);
+            event::witnessed(&e, token_id, signer.clone(), status.clone());
+            return SignReceipt {
+                token_id,
+                signer,
+                status,
+                remaining_signers: signing.remaining(),
+                completed: false,
+            };
+        }
+
+        match &status {
+            SignatureStatus::Signed => {
+                audit::append(&e, token_id, AuditAction::Signed, &signer);
+                stats::record_signature(&e);
+                event::signed(&e, token_id, signer.clone());
+            }
+            SignatureStatus::SignedLate => {
+                audit::append(&e, token_id, AuditAction::SignedLate, &signer);
+                stats::record_signature(&e);
+                event::signed_late(&e, token_id, signer.clone(), deadline, kind);
+            }
+            SignatureStatus::Rejected => {
+                audit::append(&e, token_id, AuditAction::Rejected, &signer)
+            }
+            _ => {}
+        }
+
+        let completed = status != SignatureStatus::Rejected && signing.is_complete();
+        if status == SignatureStatus::Rejected {
+            write_status(&e, token_id, DocumentStatus::Rejected);
+            event::rejected(&e, token_id, signer.clone());
+        } else if completed {
+            write_status(&e, token_id, DocumentStatus::Completed);
+            write_completed_at(&e, token_id, e.ledger().timestamp());
+            lockup::start(&e, token_id);
+            stats::record_completed(&e);
+            event::completed(&e, token_id);
+            callback::notify(&e, token_id, DocumentStatus::Completed);
+        }
+
+        SignReceipt {
+            token_id,
+            signer,
+            status,
+            remaining_signers: signing.remaining(),
+            completed,
+        }
+    }
+
+    // Runs the checks `sign_document` would for `signer`, except the hash
+    // and authorization, without changing anything.
+    pub fn can_sign(e: Env, doc_id: u32, signer: Address) -> Result<(), soroban_sdk::Error> {
+        if !Self::require_minted(&e, doc_id) {
+            return Err(Error::TokenNotMinted.into());
+        }
+        let signing = Signing::try_load(&e, doc_id, &signer)?;
+        signing.check_signer(&e, &signer)?;
+        if !is_administrator(&e, &signer) {
+            ratelimit::check(&e, RateAction::Sign, &signer)?;
+        }
+        Ok(())
+    }
+
+    // Runs the checks `safe_mint` would for `to` and `token_id` without
+    // changing anything. The signers, URI and hash aren't known here, and the
+    // fee balance is left to the token transfer.
+    pub fn can_mint(e: Env, to: Address, token_id: u32) -> Result<(), soroban_sdk::Error> {
+        let is_admin = is_administrator(&e, &to);
+        if !minters::may_mint(&e, &to) {
+            return Err(ExtendedError::NotAllowedMinter.into());
+        }
+        if !is_admin {
+            ratelimit::check(&e, RateAction::Mint, &to)?;
+        }
+        if Self::require_minted(&e, token_id) {
+            return Err(Error::TokenAlreadyMinted.into());
+        }
+        if !is_admin && fee::creation_fee_for(&e, &to) > 0 && read_fee_token(&e).is_none() {
+            return Err(Error::FeeTokenNotSet.into());
+        }
+        Ok(())
+    }
+
+    pub fn version(_e: Env) -> u32 {
+        CONTRACT_VERSION
+    }
+
+    // Lets tooling check for an optional extension before calling it. Known
+    // interfaces are `nft_transfer`, `royalties`, `escrow` and
+    // `ordered_signing`; documents only transfer, and carry royalties, through
+    // a configured token contract, and signing order isn't enforced.
+    pub fn supports(e: Env, interface: Symbol) -> bool {
+        if interface == symbol_short!("escrow") {
+            return true;
+        }
+        let through_token =
+            interface == Symbol::new(&e, "nft_transfer") || interface == symbol_short!("royalties");
+        through_token && read_token_contract(&e).is_some()
+    }
+
+    pub fn get_config(e: Env) -> Config {
+        Config {
+            version: CONTRACT_VERSION,
+            event_schema_version: event::EVENT_SCHEMA_VERSION,
+            fee_token: match read_fee_token(&e) {
+                Some(fee_token) => Vec::from_array(&e, [fee_token]),
+                None => Vec::new(&e),
+            },
+            creation_fee: read_creation_fee(&e),
+            string_limits: read_string_limits(&e),
+            max_batch_size: read_max_batch_size(&e),
+            instance_ttl_threshold: INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            instance_ttl_extend_to: INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+            sealed: Self::is_sealed(e.clone()),
+            admin_renounced: is_renounced(&e),
+            max_rerequests: read_max_rerequests(&e),
+            rerequest_needs_amendment: read_requires_amendment(&e),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn safe_mint(
+        e: Env,
+        to: Address,
+        token_id: u32,
+        meta_uri: String,
+        signers: Vec<Address>,
+        document_hash: String,
+        deadline: u64,
+        options: Vec<MintOption>,
+    ) -> u32 {
+        to.require_auth();
+        if !minters::may_mint(&e, &to) {
+            panic_with_error!(&e, ExtendedError::NotAllowedMinter)
+        }
+        Self::check_rate_limit(&e, RateAction::Mint, &to);
+
+        let mut signers = signers;
+        for option in options.iter() {
+            if let MintOption::Group(name) = option {
+                signers.append(&expand_group(&e, name));
+            }
+        }
+        if signers.is_empty() {
+            panic_with_error!(&e, Error::SignersListEmpty)
+        }
+        // A document that is born expired would only feed the sweep bounty.
+        if deadline <= e.ledger().timestamp() {
+            panic_with_error!(&e, ExtendedError::InvalidDeadline)
+        }
+        limits::check_uri(&e, &meta_uri);
+        limits::check_hash(&e, &document_hash);
+        for option in options.iter() {
+            if let MintOption::HashAlgo(algo) = option {
+                limits::check_hash_algo(&e, algo, &document_hash);
+            }
+        }
+        document::check_unique_hash(&e, token_id, &document_hash);
+        uri::validate(&e, &meta_uri);
+
+        // Like the Solidity contract, the admin mints for free.
+        charge_creation_fee(&e, token_id, &to);
+        match read_token_contract(&e) {
+            Some(token_contract) => {
+                token_contract::mint(&e, &token_contract, token_id, &to, &meta_uri);
+            }
+            None => {
+                Self::mint(&e, token_id, to.clone());
+                Self::set_token_uri(&e, token_id, meta_uri);
+            }
+        }
+
+        let mut inner_doc_signings: Map<Address, SignatureStatus> = Map::new(&e);
+        for signer in signers.iter() {
+            history::record(&e, token_id, &signer, SignatureStatus::Waiting);
+            inner_doc_signings.set(signer, SignatureStatus::Waiting);
+        }
+
+        write_creator(&e, token_id, &to);
+        write_document_hash(&e, token_id, &document_hash);
+        write_deadline(&e, token_id, deadline);
+        write_signings(&e, token_id, &inner_doc_signings);
+        add_to_index(&e, token_id);
+        write_status(&e, token_id, DocumentStatus::Pending);
+        audit::append(&e, token_id, AuditAction::Minted, &to);
+        stats::record_mint(&e);
+
+        for option in options.iter() {
+            match option {
+                MintOption::Escrow(terms) => escrow::deposit(&e, token_id, &to, terms),
+                MintOption::Reminder(remind_at) => {
+                    write_reminder(&e, token_id, remind_at, deadline)
+                }
+                MintOption::Group(_) => {}
+                MintOption::AttestationLevel(level) => {
+                    attestation::write_required_level(&e, token_id, level)
+                }
+                MintOption::Callback(callback) => callback::write_callback(&e, token_id, &callback),
+                MintOption::GracePeriod(seconds) => {
+                    grace::write_grace_period(&e, token_id, seconds)
+                }
+                MintOption::SignerDeadlines(deadlines) => {
+                    deadline::write_signer_deadlines(&e, token_id, &deadlines)
+                }
+                MintOption::CoOwners(co_owners) => {
+                    ownership::write_co_owners(&e, token_id, &to, &co_owners)
+                }
+                MintOption::OwnerAuth(mode) => ownership::write_owner_auth(&e, token_id, mode),
+                MintOption::Arbiter(arbiter) => {
+                    dispute::write_document_arbiter(&e, token_id, &arbiter)
+                }
+                MintOption::HashAlgo(algo) => document::write_hash_algo(&e, token_id, algo),
+                MintOption::Referrer(referrer) => referral::credit(&e, token_id, &to, &referrer),
+                MintOption::Org(org_id) => {
+                    if let Err(error) = org::check_role(&e, org_id, &to, OrgRole::Member) {
+                        panic_with_error!(&e, error)
+                    }
+                    org::add_document(&e, org_id, token_id);
+                }
+                MintOption::RevocationWindow(seconds) => {
+                    revocation::write_window(&e, token_id, seconds)
+                }
+                MintOption::Witnesses(witnesses) => {
+                    if let Err(error) = witness::add(&e, token_id, &witnesses) {
+                        panic_with_error!(&e, error)
+                    }
+                }
+            }
+        }
+
+        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
+        token_id
+    }
+
+    pub fn create_template(
+        e: Env,
+        template_id: u32,
+        default_deadline_offset: u64,
+        required_signers: Vec<Address>,
+        metadata: String,
+    ) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        let template = Template {
+            default_deadline_offset,
+            required_signers,
+            metadata,
+        };
+        write_template(&e, template_id, &template);
+    }
+
+    pub fn get_template(e: Env, template_id: u32) -> Option<Template> {
+        read_template(&e, template_id)
+    }
+
+    // Mints with the template's signers and metadata URI, a deadline relative
+    // to now and the regular creation fee.
+    pub fn mint_from_template(e: Env, template_id: u32, to: Address, document_hash: String) -> u32 {
+        let template = match read_template(&e, template_id) {
+            Some(template) => template,
+            None => panic_with_error!(&e, Error::TemplateNotFound),
+        };
+
+        let mut token_id = take_next_token_id(&e);
+        while Self::require_minted(&e, token_id) {
+            token_id = take_next_token_id(&e);
+        }
+        // An offset too large to add is as good as no deadline at all.
+        let deadline = e
+            .ledger()
+            .timestamp()
+            .saturating_add(template.default_deadline_offset);
+
+        Self::safe_mint(
+            e.clone(),
+            to,
+            token_id,
+            template.metadata,
+            template.required_signers,
+            document_hash,
+            deadline,
+            Vec::new(&e),
+        )
+    }
+
+    pub fn set_unique_hashes(e: Env, enabled: bool) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        write_unique_hashes(&e, enabled);
+    }
+
+    pub fn get_unique_hashes(e: Env) -> bool {
+        read_unique_hashes(&e)
+    }
+
+    pub fn get_hash_algo(e: Env, doc_id: u32) -> HashAlgo {
+        document::read_hash_algo(&e, doc_id)
+    }
+
+    // The minted hash first, then any added with `add_document_hash`.
+    pub fn get_document_hashes(e: Env, doc_id: u32) -> Vec<DocumentHash> {
+        let mut hashes = Vec::new(&e);
+        if let Some(hash) = read_document_hash(&e, doc_id) {
+            hashes.push_back(DocumentHash {
+                algo: document::read_hash_algo(&e, doc_id),
+                hash,
+            });
+        }
+        hashes.append(&document::read_extra_hashes(&e, doc_id));
+        hashes
+    }
+
+    // Adds another content hash signers may sign against. Only the creator
+    // can, and only before anyone has signed or rejected.
+    pub fn add_document_hash(e: Env, caller: Address, doc_id: u32, algo: HashAlgo, hash: String) {
+        caller.require_auth();
+
+        let creator = match Self::creator_of(&e, doc_id) {
+            Some(creator) => creator,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        if caller != creator {
+            panic_with_error!(&e, ExtendedError::NotDocumentCreator)
+        }
+        if Self::expire_if_due(&e, doc_id) != DocumentStatus::Pending {
+            panic_with_error!(&e, Error::DocumentNotPending)
+        }
+        let signings = read_signings(&e, doc_id).unwrap_or(Map::new(&e));
+        if signing::has_recorded_signatures(&signings) {
+            panic_with_error!(&e, ExtendedError::SignaturesRecorded)
+        }
+
+        limits::check_hash(&e, &hash);
+        limits::check_hash_algo(&e, algo, &hash);
+        document::check_unique_hash(&e, doc_id, &hash);
+        let entry = DocumentHash { algo, hash };
+        if let Err(error) = document::add_extra_hash(&e, doc_id, &entry) {
+            panic_with_error!(&e, error)
+        }
+        audit::append(&e, doc_id, AuditAction::HashAdded, &caller);
+        event::hash_added(&e, doc_id, caller, entry);
+    }
+
+    pub fn get_token_by_hash(e: Env, document_hash: String) -> Result<u32, Error> {
+        match read_token_by_hash(&e, &document_hash) {
+            Some(token_id) => Ok(token_id),
+            None => Err(Error::HashNotFound),
+        }
+    }
+
+    pub fn hash_exists(e: Env, document_hash: String) -> bool {
+        read_token_by_hash(&e, &document_hash).is_some()
+    }
+
+    // The address that minted the document. Unlike the owner it never
+    // changes, so it is what document management permissions check.
+    pub fn get_creator(e: Env, doc_id: u32) -> Result<Address, Error> {
+        match Self::creator_of(&e, doc_id) {
+            Some(creator) => Ok(creator),
+            None => Err(Error::TokenDoesNotExist),
+        }
+    }
+
+    // Ids that do not resolve to a full document, e.g. unknown or archived
+    // ones, are left out instead of failing the whole call.
+    pub fn get_documents_by_ids(e: Env, ids: Vec<u32>) -> Vec<DocumentDetails> {
+        limits::check_batch_size(&e, ids.len());
+
+        let mut documents = Vec::new(&e);
+        for doc_id in ids.iter() {
+            if let Ok(details) = Self::get_document_details(e.clone(), doc_id) {
+                documents.push_back(details);
+            }
+        }
+        documents
+    }
+
+    pub fn set_max_batch_size(e: Env, size: u32) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        write_max_batch_size(&e, size);
+    }
+
+    pub fn get_max_batch_size(e: Env) -> u32 {
+        read_max_batch_size(&e)
+    }
+
+    pub fn get_document_details(e: Env, doc_id: u32) -> Result<DocumentDetails, Error> {
+        let owner = match Self::owner_of(&e, doc_id) {
+            Some(owner) => owner,
+            None => return Err(Error::TokenDoesNotExist),
+        };
+        let document_hash = match read_document_hash(&e, doc_id) {
+            Some(hash) => hash,
+            None => return Err(Error::HashNotFound),
+        };
+        let deadline = match read_deadline(&e, doc_id) {
+            Some(deadline) => deadline,
+            None => return Err(Error::DeadlineNotFound),
+        };
+
+        Ok(DocumentDetails {
+            owner,
+            uri: Self::get_token_uri(e.clone(), doc_id),
+            document_hash,
+            deadline,
+            status: Self::get_document_status(e.clone(), doc_id),
+            signers: Self::get_document(e, doc_id),
+        })
+    }
+
+    pub fn get_certificate(e: Env, doc_id: u32) -> Result<Certificate, Error> {
+        if Self::owner_of(&e, doc_id).is_none() {
+            return Err(Error::TokenDoesNotExist);
+        }
+        let status = Self::get_document_status(e.clone(), doc_id);
+        certificate::build(&e, doc_id, status)
+    }
+
+    // Archived documents keep the hash of their final certificate.
+    pub fn certificate_hash(e: Env, doc_id: u32) -> Result<BytesN<32>, Error> {
+        if let Some(archived) = read_archive(&e, doc_id) {
+            return Ok(archived.certificate_hash);
+        }
+        let certificate = Self::get_certificate(e.clone(), doc_id)?;
+        Ok(certificate::hash(&e, certificate))
+    }
+
+    // Lets the owner of a completed document stop paying rent for its bulky
+    // entries once the final certificate has been published as an event.
+    pub fn archive_document(e: Env, caller: Address, doc_id: u32) -> ArchivedDocument {
+        let owner = match Self::owner_of(&e, doc_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        ownership::require_owner_auth(&e, doc_id, owner, &caller);
+
+        let status = Self::get_document_status(e.clone(), doc_id);
+        if status != DocumentStatus::Completed {
+            panic_with_error!(&e, ExtendedError::DocumentNotCompleted)
+        }
+        let snapshot = match certificate::build(&e, doc_id, status) {
+            Ok(snapshot) => snapshot,
+            Err(error) => panic_with_error!(&e, error),
+        };
+        archive::archive(&e, doc_id, snapshot)
+    }
+
+    // Applies `calls` in order under a single authorization from `caller`,
+    // who has to own every document touched. Managers of a document's
+    // organization may also extend its deadline and add signers. Any failing
+    // call reverts the whole batch.
+    pub fn multicall(e: Env, caller: Address, calls: Vec<DocumentCall>) {
+        limits::check_batch_size(&e, calls.len());
+
+        let mut authorized = Vec::new(&e);
+        for call in calls.iter() {
+            let doc_id = call.doc_id;
+            let owner = match Self::owner_of(&e, doc_id) {
+                Some(owner) => owner,
+                None => panic_with_error!(&e, Error::TokenDoesNotExist),
+            };
+            let org_managed = !matches!(call.op, DocumentOp::SetMetaUri(_))
+                && !Self::is_owner(e.clone(), doc_id, caller.clone())
+                && org::may_manage(&e, doc_id, &caller);
+            if !org_managed {
+                ownership::require_owner_auth_once(&e, doc_id, owner, &caller, &mut authorized);
+            } else if !authorized.contains(&caller) {
+                caller.require_auth();
+                authorized.push_back(caller.clone());
+            }
+            if Self::expire_if_due(&e, doc_id) != DocumentStatus::Pending {
+                panic_with_error!(&e, Error::DocumentNotPending)
+            }
+
+            let action = match call.op.clone() {
+                DocumentOp::ExtendDeadline(deadline) => {
+                    if Some(deadline) <= read_deadline(&e, doc_id) {
+                        panic_with_error!(&e, ExtendedError::InvalidDeadline)
+                    }
+                    write_deadline(&e, doc_id, deadline);
+                    AuditAction::DeadlineExtended
+                }
+                DocumentOp::AddSigner(signer) => {
+                    let mut signings = read_signings(&e, doc_id).unwrap_or(Map::new(&e));
+                    if signings.contains_key(signer.clone()) {
+                        panic_with_error!(&e, ExtendedError::DuplicateSigner)
+                    }
+                    history::record(&e, doc_id, &signer, SignatureStatus::Waiting);
+                    signings.set(signer, SignatureStatus::Waiting);
+                    write_signings(&e, doc_id, &signings);
+                    AuditAction::SignersChanged
+                }
+                DocumentOp::SetMetaUri(meta_uri) => {
+                    limits::check_uri(&e, &meta_uri);
+                    uri::validate(&e, &meta_uri);
+                    match read_token_contract(&e) {
+                        Some(token_contract) => {
+                            token_contract::set_token_uri(&e, &token_contract, doc_id, &meta_uri)
+                        }
+                        None => Self::set_token_uri(&e, doc_id, meta_uri),
+                    }
+                    AuditAction::Amended
+                }
+            };
+            audit::append(&e, doc_id, action, &caller);
+            event::updated(&e, doc_id, caller.clone(), call.op);
+        }
+    }
+
+    // Lets `grantee` view the document content until `expires_at`, for an
+    // off-chain gateway to check with `has_view_access`. Granting again
+    // replaces the expiry.
+    pub fn grant_view(e: Env, caller: Address, doc_id: u32, grantee: Address, expires_at: u64) {
+        let owner = match Self::owner_of(&e, doc_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        ownership::require_owner_auth(&e, doc_id, owner, &caller);
+        if expires_at <= e.ledger().timestamp() {
+            panic_with_error!(&e, ExtendedError::InvalidDeadline)
+        }
+
+        access::write_grant(&e, doc_id, &grantee, expires_at);
+        event::view_granted(&e, doc_id, grantee, expires_at);
+    }
+
+    pub fn revoke_view(e: Env, caller: Address, doc_id: u32, grantee: Address) {
+        let owner = match Self::owner_of(&e, doc_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        ownership::require_owner_auth(&e, doc_id, owner, &caller);
+
+        access::remove_grant(&e, doc_id, &grantee);
+        event::view_revoked(&e, doc_id, grantee, caller);
+    }
+
+    pub fn get_view_grant(e: Env, doc_id: u32, grantee: Address) -> Option<u64> {
+        access::read_grant(&e, doc_id, &grantee)
+    }
+
+    // Owners and signers can always view a document; anyone else needs a
+    // grant that hasn't expired.
+    pub fn has_view_access(e: Env, doc_id: u32, viewer: Address) -> bool {
+        let owner = match Self::owner_of(&e, doc_id) {
+            Some(owner) => owner,
+            None => return false,
+        };
+        if ownership::owners(&e, doc_id, owner).contains(&viewer) {
+            return true;
+        }
+        let is_signer = read_signings(&e, doc_id)
+            .map(|signings| signings.contains_key(viewer.clone()))
+            .unwrap_or(false);
+        is_signer || access::has_valid_grant(&e, doc_id, &viewer)
+    }
+
+    // Flags a completed document as disputed by one of its signers or owners.
+    // Signatures and status are left as they are.
+    pub fn raise_dispute(e: Env, caller: Address, doc_id: u32, reason: String) {
+        caller.require_auth();
+        let owner = match Self::owner_of(&e, doc_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        if read_status(&e, doc_id) != DocumentStatus::Completed {
+            panic_with_error!(&e, ExtendedError::DocumentNotCompleted)
+        }
+        let is_signer = read_signings(&e, doc_id)
+            .map(|signings| signings.contains_key(caller.clone()))
+            .unwrap_or(false);
+        if !is_signer && !ownership::owners(&e, doc_id, owner).contains(&caller) {
+            panic_with_error!(&e, ExtendedError::NotAParty)
+        }
+        if dispute::read_dispute(&e, doc_id).is_some() {
+            panic_with_error!(&e, ExtendedError::DisputeAlreadyOpen)
+        }
+
+        let dispute = Dispute {
+            raised_by: caller.clone(),
+            reason: reason.clone(),
+            raised_at: e.ledger().timestamp(),
+        };
+        dispute::write_dispute(&e, doc_id, &dispute);
+        audit::append(&e, doc_id, AuditAction::Disputed, &caller);
+        event::disputed(&e, doc_id, caller, reason);
+    }
+
+    // Closes the open dispute. A document minted with its own arbiter can
+    // only be decided by that arbiter; others by the admin or the contract
+    // arbiter.
+    pub fn resolve_dispute(e: Env, caller: Address, doc_id: u32, outcome: ArbitrationOutcome) {
+        caller.require_auth();
+        if !dispute::may_resolve(&e, doc_id, &caller) {
+            panic_with_error!(&e, ExtendedError::NotAnArbiter)
+        }
+        if dispute::read_dispute(&e, doc_id).is_none() {
+            panic_with_error!(&e, ExtendedError::NoOpenDispute)
+        }
+
+        dispute::remove_dispute(&e, doc_id);
+        let arbitration = Arbitration {
+            arbiter: caller.clone(),
+            outcome,
+            resolved_at: e.ledger().timestamp(),
+        };
+        dispute::write_arbitration(&e, doc_id, &arbitration);
+        let action = match outcome {
+            ArbitrationOutcome::Restored => AuditAction::DisputeRestored,
+            ArbitrationOutcome::Upheld => AuditAction::DisputeUpheld,
+            ArbitrationOutcome::Voided => AuditAction::Voided,
+        };
+        audit::append(&e, doc_id, action, &caller);
+        if outcome == ArbitrationOutcome::Voided {
+            write_status(&e, doc_id, DocumentStatus::Voided);
+            callback::notify(&e, doc_id, DocumentStatus::Voided);
+        }
+        event::dispute_resolved(&e, doc_id, caller, outcome);
+    }
+
+    // The last resolved dispute on the document.
+    pub fn get_arbitration(e: Env, doc_id: u32) -> Option<Arbitration> {
+        dispute::read_arbitration(&e, doc_id)
+    }
+
+    pub fn get_document_arbiter(e: Env, doc_id: u32) -> Option<Address> {
+        dispute::read_document_arbiter(&e, doc_id)
+    }
+
+    pub fn get_dispute(e: Env, doc_id: u32) -> Option<Dispute> {
+        dispute::read_dispute(&e, doc_id)
+    }
+
+    pub fn is_disputed(e: Env, doc_id: u32) -> bool {
+        dispute::read_dispute(&e, doc_id).is_some()
+    }
+
+    pub fn set_arbiter(e: Env, arbiter: Option<Address>) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        dispute::write_arbiter(&e, arbiter);
+    }
+
+    pub fn get_arbiter(e: Env) -> Option<Address> {
+        dispute::read_arbiter(&e)
+    }
+
+    // Records that `child_id` relates to `parent_id`. The caller has to own
+    // the parent; the child only has to exist.
+    pub fn link_documents(
+        e: Env,
+        caller: Address,
+        parent_id: u32,
+        child_id: u32,
+        relation: Symbol,
+    ) {
+        let owner = match Self::owner_of(&e, parent_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        ownership::require_owner_auth(&e, parent_id, owner, &caller);
+        if !Self::require_minted(&e, child_id) {
+            panic_with_error!(&e, Error::TokenDoesNotExist)
+        }
+
+        let link = DocumentLink {
+            parent_id,
+            child_id,
+            relation: relation.clone(),
+        };
+        if let Err(error) = link::check_link(&e, &link) {
+            panic_with_error!(&e, error)
+        }
+        link::add_link(&e, &link);
+        event::linked(&e, parent_id, child_id, relation);
+    }
+
+    // Links where `doc_id` is either the parent or the child, oldest first.
+    pub fn get_links(e: Env, doc_id: u32) -> Vec<DocumentLink> {
+        link::read_links(&e, doc_id)
+    }
+
+    // Free-form fields for integrators, e.g. `jurisdiction`. Owners only.
+    pub fn set_attribute(e: Env, caller: Address, doc_id: u32, key: Symbol, value: String) {
+        let owner = match Self::owner_of(&e, doc_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        ownership::require_owner_auth(&e, doc_id, owner, &caller);
+
+        if let Err(error) = attribute::set_attribute(&e, doc_id, key.clone(), value.clone()) {
+            panic_with_error!(&e, error)
+        }
+        event::attribute_set(&e, doc_id, key, Some(value));
+    }
+
+    pub fn remove_attribute(e: Env, caller: Address, doc_id: u32, key: Symbol) {
+        let owner = match Self::owner_of(&e, doc_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        ownership::require_owner_auth(&e, doc_id, owner, &caller);
+
+        attribute::remove_attribute(&e, doc_id, key.clone());
+        event::attribute_set(&e, doc_id, key, None);
+    }
+
+    pub fn get_attributes(e: Env, doc_id: u32) -> Map<Symbol, String> {
+        attribute::read_attributes(&e, doc_id)
+    }
+
+    // The primary owner first, then any co-owners.
+    pub fn get_document_owners(e: Env, doc_id: u32) -> Result<Vec<Address>, Error> {
+        match Self::owner_of(&e, doc_id) {
+            Some(owner) => Ok(ownership::owners(&e, doc_id, owner)),
+            None => Err(Error::TokenDoesNotExist),
+        }
+    }
+
+    pub fn is_owner(e: Env, doc_id: u32, addr: Address) -> bool {
+        match Self::owner_of(&e, doc_id) {
+            Some(owner) => ownership::owners(&e, doc_id, owner).contains(&addr),
+            None => false,
+        }
+    }
+
+    pub fn get_owner_auth(e: Env, doc_id: u32) -> OwnerAuth {
+        ownership::read_owner_auth(&e, doc_id)
+    }
+
+    pub fn create_collection(e: Env, owner: Address, name: String) -> u32 {
+        owner.require_auth();
+
+        let collection_id = collection::create(&e, &owner, name);
+        event::collection_created(&e, collection_id, owner);
+        collection_id
+    }
+
+    // Only the collection owner can add documents, and only ones they own.
+    pub fn assign_to_collection(e: Env, caller: Address, doc_id: u32, collection_id: u32) {
+        caller.require_auth();
+
+        let collection = match collection::read_collection(&e, collection_id) {
+            Some(collection) => collection,
+            None => panic_with_error!(&e, ExtendedError::CollectionNotFound),
+        };
+        if collection.owner != caller {
+            panic_with_error!(&e, ExtendedError::NotCollectionOwner)
+        }
+        if !Self::is_owner(e.clone(), doc_id, caller) {
+            panic_with_error!(&e, ExtendedError::NotAnOwner)
+        }
+
+        collection::assign(&e, doc_id, collection_id);
+        event::assigned_to_collection(&e, doc_id, collection_id);
+    }
+
+    pub fn get_collection(e: Env, collection_id: u32) -> Option<Collection> {
+        collection::read_collection(&e, collection_id)
+    }
+
+    pub fn get_document_collection(e: Env, doc_id: u32) -> Option<u32> {
+        collection::read_document_collection(&e, doc_id)
+    }
+
+    pub fn create_org(e: Env, creator: Address, name: String) -> u32 {
+        creator.require_auth();
+
+        let org_id = org::create(&e, &creator, name);
+        event::org_created(&e, org_id, creator.clone());
+        event::org_member_set(&e, org_id, creator, OrgRole::Admin);
+        org_id
+    }
+
+    // Only org admins can add members or change their roles.
+    pub fn add_member(e: Env, caller: Address, org_id: u32, member: Address, role: OrgRole) {
+        caller.require_auth();
+
+        let changed = org::check_role(&e, org_id, &caller, OrgRole::Admin)
+            .and_then(|_| org::set_member(&e, org_id, &member, role));
+        if let Err(error) = changed {
+            panic_with_error!(&e, error)
+        }
+        event::org_member_set(&e, org_id, member, role);
+    }
+
+    // Org admins can remove anyone; members can also leave on their own.
+    pub fn remove_member(e: Env, caller: Address, org_id: u32, member: Address) {
+        caller.require_auth();
+
+        let removed = if caller == member {
+            org::remove_member(&e, org_id, &member)
+        } else {
+            org::check_role(&e, org_id, &caller, OrgRole::Admin)
+                .and_then(|_| org::remove_member(&e, org_id, &member))
+        };
+        if let Err(error) = removed {
+            panic_with_error!(&e, error)
+        }
+        event::org_member_removed(&e, org_id, member);
+    }
+
+    pub fn get_org(e: Env, org_id: u32) -> Option<Organization> {
+        org::read_org(&e, org_id)
+    }
+
+    pub fn get_org_role(e: Env, org_id: u32, member: Address) -> Option<OrgRole> {
+        org::read_role(&e, org_id, &member)
+    }
+
+    pub fn get_document_org(e: Env, doc_id: u32) -> Option<u32> {
+        org::read_document_org(&e, doc_id)
+    }
+
+    pub fn get_org_documents(e: Env, org_id: u32, page: u32, limit: u32) -> Vec<u32> {
+        limits::check_batch_size(&e, limit);
+        org::read_documents(&e, org_id, page, limit)
+    }
+
+    pub fn get_collection_documents(e: Env, collection_id: u32, page: u32, limit: u32) -> Vec<u32> {
+        limits::check_batch_size(&e, limit);
+        collection::read_documents(&e, collection_id, page, limit)
+    }
+
+    pub fn is_archived(e: Env, doc_id: u32) -> bool {
+        read_archive(&e, doc_id).is_some()
+    }
+
+    pub fn get_archive(e: Env, doc_id: u32) -> Option<ArchivedDocument> {
+        read_archive(&e, doc_id)
+    }
+
+    // `None` for documents that aren't archived or were archived before
+    // version 4, whose certificate hash has no `witnesses` field.
+    pub fn get_archive_version(e: Env, doc_id: u32) -> Option<u32> {
+        archive::read_archive_version(&e, doc_id)
+    }
+
+    pub fn create_group(e: Env, owner: Address, name: Symbol, signers: Vec<Address>) {
+        owner.require_auth();
+
+        write_group(&e, &owner, name, signers);
+    }
+
+    pub fn get_group(e: Env, name: Symbol) -> Option<SignerGroup> {
+        read_group(&e, name)
+    }
+
+    pub fn clawback_document(e: Env, token_id: u32, to: Address, reason: String) {
+        let admin = require_single_admin(&e);
+
+        let request = Clawback {
+            token_id,
+            to,
+            reason,
+        };
+        clawback::clawback(&e, &admin, request);
+    }
+
+    pub fn get_clawback_reason(e: Env, token_id: u32) -> Option<String> {
+        read_clawback_reason(&e, token_id)
+    }
+
+    pub fn set_voting_token(e: Env, voting_token: Address) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        write_voting_token(&e, &voting_token);
+    }
+
+    pub fn get_voting_token(e: Env) -> Option<Address> {
+        read_voting_token(&e)
+    }
+
+    // Token holders approve or veto a flagged document, e.g. a corporate
+    // resolution. Only the document creator or the admin can open the poll.
+    pub fn create_poll(e: Env, caller: Address, doc_id: u32, end_time: u64) -> Poll {
+        caller.require_auth();
+
+        let creator = match Self::creator_of(&e, doc_id) {
+            Some(creator) => creator,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        if caller != creator && !is_administrator(&e, &caller) {
+            panic_with_error!(&e, Error::NotPollCreator)
+        }
+
+        let poll = poll::create(&e, doc_id, end_time);
+        event::poll_created(&e, doc_id, end_time);
+        poll
+    }
+
+    pub fn vote(e: Env, voter: Address, doc_id: u32, support: bool) -> i128 {
+        voter.require_auth();
+
+        let weight = match poll::vote(&e, &voter, doc_id, support) {
+            Ok(weight) => weight,
+            Err(error) => panic_with_error!(&e, error),
+        };
+        event::voted(&e, doc_id, voter, support, weight);
+        weight
+    }
+
+    pub fn get_poll(e: Env, doc_id: u32) -> Option<Poll> {
+        read_poll(&e, doc_id)
+    }
+
+    pub fn poll_result(e: Env, doc_id: u32) -> PollResult {
+        poll::result(&e, doc_id)
+    }
+
+    pub fn get_event_schema_version(_e: Env) -> u32 {
+        event::EVENT_SCHEMA_VERSION
+    }
+
+    pub fn get_callback(e: Env, doc_id: u32) -> Option<Callback> {
+        read_callback(&e, doc_id)
+    }
+
+    pub fn get_stats(e: Env) -> ContractStats {
+        read_stats(&e)
+    }
+
+    pub fn set_metrics_interval(e: Env, interval: u32) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        stats::write_metrics_interval(&e, interval);
+    }
+
+    pub fn get_metrics_interval(e: Env) -> u32 {
+        stats::read_metrics_interval(&e)
+    }
+
+    pub fn set_grace_period(e: Env, seconds: u64) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        write_default_grace_period(&e, seconds);
+    }
+
+    pub fn get_default_grace_period(e: Env) -> u64 {
+        read_default_grace_period(&e)
+    }
+
+    pub fn get_grace_period(e: Env, doc_id: u32) -> u64 {
+        read_grace_period(&e, doc_id)
+    }
+
+    pub fn get_signer_deadlines(e: Env, doc_id: u32) -> Map<Address, u64> {
+        deadline::read_signer_deadlines(&e, doc_id)
+    }
+
+    // The deadline `signer` has to sign by, their own one when set.
+    pub fn get_signer_deadline(e: Env, doc_id: u32, signer: Address) -> Result<u64, Error> {
+        match deadline::applicable_deadline(&e, doc_id, &signer) {
+            Some((deadline, _)) => Ok(deadline),
+            None => Err(Error::DeadlineNotFound),
+        }
+    }
+
+    // The ledger timestamp every deadline is compared against.
+    pub fn get_time(e: Env) -> u64 {
+        e.ledger().timestamp()
+    }
+
+    // Seconds left before the document expires, counting the grace period
+    // and the earliest deadline of a signer still waiting; negative once it
+    // has passed, saturating at the bounds of `i64`. Documents without a
+    // deadline never expire and report `i64::MAX`.
+    pub fn time_until_deadline(e: Env, doc_id: u32) -> i64 {
+        let deadline = match deadline::earliest_open_deadline(&e, doc_id) {
+            Some((deadline, _)) => deadline,
+            None => match read_deadline(&e, doc_id) {
+                Some(deadline) => deadline,
+                None => return i64::MAX,
+            },
+        };
+        let cutoff = deadline as i128 + read_grace_period(&e, doc_id) as i128;
+        let left = cutoff - e.ledger().timestamp() as i128;
+        left.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    pub fn get_documents_by_status(
+        e: Env,
+        status: DocumentStatus,
+        start: u32,
+        limit: u32,
+    ) -> Vec<u32> {
+        read_tokens_by_status(&e, status, start, limit)
+    }
+
+    pub fn get_document_status(e: Env, doc_id: u32) -> DocumentStatus {
+        let status = read_status(&e, doc_id);
+        if status == DocumentStatus::Pending && Self::deadline_passed(&e, doc_id) {
+            return DocumentStatus::Expired;
+        }
+        status
+    }
+
+    // Walks every document, so it is meant to be simulated rather than
+    // submitted.
+    // Co-owned documents count as owned.
+    pub fn get_dashboard(e: Env, user: Address) -> Dashboard {
+        let mut dashboard = Dashboard {
+            owned: Vec::new(&e),
+            awaiting_signature: Vec::new(&e),
+            signed: Vec::new(&e),
+            status_counts: Map::new(&e),
+        };
+        for token_id in read_token_ids(&e).iter() {
+            let status = Self::get_document_status(e.clone(), token_id);
+            let owns = Self::is_owner(e.clone(), token_id, user.clone());
+            if owns {
+                dashboard.owned.push_back(token_id);
+            }
+            let signature =
+                read_signings(&e, token_id).and_then(|signings| signings.get(user.clone()));
+            match signature {
+                Some(SignatureStatus::Waiting) if status == DocumentStatus::Pending => {
+                    dashboard.awaiting_signature.push_back(token_id)
+                }
+                Some(SignatureStatus::Signed) | Some(SignatureStatus::SignedLate) => {
+                    dashboard.signed.push_back(token_id)
+                }
+                _ => {}
+            }
+            let is_signer =
+                signature.is_some_and(|signature| signature != SignatureStatus::NotASigner);
+            if owns || is_signer {
+                let count = dashboard.status_counts.get(status).unwrap_or(0);
+                dashboard.status_counts.set(status, count + 1);
+            }
+        }
+        dashboard
+    }
+
+    pub fn expire_document(e: Env, doc_id: u32) -> DocumentStatus {
+        if !Self::require_minted(&e, doc_id) {
+            panic_with_error!(&e, Error::TokenNotMinted)
+        }
+        Self::expire_if_due(&e, doc_id)
+    }
+
+    // Looks at up to `limit` pending documents, resuming where the previous
+    // sweep stopped, and expires those past their deadline. Anyone may call
+    // it; `keeper` earns the sweep bounty per expired document it didn't
+    // create itself. Returns how many documents were expired.
+    pub fn sweep_expired(e: Env, keeper: Address, limit: u32) -> u32 {
+        keeper.require_auth();
+        limits::check_batch_size(&e, limit);
+
+        let mut cursor = status::read_sweep_cursor(&e);
+        if cursor >= status::read_index_len(&e, DocumentStatus::Pending) {
+            cursor = 0;
+        }
+        let mut expired = 0;
+        let mut rewarded = 0;
+        for _ in 0..limit {
+            let token_id = match status::read_token_at(&e, DocumentStatus::Pending, cursor) {
+                Some(token_id) => token_id,
+                None => break,
+            };
+            // An expired document leaves the index and the last pending
+            // one takes its slot, so the cursor only moves past survivors.
+            if Self::expire_if_due(&e, token_id) == DocumentStatus::Expired {
+                expired += 1;
+                if Self::creator_of(&e, token_id) != Some(keeper.clone()) {
+                    rewarded += 1;
+                }
+            } else {
+                cursor += 1;
+            }
+        }
+        status::write_sweep_cursor(&e, cursor);
+        bounty::pay(&e, BountyAction::Sweep, &keeper, rewarded);
+        expired
+    }
+
+    // Lets the creator recall a document nobody has acted on yet. Escrow and
+    // the creation fee go back to whoever paid them.
+    pub fn cancel_document(e: Env, doc_id: u32) {
+        let creator = match Self::creator_of(&e, doc_id) {
+            Some(creator) => creator,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        creator.require_auth();
+
+        if Self::expire_if_due(&e, doc_id) != DocumentStatus::Pending {
+            panic_with_error!(&e, Error::DocumentNotPending)
+        }
+        let signings = read_signings(&e, doc_id).unwrap_or(Map::new(&e));
+        if signing::has_recorded_signatures(&signings) {
+            panic_with_error!(&e, ExtendedError::SignaturesRecorded)
+        }
+
+        if read_escrow(&e, doc_id).is_some() {
+            escrow::refund(&e, doc_id);
+        }
+        if let Err(error) = refund_creation_fee(&e, doc_id, &creator) {
+            panic_with_error!(&e, error)
+        }
+
+        write_status(&e, doc_id, DocumentStatus::Cancelled);
+        audit::append(&e, doc_id, AuditAction::Cancelled, &creator);
+        event::cancelled(&e, doc_id, creator);
+        callback::notify(&e, doc_id, DocumentStatus::Cancelled);
+    }
+
+    // Gives an expired document another round of signing. With
+    // `reset_rejected`, rejected signers are asked again, which also lets a
+    // rejected document be reopened.
+    pub fn reopen_document(
+        e: Env,
+        caller: Address,
+        doc_id: u32,
+        new_deadline: u64,
+        reset_rejected: bool,
+    ) {
+        caller.require_auth();
+
+        let creator = match Self::creator_of(&e, doc_id) {
+            Some(creator) => creator,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        if caller != creator && !is_administrator(&e, &caller) {
+            panic_with_error!(&e, ExtendedError::NotDocumentCreator)
+        }
+        let status = Self::expire_if_due(&e, doc_id);
+        if let Err(error) = reopen::check_reopenable(&status, reset_rejected) {
+            panic_with_error!(&e, error)
+        }
+        if let Err(error) = escrow::check_not_refunded(&e, doc_id) {
+            panic_with_error!(&e, error)
+        }
+
+        reopen::reopen(&e, doc_id, new_deadline, reset_rejected);
+        audit::append(&e, doc_id, AuditAction::Reopened, &caller);
+        event::reopened(&e, doc_id, caller, new_deadline, reset_rejected);
+    }
+
+    // Asks a signer who rejected `doc_id` to sign again. Each signer can be
+    // asked `max_rerequests` times. If the admin requires amendments, every
+    // re-request needs one more document linked to this one as `amends`.
+    pub fn rerequest_signature(e: Env, caller: Address, doc_id: u32, signer: Address) -> u32 {
+        caller.require_auth();
+
+        let creator = match Self::creator_of(&e, doc_id) {
+            Some(creator) => creator,
+            None => panic_with_error!(&e, Error::TokenDoesNotExist),
+        };
+        if caller != creator {
+            panic_with_error!(&e, ExtendedError::NotDocumentCreator)
+        }
+        if read_status(&e, doc_id) != DocumentStatus::Rejected {
+            panic_with_error!(&e, ExtendedError::DocumentNotReopenable)
+        }
+        if Self::deadline_passed(&e, doc_id) {
+            panic_with_error!(&e, Error::DeadlinePassed)
+        }
+
+        let count = match rerequest::rerequest(&e, doc_id, &signer) {
+            Ok(count) => count,
+            Err(error) => panic_with_error!(&e, error),
+        };
+        audit::append(&e, doc_id, AuditAction::Rerequested, &caller);
+        event::rerequested(&e, doc_id, signer, caller, count);
+        count
+    }
+
+    pub fn get_rerequest_count(e: Env, doc_id: u32, signer: Address) -> u32 {
+        rerequest::read_rerequest_count(&e, doc_id, signer)
+    }
+
+    // Set `max_rerequests` to 0 to turn re-requests off.
+    pub fn set_rerequest_policy(e: Env, max_rerequests: u32, require_amendment: bool) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        rerequest::write_max_rerequests(&e, max_rerequests);
+        rerequest::write_requires_amendment(&e, require_amendment);
+    }
+
+    // Takes back a signature within the document's revocation window. Only
+    // pending documents qualify: once every signature is in, it stays.
+    pub fn revoke_signature(e: Env, signer: Address, doc_id: u32) {
+        signer.require_auth();
+
+        if !Self::require_minted(&e, doc_id) {
+            panic_with_error!(&e, Error::TokenNotMinted)
+        }
+        if read_status(&e, doc_id) != DocumentStatus::Pending {
+            panic_with_error!(&e, Error::DocumentNotPending)
+        }
+        if let Err(error) = revocation::revoke(&e, doc_id, &signer) {
+            panic_with_error!(&e, error)
+        }
+        audit::append(&e, doc_id, AuditAction::SignatureRevoked, &signer);
+        event::revoked(&e, doc_id, signer);
+    }
+
+    pub fn get_revocation_window(e: Env, doc_id: u32) -> u64 {
+        revocation::read_window(&e, doc_id)
+    }
+
+    // Witnesses also appear in `get_document`, next to the parties.
+    pub fn get_witnesses(e: Env, doc_id: u32) -> Vec<Address> {
+        witness::read_witnesses(&e, doc_id)
+    }
+
+    // Completed documents can't change hands for `ledgers` ledgers. Only
+    // documents completed afterwards are affected.
+    pub fn set_transfer_lockup(e: Env, ledgers: u32) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        lockup::write_lockup_ledgers(&e, ledgers);
+    }
+
+    pub fn get_transfer_lockup(e: Env) -> u32 {
+        lockup::read_lockup_ledgers(&e)
+    }
+
+    // First ledger the document's token can be transferred in, if it
+    // completed under a lock-up.
+    pub fn transferable_at(e: Env, doc_id: u32) -> Option<u32> {
+        lockup::read_transferable_at(&e, doc_id)
+    }
+
+    pub fn get_escrow(e: Env, doc_id: u32) -> Option<Escrow> {
+        read_escrow(&e, doc_id)
+    }
+
+    pub fn claim_escrow(e: Env, doc_id: u32) -> Escrow {
+        if read_status(&e, doc_id) != DocumentStatus::Completed {
+            panic_with_error!(&e, Error::EscrowNotReleasable)
+        }
+        escrow::release(&e, doc_id)
+    }
+
+    // A voided document can't complete any more, so its escrow goes back
+    // like that of an expired or rejected one. Clawbacks refund on their
+    // own; documents clawed back before that can still be refunded here.
+    pub fn refund_escrow(e: Env, doc_id: u32) -> Escrow {
+        let status = Self::expire_if_due(&e, doc_id);
+        if !matches!(
+            status,
+            DocumentStatus::Expired
+                | DocumentStatus::Rejected
+                | DocumentStatus::Voided
+                | DocumentStatus::ClawedBack
+        ) {
+            panic_with_error!(&e, Error::EscrowNotRefundable)
+        }
+        escrow::refund(&e, doc_id)
+    }
+
+    // The keeper earns the reminder bounty only when someone was actually
+    // waiting, never for their own document, and only for the first few
+    // reminders of each document.
+    pub fn emit_reminder(e: Env, keeper: Address, doc_id: u32) -> Result<Vec<Address>, Error> {
+        keeper.require_auth();
+        if read_status(&e, doc_id) != DocumentStatus::Pending {
+            return Err(Error::DocumentNotPending);
+        }
+        let remind_at = read_reminder(&e, doc_id).ok_or(Error::ReminderNotSet)?;
+        if e.ledger().timestamp() < remind_at {
+            return Err(Error::ReminderNotDue);
+        }
+        if Self::deadline_passed(&e, doc_id) {
+            return Err(Error::DeadlinePassed);
+        }
+        mark_reminder_sent(&e, doc_id)?;
+
+        let waiting_signers = Self::get_pending_signers(e.clone(), doc_id);
+        event::reminder(&e, doc_id, waiting_signers.clone());
+        if !waiting_signers.is_empty()
+            && Self::creator_of(&e, doc_id) != Some(keeper.clone())
+            && reminder::take_bounty_slot(&e, doc_id)
+        {
+            bounty::pay(&e, BountyAction::Reminder, &keeper, 1);
+        }
+        Ok(waiting_signers)
+    }
+
+    pub fn get_pending_signers(e: Env, doc_id: u32) -> Vec<Address> {
+        let mut waiting_signers: Vec<Address> = Vec::new(&e);
+        for (signer, status) in Self::get_document(e.clone(), doc_id).iter() {
+            if status == SignatureStatus::Waiting {
+                waiting_signers.push_back(signer);
+            }
+        }
+        waiting_signers
+    }
+
+    // (signed, total). Late signatures count as signed.
+    pub fn get_signed_count(e: Env, doc_id: u32) -> (u32, u32) {
+        let mut signed = 0;
+        let mut total = 0;
+        for (_, status) in Self::get_document(e, doc_id).iter() {
+            match status {
+                SignatureStatus::NotASigner => continue,
+                SignatureStatus::Signed | SignatureStatus::SignedLate => signed += 1,
+                _ => {}
+            }
+            total += 1;
+        }
+        (signed, total)
+    }
+
+    pub fn get_audit_log(e: Env, doc_id: u32, start: u32, limit: u32) -> Vec<AuditEntry> {
+        read_audit_log(&e, doc_id, start, limit)
+    }
+
+    pub fn get_reminder(e: Env, doc_id: u32) -> Option<u64> {
+        read_reminder(&e, doc_id)
+    }
+
+    pub fn set_reminder_interval(e: Env, ledgers: u32) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        write_reminder_interval(&e, ledgers);
+    }
+
+    pub fn get_reminder_interval(e: Env) -> u32 {
+        read_reminder_interval(&e)
+    }
+
+    // Includes the grace period: a document is not expired while late
+    // signatures are still accepted.
+    fn deadline_passed(e: &Env, token_id: u32) -> bool {
+        match deadline::earliest_open_deadline(e, token_id) {
+            Some((deadline, _)) => {
+                e.ledger().timestamp() > deadline.saturating_add(read_grace_period(e, token_id))
+            }
+            None => false,
+        }
+    }
+
+    fn expire_if_due(e: &Env, token_id: u32) -> DocumentStatus {
+        let status = read_status(e, token_id);
+        if status != DocumentStatus::Pending || !Self::deadline_passed(e, token_id) {
+            return status;
+        }
+        let (deadline, kind) =
+            deadline::earliest_open_deadline(e, token_id).unwrap_or((0, DeadlineKind::Document));
+        write_status(e, token_id, DocumentStatus::Expired);
+        stats::record_expired(e);
+        event::expired(e, token_id, deadline, kind);
+        callback::notify(e, token_id, DocumentStatus::Expired);
+        DocumentStatus::Expired
+    }
+
+    fn mint(e: &Env, token_id: u32, to: Address) {
+        // New Token id should be incremented by 1 and not injected as param.
+
+        if read_owner(e, token_id).is_some() {
+            panic_with_error!(&e, Error::TokenAlreadyMinted)
+        }
+        let cloned_to = to.clone();
+
+        write_owner(e, token_id, &to);
+        log!(&e, "Owner set {}", to);
+
+        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
+        event::mint(&e, &cloned_to, token_id);
+    }
+
+    fn set_token_uri(e: &Env, token_id: u32, token_uri: String) {
+        limits::check_uri(e, &token_uri);
+        if read_owner(e, token_id).is_none() {
+            panic_with_error!(&e, Error::TokenDoesNotExist)
+        }
+
+        write_uri(e, token_id, &token_uri);
+        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
+    }
+
+    // The admin is never limited.
+    fn check_rate_limit(e: &Env, action: RateAction, addr: &Address) {
+        if is_administrator(e, addr) {
+            return;
+        }
+        if let Err(error) = ratelimit::hit(e, action, addr) {
+            panic_with_error!(e, error)
+        }
+    }
+
+    // Documents minted before creators were recorded fall back to their
+    // current owner.
+    fn creator_of(e: &Env, token_id: u32) -> Option<Address> {
+        read_creator(e, token_id).or_else(|| Self::owner_of(e, token_id))
+    }
+
+    fn owner_of(e: &Env, token_id: u32) -> Option<Address> {
+        match read_token_contract(e) {
+            Some(token_contract) => token_contract::owner_of(e, &token_contract, token_id),
+            None => read_owner(e, token_id),
+        }
+    }
+
+    fn require_minted(e: &Env, token_id: u32) -> bool {
+        if let Some(token_contract) = read_token_contract(e) {
+            return token_contract::require_minted(e, &token_contract, token_id);
+        }
+        read_owner(e, token_id).is_some()
+    }
+
+    pub fn set_test_int(e: Env) {
+        let key = ConfigKey::TestInt;
+        let test_int: u32 = e.storage().persistent().get(&key).unwrap_or(0);
+        let bump: u32 = test_int + 1;
+        e.storage().persistent().set(&key, &bump);
+    }
+
+    pub fn get_test_int(e: Env) -> u32 {
+        let test_int: u32 = e
+            .storage()
+            .persistent()
+            .get(&ConfigKey::TestInt)
+            .unwrap_or(0);
+        test_int
+    }
+
+    pub fn set_fee_token(e: Env, fee_token: Address) {
+        let admin = require_single_admin(&e);
+
+        bounty::settle_legacy_pool(&e);
+        write_fee_token(&e, &fee_token);
+        event::set_fee_token(&e, admin, fee_token);
+    }
+
+    pub fn get_fee_token(e: Env) -> Option<Address> {
+        read_fee_token(&e)
+    }
+
+    pub fn set_creation_fee(e: Env, fee: i128) {
+        let admin = require_single_admin(&e);
+
+        write_creation_fee(&e, fee);
+        event::set_creation_fee(&e, admin, fee);
+    }
+
+    pub fn get_creation_fee(e: Env) -> i128 {
+        read_creation_fee(&e)
+    }
+
+    // Switches fees to native XLM through its Stellar Asset Contract.
+    pub fn set_native_fee_token(e: Env) -> Address {
+        let admin = require_single_admin(&e);
+
+        let fee_token = fee::native_fee_token(&e);
+        write_fee_token(&e, &fee_token);
+        event::set_fee_token(&e, admin, fee_token.clone());
+        fee_token
+    }
+
+    // Like `set_creation_fee`, in whole XLM rather than stroops.
+    pub fn set_creation_fee_xlm(e: Env, xlm: i128) {
+        let admin = require_single_admin(&e);
+        if let Err(error) = fee::check_native(&e) {
+            panic_with_error!(&e, error)
+        }
+        let fee = match fee::xlm_to_stroops(xlm) {
+            Ok(fee) => fee,
+            Err(error) => panic_with_error!(&e, error),
+        };
+
+        write_creation_fee(&e, fee);
+        event::set_creation_fee(&e, admin, fee);
+    }
+
+    // None unless fees are paid in native XLM.
+    pub fn get_native_fee_quote(e: Env) -> Option<NativeFeeQuote> {
+        if !fee::is_native_fee_token(&e) {
+            return None;
+        }
+        let stroops = read_creation_fee(&e);
+        Some(NativeFeeQuote {
+            fee_token: fee::native_fee_token(&e),
+            stroops,
+            xlm: stroops / fee::STROOPS_PER_XLM,
+            remainder_stroops: stroops % fee::STROOPS_PER_XLM,
+        })
+    }
+
+    // Fees per tier; see `set_fee_tier`. Addresses outside every tier pay
+    // the public creation fee.
+    pub fn set_fee_schedule(e: Env, schedule: Map<u32, i128>) {
+        require_single_admin(&e);
+
+        fee::write_fee_schedule(&e, &schedule);
+    }
+
+    pub fn get_fee_schedule(e: Env) -> Map<u32, i128> {
+        fee::read_fee_schedule(&e)
+    }
+
+    // `None` moves `addr` back to the public fee.
+    pub fn set_fee_tier(e: Env, addr: Address, tier: Option<u32>) {
+        require_single_admin(&e);
+
+        fee::write_fee_tier(&e, &addr, tier);
+    }
+
+    pub fn get_fee_tier(e: Env, addr: Address) -> Option<u32> {
+        fee::read_fee_tier(&e, &addr)
+    }
+
+    pub fn get_creation_fee_for(e: Env, addr: Address) -> i128 {
+        fee::creation_fee_for(&e, &addr)
+    }
+
+    // Holders of the discount token, or of the token contract if none is
+    // set, pay less; the highest discount they qualify for applies.
+    pub fn set_fee_discounts(e: Env, discounts: Vec<FeeDiscount>) {
+        require_single_admin(&e);
+
+        if let Err(error) = fee::check_fee_discounts(&discounts) {
+            panic_with_error!(&e, error)
+        }
+        fee::write_fee_discounts(&e, &discounts);
+    }
+
+    pub fn get_fee_discounts(e: Env) -> Vec<FeeDiscount> {
+        fee::read_fee_discounts(&e)
+    }
+
+    pub fn set_discount_token(e: Env, discount_token: Address) {
+        require_single_admin(&e);
+
+        fee::write_discount_token(&e, &discount_token);
+    }
+
+    pub fn get_discount_token(e: Env) -> Option<Address> {
+        fee::read_discount_token(&e)
+    }
+
+    // Moves `amount` of the fee token from the admin into the keeper bounty
+    // pool for that token.
+    pub fn fund_bounty_pool(e: Env, amount: i128) {
+        let admin = require_single_admin(&e);
+
+        bounty::fund(&e, &admin, amount);
+    }
 
-mod event;
+    // The pool in the current fee token, which is the one bounties are paid
+    // from.
+    pub fn get_bounty_pool(e: Env) -> i128 {
+        match read_fee_token(&e) {
+            Some(fee_token) => bounty::read_pool(&e, &fee_token),
+            None => 0,
+        }
+    }
 
-mod admin;
-use crate::admin::{has_administrator, read_administrator, write_administrator};
+    pub fn get_bounty_pool_of(e: Env, token: Address) -> i128 {
+        bounty::read_pool(&e, &token)
+    }
 
-use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, log, panic_with_error, symbol_short,
-    Address, Env, Map, String, Symbol, Vec,
-};
+    pub fn set_bounty_reward(e: Env, action: BountyAction, reward: i128) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-// mod erc721 {
-//     soroban_sdk::contractimport!(
-//         file = "../token/target/wasm32-unknown-unknown/release/soroban_token_contract.wasm"
-//     );
-// }
+        bounty::write_reward(&e, action, reward);
+    }
 
-#[contract]
-pub struct PetalDocuments;
+    pub fn get_bounty_reward(e: Env, action: BountyAction) -> i128 {
+        bounty::read_reward(&e, action)
+    }
 
-#[contracterror]
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
-#[repr(u32)]
-pub enum Error {
-    TokenNotMinted = 1,
-    DocumentSigningsIsEmpty = 2,
-    NotASigner = 3,
-    AlreadySigned = 4,
-    SignerDoesNotExist = 5,
-    DocumentHashesIsEmpty = 6,
-    DocumentHashesDoesNotMatchTokenHash = 7,
-    HashNotFound = 8,
-    DeadlinesIsEmpty = 9,
-    DeadlinePassed = 10,
-    DeadlineNotFound = 11,
-    SignatureExpired = 12,
-    TokenAlreadyMinted = 13,
-    TokenDoesNotExist = 14,
-    SignersListEmpty = 15,
-}
+    pub fn get_keeper_earnings(e: Env, keeper: Address) -> i128 {
+        bounty::read_earnings(&e, &keeper)
+    }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-#[contracttype]
-pub enum SignatureStatus {
-    NotASigner,
-    Rejected,
-    Signed,
-    Waiting,
-}
+    // Prepays `credits` mints at the buyer's current creation fee. Minting
+    // uses a credit instead of charging the fee while any are left.
+    pub fn buy_credits(e: Env, buyer: Address, credits: u32) -> i128 {
+        buyer.require_auth();
+        credit::buy(&e, &buyer, credits)
+    }
 
-#[derive(Clone, Debug)]
-#[contracttype]
-pub struct SignedMessage {
-    pub deadline: u64,
-    pub description: String,
-    pub document_hash: String,
-    pub document_uri: String,
-    pub signer: Address,
-    pub status: SignatureStatus,
-    pub token_id: u32,
-    pub nonce: u32,
-}
+    // Promotional credits, free of charge.
+    pub fn grant_credits(e: Env, to: Address, credits: u32) {
+        require_single_admin(&e);
 
-const OWNERS: Symbol = symbol_short!("OWNERS");
-const URIS: Symbol = symbol_short!("URIS");
+        credit::grant(&e, &to, credits);
+    }
 
-const NONCES: Symbol = symbol_short!("NONCES");
-const T2DHASH: Symbol = symbol_short!("T2DHASH");
-const DEADLINES: Symbol = symbol_short!("DEADLINES");
-const DOCSIGN: Symbol = symbol_short!("DOCSIGN");
-const CREACTION_FEE: Symbol = symbol_short!("crea_fee");
+    pub fn get_credits(e: Env, addr: Address) -> u32 {
+        credit::read_credits(&e, &addr)
+    }
 
-const TEST: Symbol = symbol_short!("TEST");
+    // Basis points of each referred creation fee credited to the referrer.
+    pub fn set_referral_share(e: Env, bps: u32) {
+        require_single_admin(&e);
 
-#[contractimpl]
-impl PetalDocuments {
-    pub fn init(e: Env, admin: Address, token_id: u32) {
-        if has_administrator(&e) {
-            panic!("already initialized")
+        if let Err(error) = referral::write_share_bps(&e, bps) {
+            panic_with_error!(&e, error)
         }
+    }
 
-        write_administrator(&e, &admin);
+    pub fn get_referral_share(e: Env) -> u32 {
+        referral::read_share_bps(&e)
     }
 
-    pub fn sign_document(
-        e: Env,
-        document_hash: String,
-        signer: Address,
-        status: SignatureStatus,
-        token_id: u32,
-    ) -> Map<u32, Map<Address, SignatureStatus>> {
-        // let client = erc721::Client::new(&e, &erc721_address);
-        // let is_token_minted: bool = client.require_minted(&payload.token_id);
-        let is_token_minted: bool = Self::require_minted(&e, token_id);
-        if is_token_minted == false {
-            panic_with_error!(&e, Error::TokenNotMinted)
+    pub fn get_referral_account(e: Env, referrer: Address, fee_token: Address) -> ReferralAccount {
+        referral::read_account(&e, &referrer, &fee_token)
+    }
+
+    pub fn get_referral_earnings(e: Env, referrer: Address, fee_token: Address) -> i128 {
+        referral::claimable(&referral::read_account(&e, &referrer, &fee_token))
+    }
+
+    // Pays out everything credited to `referrer` so far in `fee_token`.
+    pub fn claim_referral_earnings(e: Env, referrer: Address, fee_token: Address) -> i128 {
+        referrer.require_auth();
+        referral::claim(&e, &referrer, &fee_token)
+    }
+
+    // Only collected fees can be withdrawn. Escrow deposits, the bounty pool
+    // and unclaimed referral shares held in the same token stay put.
+    pub fn withdraw_fees(e: Env, to: Address, amount: i128) {
+        require_single_admin(&e);
+
+        if let Err(error) = fee::withdraw(&e, &to, amount) {
+            panic_with_error!(&e, error)
         }
-        let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
-        if doc_signings.is_empty() {
-            panic_with_error!(&e, Error::DocumentSigningsIsEmpty)
-        }
-
-        let clone_signer = signer.clone();
-        let all_signings = doc_signings.get(token_id);
-        let signer_status: SignatureStatus = match all_signings {
-            Some(signing) => {
-                let is_signer = signing.get(signer);
-                match is_signer {
-                    Some(signer) => {
-                        if signer == SignatureStatus::NotASigner {
-                            panic_with_error!(&e, Error::NotASigner)
-                        } else if signer == SignatureStatus::Signed {
-                            panic_with_error!(&e, Error::AlreadySigned)
-                        }
-                        signer
-                    }
-                    None => {
-                        panic_with_error!(&e, Error::SignerDoesNotExist)
-                    }
-                }
-            }
-            None => {
-                panic_with_error!(&e, Error::DocumentSigningsIsEmpty)
-            }
-        };
+    }
 
-        let token_to_doc_hashes: Map<u32, String> = e
-            .storage()
-            .persistent()
-            .get(&T2DHASH)
-            .unwrap_or(Map::new(&e));
-        if token_to_doc_hashes.is_empty() {
-            panic_with_error!(&e, Error::DocumentHashesIsEmpty)
+    // Fees collected in the current fee token and not yet withdrawn.
+    pub fn get_collected_fees(e: Env) -> i128 {
+        match read_fee_token(&e) {
+            Some(fee_token) => fee::read_collected(&e, &fee_token),
+            None => 0,
         }
+    }
 
-        let doc_hash = token_to_doc_hashes.get(token_id);
-        let matched_hash = match doc_hash {
-            Some(hash) => {
-                if (hash != document_hash) {
-                    panic_with_error!(&e, Error::DocumentHashesDoesNotMatchTokenHash)
-                }
-                hash
-            }
-            None => {
-                panic_with_error!(&e, Error::HashNotFound)
-            }
-        };
+    pub fn migrate(e: Env, batch_size: u32) -> MigrationStatus {
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        let doc_signing_deadlines: Map<u32, u64> = e
-            .storage()
-            .persistent()
-            .get(&DEADLINES)
-            .unwrap_or(Map::new(&e));
-        if doc_signing_deadlines.is_empty() {
-            panic_with_error!(&e, Error::DeadlinesIsEmpty)
-        }
-        let deadlines: Option<u64> = doc_signing_deadlines.get(token_id);
-        let deadline: u64 = match deadlines {
-            Some(v) => {
-                if e.ledger().timestamp() > v {
-                    panic_with_error!(&e, Error::DeadlinePassed)
+        migration::migrate(&e, batch_size)
+    }
+
+    pub fn migration_status(e: Env) -> MigrationStatus {
+        migration_status(&e)
+    }
+
+    // Recreates documents exported from another deployment under their
+    // original token ids, signatures included. Only allowed until `seal`.
+    pub fn import_state(e: Env, chunk: StateChunk) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+        if Self::is_sealed(e.clone()) {
+            panic_with_error!(&e, ExtendedError::ContractSealed)
+        }
+        limits::check_batch_size(&e, chunk.documents.len());
+
+        for state in chunk.documents.iter() {
+            let token_id = state.token_id;
+            document::check_unique_hash(&e, token_id, &state.document_hash);
+            match read_token_contract(&e) {
+                Some(token_contract) => {
+                    token_contract::mint(&e, &token_contract, token_id, &state.owner, &state.uri);
+                }
+                None => {
+                    Self::mint(&e, token_id, state.owner.clone());
+                    Self::set_token_uri(&e, token_id, state.uri);
                 }
-                v
             }
-            None => {
-                panic_with_error!(&e, Error::DeadlineNotFound)
+            write_creator(&e, token_id, &state.creator);
+            write_document_hash(&e, token_id, &state.document_hash);
+            limits::check_hash_algo(&e, state.hash_algo, &state.document_hash);
+            document::write_hash_algo(&e, token_id, state.hash_algo);
+            for extra in state.extra_hashes.iter() {
+                limits::check_hash_algo(&e, extra.algo, &extra.hash);
+                document::check_unique_hash(&e, token_id, &extra.hash);
+                if let Err(error) = document::add_extra_hash(&e, token_id, &extra) {
+                    panic_with_error!(&e, error)
+                }
             }
-        };
+            write_deadline(&e, token_id, state.deadline);
+            write_signings(&e, token_id, &state.signings);
+            add_to_index(&e, token_id);
+            write_status(&e, token_id, state.status);
+            stats::record_mint(&e);
+        }
+    }
 
-        let clone_signer_2 = clone_signer.clone();
-        Self::verify_signer(&e, clone_signer, token_id);
+    // Ends the import window for good.
+    pub fn seal(e: Env) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        if e.ledger().timestamp() > deadline {
-            panic_with_error!(&e, Error::SignatureExpired)
-        };
+        e.storage().instance().set(&ConfigKey::Sealed, &true);
+    }
 
-        let clone_signer_3 = clone_signer_2.clone();
-        let clone_signer_4 = clone_signer_3.clone();
-        let mut signature_nonces: Map<Address, u32> = e
-            .storage()
-            .persistent()
-            .get(&NONCES)
-            .unwrap_or(Map::new(&e));
-        let last_nonce = signature_nonces.get(clone_signer_4).unwrap_or(0);
-        if signature_nonces.is_empty() {
-            signature_nonces.set(clone_signer_2, last_nonce);
-        } else {
-            signature_nonces.set(clone_signer_2, last_nonce + 1);
+    pub fn is_sealed(e: Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&ConfigKey::Sealed)
+            .unwrap_or(false)
+    }
+
+    // Pages through every document in mint order, starting at index
+    // `cursor`. Legacy documents have to be migrated first.
+    pub fn export_state(e: Env, cursor: u32, limit: u32) -> StateChunk {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+        limits::check_batch_size(&e, limit);
+        if !migration_status(&e).complete {
+            panic_with_error!(&e, ExtendedError::MigrationPending)
+        }
+
+        let total = document::read_token_count(&e);
+        let end = total.min(cursor.saturating_add(limit));
+        let mut documents = Vec::new(&e);
+        for index in cursor..end {
+            let token_id = match document::read_token_at(&e, index) {
+                Some(token_id) => token_id,
+                None => continue,
+            };
+            let owner = match Self::owner_of(&e, token_id) {
+                Some(owner) => owner,
+                None => continue,
+            };
+            documents.push_back(DocumentState {
+                token_id,
+                creator: Self::creator_of(&e, token_id).unwrap_or(owner.clone()),
+                owner,
+                uri: Self::get_token_uri(e.clone(), token_id),
+                document_hash: read_document_hash(&e, token_id).unwrap_or(String::from_str(&e, "")),
+                hash_algo: document::read_hash_algo(&e, token_id),
+                extra_hashes: document::read_extra_hashes(&e, token_id),
+                deadline: read_deadline(&e, token_id).unwrap_or(0),
+                status: read_status(&e, token_id),
+                signings: read_signings(&e, token_id).unwrap_or(Map::new(&e)),
+            });
+        }
+        StateChunk {
+            documents,
+            next_cursor: end,
+            total,
         }
-        let status_copy = status.clone();
-        // doc_signings.get(token_id).unwrap().set(clone_signer_3, status);
-        let mut inner_signings: Map<Address, SignatureStatus> = doc_signings.get(token_id).unwrap();
-        // inner_signings.set(clone_signer_3, SignatureStatus::Signed);
-        inner_signings.set(clone_signer_3, status);
-        doc_signings.set(token_id, inner_signings);
+    }
 
-        e.storage().persistent().set(&DOCSIGN, &doc_signings);
-        // e.storage().persistent().bump(34560);
+    // e.g. `ipfs://` or `https://docs.example.com/`. An empty list accepts
+    // any URI.
+    pub fn set_uri_prefixes(e: Env, prefixes: Vec<String>) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        doc_signings
+        write_uri_prefixes(&e, &prefixes);
     }
 
-    fn verify_signer(e: &Env, signer: Address, token_id: u32) {
-        signer.require_auth();
+    pub fn get_uri_prefixes(e: Env) -> Vec<String> {
+        read_uri_prefixes(&e)
+    }
 
-        let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
-        let mut inner_doc_signings: Map<Address, SignatureStatus> =
-            doc_signings.get(token_id).unwrap();
-        let mut current_signature_status: SignatureStatus = inner_doc_signings.get(signer).unwrap();
+    pub fn set_attestor(e: Env, attestor: Address, enabled: bool) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        if (current_signature_status != SignatureStatus::Waiting) {
-            panic_with_error!(&e, Error::AlreadySigned)
-        }
+        write_attestor(&e, &attestor, enabled);
     }
 
-    pub fn safe_mint(
-        e: Env,
-        to: Address,
-        token_id: u32,
-        meta_uri: String,
-        signers: Vec<Address>,
-        document_hash: String,
-        deadline: u64,
-    ) -> u32 {
-        // IMPLEMENT THIS LIKE IN SOLIDITY PETAL DOCUMENTS CONTRACT
-        //		require(
-        // 	msg.value >= creationFee || owner() == msg.sender,
-        // 	'Creation fee not met'
-        // );
+    pub fn is_attestor(e: Env, attestor: Address) -> bool {
+        attestation::is_attestor(&e, &attestor)
+    }
 
-        if signers.is_empty() {
-            panic_with_error!(&e, Error::SignersListEmpty)
-        }
-        // let client = erc721::Client::new(&e, &erc721_address);
-        // client.mint(&token_id, &to);
-        // client.set_token_uri(&token_id, &meta_uri);
+    // Marks `id` as verified (e.g. KYC) at `level`; 0 revokes it.
+    pub fn attest(e: Env, attestor: Address, id: Address, level: u32) {
+        attestor.require_auth();
 
-        Self::mint(&e, token_id, to);
-        Self::set_token_uri(&e, token_id, meta_uri);
+        attestation::attest(&e, &attestor, &id, level);
+    }
 
-        let mut token_to_doc_hashes: Map<u32, String> = e
-            .storage()
-            .persistent()
-            .get(&T2DHASH)
-            .unwrap_or(Map::new(&e));
-        token_to_doc_hashes.set(token_id, document_hash);
+    pub fn get_attestation(e: Env, id: Address) -> u32 {
+        read_attestation(&e, &id)
+    }
 
-        let mut doc_signing_deadlines: Map<u32, u64> = e
-            .storage()
-            .persistent()
-            .get(&DEADLINES)
-            .unwrap_or(Map::new(&e));
-        doc_signing_deadlines.set(token_id, deadline);
+    pub fn get_required_attestation(e: Env, doc_id: u32) -> u32 {
+        read_required_level(&e, doc_id)
+    }
 
-        let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
-        let mut inner_doc_signings: Map<Address, SignatureStatus> = Map::new(&e);
+    pub fn set_string_limits(e: Env, limits: StringLimits) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        for signer in signers.iter() {
-            inner_doc_signings.set(signer, SignatureStatus::Waiting);
-        }
-        doc_signings.set(token_id, inner_doc_signings);
+        write_string_limits(&e, &limits);
+    }
 
-        e.storage().persistent().set(&T2DHASH, &token_to_doc_hashes);
-        e.storage()
-            .persistent()
-            .set(&DEADLINES, &doc_signing_deadlines);
-        e.storage().persistent().set(&DOCSIGN, &doc_signings);
+    pub fn get_string_limits(e: Env) -> StringLimits {
+        read_string_limits(&e)
+    }
 
-        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
-        token_id
+    // `None` removes the limit for `action`.
+    pub fn set_rate_limit(e: Env, action: RateAction, limit: Option<RateLimit>) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        ratelimit::write_rate_limit(&e, action, limit);
     }
 
-    fn mint(e: &Env, token_id: u32, to: Address) {
-        // New Token id should be incremented by 1 and not injected as param.
+    pub fn get_rate_limit(e: Env, action: RateAction) -> Option<RateLimit> {
+        ratelimit::read_rate_limit(&e, action)
+    }
 
-        let mut owners: Map<u32, Address> = e
-            .storage()
-            .persistent()
-            .get(&OWNERS)
-            .unwrap_or(Map::new(&e));
-        if exists(&e, token_id, &owners) == true {
-            panic_with_error!(&e, Error::TokenAlreadyMinted)
-        }
-        let cloned_to = to.clone();
+    // With the allowlist on, only listed minters and the admin may call
+    // `safe_mint`.
+    pub fn set_minter_allowlist(e: Env, enabled: bool) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        minters::write_allowlist_enabled(&e, enabled);
+        event::set_minter_allowlist(&e, admin, enabled);
+    }
 
-        owners.set(token_id, to);
-        log!(&e, "Owners set locally {}", owners);
+    pub fn is_minter_allowlist_enabled(e: Env) -> bool {
+        minters::read_allowlist_enabled(&e)
+    }
 
-        e.storage().persistent().set(&OWNERS, &owners);
-        log!(&e, "Owners set instance {}", owners);
+    pub fn add_minter(e: Env, minter: Address) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
-        event::mint(&e, &cloned_to, token_id);
+        minters::add_minter(&e, &minter);
+        event::minter_added(&e, admin, minter);
     }
 
-    fn set_token_uri(e: &Env, token_id: u32, token_uri: String) {
-        let owners: Map<u32, Address> = e
-            .storage()
-            .persistent()
-            .get(&OWNERS)
-            .unwrap_or(Map::new(&e));
+    pub fn remove_minter(e: Env, minter: Address) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        if exists(&e, token_id, &owners) == false {
-            panic_with_error!(&e, Error::TokenDoesNotExist)
-        }
+        minters::remove_minter(&e, &minter);
+        event::minter_removed(&e, admin, minter);
+    }
 
-        let mut token_uris: Map<u32, String> =
-            e.storage().persistent().get(&URIS).unwrap_or(Map::new(&e));
-        token_uris.set(token_id, token_uri);
+    pub fn is_minter(e: Env, minter: Address) -> bool {
+        minters::is_minter(&e, &minter)
+    }
 
-        e.storage().persistent().set(&URIS, &token_uris);
-        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
+    pub fn get_token_contract(e: Env) -> Option<Address> {
+        read_token_contract(&e)
     }
 
-    fn require_minted(e: &Env, token_id: u32) -> bool {
-        let owners: Map<u32, Address> = e
-            .storage()
-            .persistent()
-            .get(&OWNERS)
-            .unwrap_or(Map::new(&e));
-        if exists(&e, token_id, &owners) == true {
-            return true;
-        }
-        return false;
+    pub fn propose_action(e: Env, proposer: Address, action: ProposalAction) -> u32 {
+        proposer.require_auth();
+
+        proposal::propose(&e, &proposer, action)
     }
 
-    pub fn set_test_int(e: Env) {
-        let test_int: u32 = e.storage().persistent().get(&TEST).unwrap_or(0);
-        let bump: u32 = test_int + 1;
-        e.storage().persistent().set(&TEST, &bump);
+    pub fn approve_action(e: Env, admin: Address, proposal_id: u32) -> bool {
+        admin.require_auth();
+
+        proposal::approve(&e, &admin, proposal_id)
     }
 
-    pub fn get_test_int(e: Env) -> u32 {
-        let test_int: u32 = e.storage().persistent().get(&TEST).unwrap_or(0);
-        test_int
+    pub fn get_proposal(e: Env, proposal_id: u32) -> Option<Proposal> {
+        read_proposal(&e, proposal_id)
+    }
+
+    pub fn get_admins(e: Env) -> Vec<Address> {
+        read_admins(&e)
+    }
+
+    pub fn get_threshold(e: Env) -> u32 {
+        read_threshold(&e)
     }
 
     pub fn get_admin(e: Env) -> Address {
@@ -361,76 +2835,148 @@ impl PetalDocuments {
         admin
     }
 
+    pub fn has_admin(e: Env) -> bool {
+        has_administrator(&e)
+    }
+
+    pub fn set_admin_renounceable(e: Env, enabled: bool) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        admin::write_renounceable(&e, enabled);
+    }
+
+    pub fn is_admin_renounceable(e: Env) -> bool {
+        admin::read_renounceable(&e)
+    }
+
+    pub fn request_renounce_admin(e: Env) {
+        let admin = admin::request_renounce(&e);
+        event::renounce_requested(&e, admin);
+    }
+
+    pub fn cancel_renounce_admin(e: Env) {
+        let admin = admin::cancel_renounce(&e);
+        event::renounce_cancelled(&e, admin);
+    }
+
+    // Confirms a request made in an earlier ledger and leaves the contract
+    // without an admin for good: upgrades, fees and every other admin-gated
+    // entrypoint fail with `AdminRenounced` afterwards.
+    pub fn renounce_admin(e: Env) {
+        let admin = admin::renounce(&e);
+        event::renounce_admin(&e, admin);
+    }
+
+    pub fn is_admin_renounced(e: Env) -> bool {
+        is_renounced(&e)
+    }
+
+    // The nonce `sign` expects next from `user`.
     pub fn get_nonces(e: Env, user: Address) -> u32 {
-        let nonces: Map<Address, u32> = e
-            .storage()
-            .persistent()
-            .get(&NONCES)
-            .unwrap_or(Map::new(&e));
-        let user_nonce = nonces.get(user).unwrap_or(0);
-        // e.storage().persistent().bump(INSTANCE_BUMP_AMOUNT_LOW_WATERMARK, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK);
-        user_nonce
+        signing::read_nonce(&e, &user)
     }
 
     pub fn get_owners(e: Env) -> Map<u32, Address> {
-        let owners: Map<u32, Address> = e
-            .storage()
-            .persistent()
-            .get(&OWNERS)
-            .unwrap_or(Map::new(&e));
+        if let Some(token_contract) = read_token_contract(&e) {
+            return token_contract::owners(&e, &token_contract);
+        }
+        let mut owners: Map<u32, Address> = Map::new(&e);
+        for token_id in read_token_ids(&e).iter() {
+            if let Some(owner) = read_owner(&e, token_id) {
+                owners.set(token_id, owner);
+            }
+        }
         owners
     }
 
+    // (token id, owner) pairs in ascending token id, starting at the first id
+    // not below `cursor`. The next page starts at the last id returned plus
+    // one, so documents minted in between never shift a page already read.
+    pub fn get_owners_sorted(e: Env, cursor: u32, limit: u32) -> Vec<(u32, Address)> {
+        limits::check_batch_size(&e, limit);
+        let mut page = Vec::new(&e);
+        // Map keys iterate in ascending order.
+        for (token_id, owner) in Self::get_owners(e.clone()).iter() {
+            if page.len() >= limit {
+                break;
+            }
+            if token_id >= cursor {
+                page.push_back((token_id, owner));
+            }
+        }
+        page
+    }
+
     pub fn get_token_uris(e: Env) -> Map<u32, String> {
-        let token_uris: Map<u32, String> =
-            e.storage().persistent().get(&URIS).unwrap_or(Map::new(&e));
+        let mut token_uris: Map<u32, String> = Map::new(&e);
+        for token_id in read_token_ids(&e).iter() {
+            if let Some(uri) = read_uri(&e, token_id) {
+                token_uris.set(token_id, uri);
+            }
+        }
         token_uris
     }
 
     pub fn get_token_uri(e: Env, doc_id: u32) -> String {
-        let token_uris: Map<u32, String> =
-            e.storage().persistent().get(&URIS).unwrap_or(Map::new(&e));
-        let token_uri = token_uris.get(doc_id).unwrap();
+        if let Some(token_contract) = read_token_contract(&e) {
+            return token_contract::token_uri(&e, &token_contract, doc_id);
+        }
+        let token_uri = read_uri(&e, doc_id).unwrap();
         token_uri
     }
 
     pub fn get_td_hashes(e: Env) -> Map<u32, String> {
-        let token_to_doc_hashes: Map<u32, String> = e
-            .storage()
-            .persistent()
-            .get(&T2DHASH)
-            .unwrap_or(Map::new(&e));
+        let mut token_to_doc_hashes: Map<u32, String> = Map::new(&e);
+        for token_id in read_token_ids(&e).iter() {
+            if let Some(hash) = read_document_hash(&e, token_id) {
+                token_to_doc_hashes.set(token_id, hash);
+            }
+        }
         token_to_doc_hashes
     }
 
     pub fn get_deadlines(e: Env) -> Map<u32, u64> {
-        let deadlines: Map<u32, u64> = e
-            .storage()
-            .persistent()
-            .get(&DEADLINES)
-            .unwrap_or(Map::new(&e));
+        let mut deadlines: Map<u32, u64> = Map::new(&e);
+        for token_id in read_token_ids(&e).iter() {
+            if let Some(deadline) = read_deadline(&e, token_id) {
+                deadlines.set(token_id, deadline);
+            }
+        }
         deadlines
     }
 
     pub fn get_documents(e: Env) -> Map<u32, Map<Address, SignatureStatus>> {
-        let doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
+        let mut doc_signings: Map<u32, Map<Address, SignatureStatus>> = Map::new(&e);
+        for token_id in read_token_ids(&e).iter() {
+            if let Some(signings) = read_signings(&e, token_id) {
+                doc_signings.set(token_id, signings);
+            }
+        }
         doc_signings
     }
 
     pub fn get_document(e: Env, doc_id: u32) -> Map<Address, SignatureStatus> {
-        let doc_signings: Map<u32, Map<Address, SignatureStatus>> = e
-            .storage()
-            .persistent()
-            .get(&DOCSIGN)
-            .unwrap_or(Map::new(&e));
-        let document = doc_signings.get(doc_id).unwrap_or(Map::new(&e));
+        let document = read_signings(&e, doc_id).unwrap_or(Map::new(&e));
         document
     }
 
+    // Signers of `doc_id` in ascending address order, as the host compares
+    // addresses: by type, then by key bytes.
+    pub fn get_signers_sorted(e: Env, doc_id: u32) -> Vec<(Address, SignatureStatus)> {
+        let mut signers = Vec::new(&e);
+        for (signer, status) in Self::get_document(e.clone(), doc_id).iter() {
+            signers.push_back((signer, status));
+        }
+        signers
+    }
+
+    // The signer's last status changes on `doc_id`, oldest first, e.g. a
+    // rejection followed by a signature after the document was reopened.
+    pub fn get_signer_history(e: Env, doc_id: u32, signer: Address) -> Vec<StatusChange> {
+        history::read_history(&e, doc_id, signer)
+    }
+
     // pub fn add_extra_signers(e: Env, signers: Vec<Address>, doc_id: u32) {
 
     //     if signers.is_empty() {
@@ -0,0 +1,69 @@
+use soroban_sdk::{panic_with_error, Env, String};
+
+use crate::storage_types::{
+    DataKey, DEFAULT_MAX_BATCH_SIZE, DEFAULT_MAX_HASH_LEN, DEFAULT_MAX_URI_LEN,
+};
+use crate::{Error, ExtendedError, HashAlgo, StringLimits};
+
+pub fn read_string_limits(e: &Env) -> StringLimits {
+    let key = DataKey::StringLimits;
+    e.storage().instance().get(&key).unwrap_or(StringLimits {
+        max_uri_len: DEFAULT_MAX_URI_LEN,
+        max_hash_len: DEFAULT_MAX_HASH_LEN,
+    })
+}
+
+pub fn write_string_limits(e: &Env, limits: &StringLimits) {
+    let key = DataKey::StringLimits;
+    e.storage().instance().set(&key, limits);
+}
+
+pub fn check_uri(e: &Env, uri: &String) {
+    if uri.len() > read_string_limits(e).max_uri_len {
+        panic_with_error!(e, Error::StringTooLong)
+    }
+}
+
+pub fn check_hash(e: &Env, document_hash: &String) {
+    if document_hash.len() > read_string_limits(e).max_hash_len {
+        panic_with_error!(e, Error::StringTooLong)
+    }
+}
+
+// Length of the hex encoded digest.
+fn hash_hex_len(algo: HashAlgo) -> Option<u32> {
+    match algo {
+        HashAlgo::Unspecified => None,
+        HashAlgo::Sha256 | HashAlgo::Keccak256 => Some(64),
+        HashAlgo::Blake2b => Some(128),
+    }
+}
+
+pub fn check_hash_algo(e: &Env, algo: HashAlgo, document_hash: &String) {
+    match hash_hex_len(algo) {
+        Some(len) if document_hash.len() != len => {
+            panic_with_error!(e, ExtendedError::InvalidHashLength)
+        }
+        _ => {}
+    }
+}
+
+// Upper bound on the number of ids a bulk read accepts.
+pub fn read_max_batch_size(e: &Env) -> u32 {
+    let key = DataKey::MaxBatchSize;
+    e.storage()
+        .instance()
+        .get(&key)
+        .unwrap_or(DEFAULT_MAX_BATCH_SIZE)
+}
+
+pub fn write_max_batch_size(e: &Env, size: u32) {
+    let key = DataKey::MaxBatchSize;
+    e.storage().instance().set(&key, &size);
+}
+
+pub fn check_batch_size(e: &Env, size: u32) {
+    if size > read_max_batch_size(e) {
+        panic_with_error!(e, ExtendedError::BatchTooLarge)
+    }
+}
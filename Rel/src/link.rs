@@ -0,0 +1,42 @@
+use soroban_sdk::{Env, Vec};
+
+use crate::storage_types::{DocKey, MAX_LINKS_PER_DOCUMENT};
+use crate::{DocumentLink, ExtendedError};
+
+// Every link is stored on both documents, so either end can list it.
+pub fn read_links(e: &Env, token_id: u32) -> Vec<DocumentLink> {
+    let key = DocKey::Links(token_id);
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Vec::new(e))
+}
+
+fn write_links(e: &Env, token_id: u32, links: &Vec<DocumentLink>) {
+    let key = DocKey::Links(token_id);
+    e.storage().persistent().set(&key, links);
+}
+
+pub fn check_link(e: &Env, link: &DocumentLink) -> Result<(), ExtendedError> {
+    if link.parent_id == link.child_id {
+        return Err(ExtendedError::InvalidLink);
+    }
+    let parent_links = read_links(e, link.parent_id);
+    if parent_links.contains(link) {
+        return Err(ExtendedError::InvalidLink);
+    }
+    if parent_links.len() >= MAX_LINKS_PER_DOCUMENT
+        || read_links(e, link.child_id).len() >= MAX_LINKS_PER_DOCUMENT
+    {
+        return Err(ExtendedError::TooManyLinks);
+    }
+    Ok(())
+}
+
+pub fn add_link(e: &Env, link: &DocumentLink) {
+    for token_id in [link.parent_id, link.child_id] {
+        let mut links = read_links(e, token_id);
+        links.push_back(link.clone());
+        write_links(e, token_id, &links);
+    }
+}
@@ -0,0 +1,36 @@
+use soroban_sdk::Env;
+
+use crate::storage_types::{ConfigKey, DocKey};
+use crate::token_contract::{self, read_token_contract};
+
+// Ledgers a completed document's token stays with its owner, as a
+// cooling-off period. 0 turns the lock-up off.
+pub fn read_lockup_ledgers(e: &Env) -> u32 {
+    let key = ConfigKey::TransferLockLedgers;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_lockup_ledgers(e: &Env, ledgers: u32) {
+    let key = ConfigKey::TransferLockLedgers;
+    e.storage().instance().set(&key, &ledgers);
+}
+
+pub fn read_transferable_at(e: &Env, token_id: u32) -> Option<u32> {
+    let key = DocKey::TransferableAt(token_id);
+    e.storage().persistent().get(&key)
+}
+
+// Starts the lock-up when `token_id` completes. The token contract enforces
+// it on transfers; this contract must be its admin.
+pub fn start(e: &Env, token_id: u32) {
+    let ledgers = read_lockup_ledgers(e);
+    if ledgers == 0 {
+        return;
+    }
+    let until = e.ledger().sequence().saturating_add(ledgers);
+    let key = DocKey::TransferableAt(token_id);
+    e.storage().persistent().set(&key, &until);
+    if let Some(token_contract) = read_token_contract(e) {
+        token_contract::lock_transfer(e, &token_contract, token_id, until);
+    }
+}
@@ -0,0 +1,87 @@
+use petal_common::OwnerStore;
+use soroban_sdk::{Address, Env, Map, String};
+
+use crate::document::{
+    add_to_index, read_legacy, write_deadline, write_document_hash, write_owner, write_signings,
+    write_uri,
+};
+use crate::storage_types::{DataKey, DEADLINES, DOCSIGN, OWNERS, T2DHASH, URIS};
+use crate::{MigrationStatus, SignatureStatus};
+
+// Number of legacy documents already moved to per-token keys.
+pub fn read_migration_cursor(e: &Env) -> u32 {
+    let key = DataKey::MigrationCursor;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+fn read_legacy_signings(e: &Env) -> Option<Map<u32, Map<Address, SignatureStatus>>> {
+    e.storage().persistent().get(&DOCSIGN)
+}
+
+pub fn migration_status(e: &Env) -> MigrationStatus {
+    let migrated = read_migration_cursor(e);
+    let total = match read_legacy_signings(e) {
+        Some(legacy) => legacy.len(),
+        None => migrated,
+    };
+    MigrationStatus {
+        migrated,
+        remaining: total - migrated,
+        complete: migrated == total,
+    }
+}
+
+// Moves up to `batch_size` legacy documents to per-token keys. Entries that
+// were already rewritten since (a signature, a clawback) are newer than the
+// legacy copy and are left alone. The legacy maps are dropped once every
+// document has been moved.
+pub fn migrate(e: &Env, batch_size: u32) -> MigrationStatus {
+    let legacy = match read_legacy_signings(e) {
+        Some(legacy) => legacy,
+        None => return migration_status(e),
+    };
+    let token_ids = legacy.keys();
+    let cursor = read_migration_cursor(e);
+    let end = token_ids.len().min(cursor.saturating_add(batch_size));
+
+    for index in cursor..end {
+        let token_id = token_ids.get_unchecked(index);
+        let storage = e.storage().persistent();
+
+        if let Some(owner) = read_legacy::<Address>(e, &OWNERS, token_id) {
+            if !OwnerStore::new(e).has(token_id) {
+                write_owner(e, token_id, &owner);
+            }
+        }
+        if let Some(uri) = read_legacy::<String>(e, &URIS, token_id) {
+            if !storage.has(&DataKey::Uri(token_id)) {
+                write_uri(e, token_id, &uri);
+            }
+        }
+        if let Some(hash) = read_legacy::<String>(e, &T2DHASH, token_id) {
+            if !storage.has(&DataKey::DocumentHash(token_id)) {
+                write_document_hash(e, token_id, &hash);
+            }
+        }
+        if let Some(deadline) = read_legacy::<u64>(e, &DEADLINES, token_id) {
+            if !storage.has(&DataKey::Deadline(token_id)) {
+                write_deadline(e, token_id, deadline);
+            }
+        }
+        if !storage.has(&DataKey::Signings(token_id)) {
+            write_signings(e, token_id, &legacy.get_unchecked(token_id));
+        }
+        add_to_index(e, token_id);
+    }
+
+    if end == token_ids.len() {
+        let storage = e.storage().persistent();
+        storage.remove(&OWNERS);
+        storage.remove(&URIS);
+        storage.remove(&T2DHASH);
+        storage.remove(&DEADLINES);
+        storage.remove(&DOCSIGN);
+    }
+    e.storage().instance().set(&DataKey::MigrationCursor, &end);
+    migration_status(e)
+}
@@ -0,0 +1,34 @@
+use soroban_sdk::{Address, Env};
+
+use crate::admin::is_administrator;
+use crate::storage_types::{AccessKey, ConfigKey};
+
+// While the allowlist is off anyone may mint, as before it existed.
+pub fn read_allowlist_enabled(e: &Env) -> bool {
+    let key = ConfigKey::MinterAllowlist;
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+pub fn write_allowlist_enabled(e: &Env, enabled: bool) {
+    let key = ConfigKey::MinterAllowlist;
+    e.storage().instance().set(&key, &enabled);
+}
+
+pub fn is_minter(e: &Env, addr: &Address) -> bool {
+    let key = AccessKey::Minter(addr.clone());
+    e.storage().persistent().has(&key)
+}
+
+pub fn add_minter(e: &Env, addr: &Address) {
+    let key = AccessKey::Minter(addr.clone());
+    e.storage().persistent().set(&key, &true);
+}
+
+pub fn remove_minter(e: &Env, addr: &Address) {
+    let key = AccessKey::Minter(addr.clone());
+    e.storage().persistent().remove(&key);
+}
+
+pub fn may_mint(e: &Env, addr: &Address) -> bool {
+    !read_allowlist_enabled(e) || is_administrator(e, addr) || is_minter(e, addr)
+}
@@ -0,0 +1,163 @@
+use soroban_sdk::{panic_with_error, Address, Env, String, Vec};
+
+use crate::storage_types::{OrgItemKey, OrgKey, OrgMemberKey};
+use crate::{ExtendedError, OrgRole, Organization};
+
+// Organizations let their members act on the documents minted under them:
+// managers can extend deadlines and add signers, admins also manage members.
+pub fn create(e: &Env, creator: &Address, name: String) -> u32 {
+    let storage = e.storage().persistent();
+    let org_id: u32 = storage.get(&OrgKey::NextOrgId).unwrap_or(1);
+    let org = Organization {
+        name,
+        creator: creator.clone(),
+        members: 1,
+        admins: 1,
+        documents: 0,
+    };
+    write_org(e, org_id, &org);
+    write_role(e, org_id, creator, OrgRole::Admin);
+    storage.set(&OrgKey::NextOrgId, &(org_id + 1));
+    org_id
+}
+
+pub fn read_org(e: &Env, org_id: u32) -> Option<Organization> {
+    let key = OrgKey::Org(org_id);
+    e.storage().persistent().get(&key)
+}
+
+fn write_org(e: &Env, org_id: u32, org: &Organization) {
+    let key = OrgKey::Org(org_id);
+    e.storage().persistent().set(&key, org);
+}
+
+fn load(e: &Env, org_id: u32) -> Result<Organization, ExtendedError> {
+    read_org(e, org_id).ok_or(ExtendedError::OrgNotFound)
+}
+
+pub fn read_role(e: &Env, org_id: u32, member: &Address) -> Option<OrgRole> {
+    let key = OrgKey::Member(OrgMemberKey {
+        org_id,
+        member: member.clone(),
+    });
+    e.storage().persistent().get(&key)
+}
+
+fn write_role(e: &Env, org_id: u32, member: &Address, role: OrgRole) {
+    let key = OrgKey::Member(OrgMemberKey {
+        org_id,
+        member: member.clone(),
+    });
+    e.storage().persistent().set(&key, &role);
+}
+
+pub fn check_role(
+    e: &Env,
+    org_id: u32,
+    member: &Address,
+    required: OrgRole,
+) -> Result<(), ExtendedError> {
+    load(e, org_id)?;
+    match read_role(e, org_id, member) {
+        Some(role) if role >= required => Ok(()),
+        Some(_) => Err(ExtendedError::OrgRoleTooLow),
+        None => Err(ExtendedError::NotOrgMember),
+    }
+}
+
+// Adds `member`, or changes the role of an existing one. The last admin
+// can't step down.
+pub fn set_member(
+    e: &Env,
+    org_id: u32,
+    member: &Address,
+    role: OrgRole,
+) -> Result<(), ExtendedError> {
+    let mut org = load(e, org_id)?;
+    match read_role(e, org_id, member) {
+        Some(OrgRole::Admin) if role != OrgRole::Admin => {
+            if org.admins == 1 {
+                return Err(ExtendedError::LastOrgAdmin);
+            }
+            org.admins -= 1;
+        }
+        Some(OrgRole::Admin) => {}
+        Some(_) if role == OrgRole::Admin => org.admins += 1,
+        Some(_) => {}
+        None => {
+            org.members += 1;
+            if role == OrgRole::Admin {
+                org.admins += 1;
+            }
+        }
+    }
+    write_org(e, org_id, &org);
+    write_role(e, org_id, member, role);
+    Ok(())
+}
+
+pub fn remove_member(e: &Env, org_id: u32, member: &Address) -> Result<(), ExtendedError> {
+    let mut org = load(e, org_id)?;
+    match read_role(e, org_id, member) {
+        Some(OrgRole::Admin) if org.admins == 1 => return Err(ExtendedError::LastOrgAdmin),
+        Some(OrgRole::Admin) => org.admins -= 1,
+        Some(_) => {}
+        None => return Err(ExtendedError::NotOrgMember),
+    }
+    org.members -= 1;
+    write_org(e, org_id, &org);
+    let key = OrgKey::Member(OrgMemberKey {
+        org_id,
+        member: member.clone(),
+    });
+    e.storage().persistent().remove(&key);
+    Ok(())
+}
+
+pub fn read_document_org(e: &Env, token_id: u32) -> Option<u32> {
+    let key = OrgKey::DocOrg(token_id);
+    e.storage().persistent().get(&key)
+}
+
+// Documents stay with the organization they were minted under.
+pub fn add_document(e: &Env, org_id: u32, token_id: u32) {
+    let mut org = match read_org(e, org_id) {
+        Some(org) => org,
+        None => panic_with_error!(e, ExtendedError::OrgNotFound),
+    };
+    let storage = e.storage().persistent();
+    let item = OrgKey::Document(OrgItemKey {
+        org_id,
+        index: org.documents,
+    });
+    storage.set(&item, &token_id);
+    storage.set(&OrgKey::DocOrg(token_id), &org_id);
+    org.documents += 1;
+    write_org(e, org_id, &org);
+}
+
+// Whether `member` may manage the deadline and signers of `token_id` through
+// its organization.
+pub fn may_manage(e: &Env, token_id: u32, member: &Address) -> bool {
+    match read_document_org(e, token_id) {
+        Some(org_id) => check_role(e, org_id, member, OrgRole::Manager).is_ok(),
+        None => false,
+    }
+}
+
+pub fn read_documents(e: &Env, org_id: u32, page: u32, limit: u32) -> Vec<u32> {
+    let mut token_ids = Vec::new(e);
+    let size = match read_org(e, org_id) {
+        Some(org) => org.documents,
+        None => return token_ids,
+    };
+    let start = page.saturating_mul(limit);
+    let end = size.min(start.saturating_add(limit));
+    for index in start..end {
+        let key = OrgKey::Document(OrgItemKey { org_id, index });
+        if let Some(token_id) = e.storage().persistent().get(&key) {
+            token_ids.push_back(token_id);
+        }
+    }
+    token_ids
+}
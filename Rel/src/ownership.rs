@@ -0,0 +1,84 @@
+use soroban_sdk::{panic_with_error, vec, Address, Env, Vec};
+
+use crate::storage_types::DocKey;
+use crate::{ExtendedError, OwnerAuth};
+
+// Owners besides the primary one, which stays the ERC721 owner of the token.
+// They are stored with the primary owner they were added under, so they lapse
+// once the token changes hands, including through the token contract.
+pub fn read_co_owners(e: &Env, token_id: u32, primary: &Address) -> Vec<Address> {
+    let key = DocKey::CoOwners(token_id);
+    match e
+        .storage()
+        .persistent()
+        .get::<_, (Address, Vec<Address>)>(&key)
+    {
+        Some((added_under, co_owners)) if added_under == *primary => co_owners,
+        _ => Vec::new(e),
+    }
+}
+
+pub fn write_co_owners(e: &Env, token_id: u32, primary: &Address, co_owners: &Vec<Address>) {
+    let key = DocKey::CoOwners(token_id);
+    e.storage()
+        .persistent()
+        .set(&key, &(primary.clone(), co_owners.clone()));
+}
+
+pub fn clear_co_owners(e: &Env, token_id: u32) {
+    let key = DocKey::CoOwners(token_id);
+    e.storage().persistent().remove(&key);
+}
+
+pub fn read_owner_auth(e: &Env, token_id: u32) -> OwnerAuth {
+    let key = DocKey::OwnerAuthMode(token_id);
+    e.storage().persistent().get(&key).unwrap_or(OwnerAuth::Any)
+}
+
+pub fn write_owner_auth(e: &Env, token_id: u32, mode: OwnerAuth) {
+    let key = DocKey::OwnerAuthMode(token_id);
+    e.storage().persistent().set(&key, &mode);
+}
+
+pub fn owners(e: &Env, token_id: u32, primary: Address) -> Vec<Address> {
+    let co_owners = read_co_owners(e, token_id, &primary);
+    let mut owners = vec![e, primary];
+    for co_owner in co_owners.iter() {
+        if !owners.contains(&co_owner) {
+            owners.push_back(co_owner);
+        }
+    }
+    owners
+}
+
+// `caller` has to be one of the owners. Documents set to `All` also need
+// every other owner to authorize the same call.
+pub fn require_owner_auth(e: &Env, token_id: u32, primary: Address, caller: &Address) {
+    require_owner_auth_once(e, token_id, primary, caller, &mut Vec::new(e));
+}
+
+// Like `require_owner_auth`, but skips addresses in `authorized` and adds
+// the ones it checks, so a call touching several documents asks each
+// address for its authorization only once.
+pub fn require_owner_auth_once(
+    e: &Env,
+    token_id: u32,
+    primary: Address,
+    caller: &Address,
+    authorized: &mut Vec<Address>,
+) {
+    let owners = owners(e, token_id, primary);
+    if !owners.contains(caller) {
+        panic_with_error!(e, ExtendedError::NotAnOwner)
+    }
+    let required = match read_owner_auth(e, token_id) {
+        OwnerAuth::Any => vec![e, caller.clone()],
+        OwnerAuth::All => owners,
+    };
+    for owner in required.iter() {
+        if !authorized.contains(&owner) {
+            owner.require_auth();
+            authorized.push_back(owner);
+        }
+    }
+}
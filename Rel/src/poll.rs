@@ -0,0 +1,143 @@
+use soroban_sdk::xdr::ScErrorType;
+use soroban_sdk::{panic_with_error, vec, Address, Env, IntoVal, InvokeError, Symbol};
+
+use crate::storage_types::{DataKey, PollVoteKey};
+use crate::token_contract::read_token_contract;
+use crate::{Error, ExtendedError, Poll, PollOutcome, PollResult};
+
+pub fn read_voting_token(e: &Env) -> Option<Address> {
+    let key = DataKey::VotingToken;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_voting_token(e: &Env, voting_token: &Address) {
+    let key = DataKey::VotingToken;
+    e.storage().instance().set(&key, voting_token);
+}
+
+// Falls back to the configured token contract, whose checkpointed
+// `balance_at` polls read their votes from.
+fn voting_token(e: &Env) -> Address {
+    match read_voting_token(e).or_else(|| read_token_contract(e)) {
+        Some(voting_token) => voting_token,
+        None => panic_with_error!(e, Error::VotingTokenNotSet),
+    }
+}
+
+pub fn read_poll(e: &Env, token_id: u32) -> Option<Poll> {
+    let key = DataKey::Poll(token_id);
+    e.storage().persistent().get(&key)
+}
+
+fn write_poll(e: &Env, token_id: u32, poll: &Poll) {
+    let key = DataKey::Poll(token_id);
+    e.storage().persistent().set(&key, poll);
+}
+
+// The token's `balance_at`, read from its balance checkpoints.
+fn invoke_balance_at(
+    e: &Env,
+    voting_token: &Address,
+    id: &Address,
+    ledger_seq: u32,
+) -> Result<Result<i128, soroban_sdk::Error>, Result<soroban_sdk::Error, InvokeError>> {
+    e.try_invoke_contract::<i128, soroban_sdk::Error>(
+        voting_token,
+        &Symbol::new(e, "balance_at"),
+        vec![e, id.into_val(e), ledger_seq.into_val(e)],
+    )
+}
+
+// A token that answers `balance_at`, even with its own error because this
+// contract has no checkpoints, supports snapshots. One without the function
+// fails with a host error instead.
+fn supports_snapshots(e: &Env, voting_token: &Address, ledger_seq: u32) -> bool {
+    match invoke_balance_at(e, voting_token, &e.current_contract_address(), ledger_seq) {
+        Ok(Ok(_)) => true,
+        Err(Ok(error)) => error.is_type(ScErrorType::Contract),
+        _ => false,
+    }
+}
+
+pub fn create(e: &Env, token_id: u32, end_time: u64) -> Poll {
+    if read_poll(e, token_id).is_some() {
+        panic_with_error!(e, Error::PollAlreadyExists)
+    }
+    if end_time <= e.ledger().timestamp() {
+        panic_with_error!(e, Error::InvalidPollEnd)
+    }
+
+    // The previous ledger is closed, so its balances can't change anymore.
+    let snapshot_ledger = e.ledger().sequence().saturating_sub(1);
+    let voting_token = voting_token(e);
+    if !supports_snapshots(e, &voting_token, snapshot_ledger) {
+        panic_with_error!(e, ExtendedError::SnapshotsUnsupported)
+    }
+
+    let poll = Poll {
+        voting_token,
+        snapshot_ledger,
+        end_time,
+        votes_for: 0,
+        votes_against: 0,
+    };
+    write_poll(e, token_id, &poll);
+    poll
+}
+
+// Votes are weighted by the voter's balance at the poll's snapshot ledger,
+// so tokens moved after the poll opened don't vote again from their new
+// address.
+pub fn vote(e: &Env, voter: &Address, token_id: u32, support: bool) -> Result<i128, Error> {
+    let mut poll = match read_poll(e, token_id) {
+        Some(poll) => poll,
+        None => return Err(Error::PollNotFound),
+    };
+    if e.ledger().timestamp() >= poll.end_time {
+        return Err(Error::PollClosed);
+    }
+    let key = DataKey::PollVote(PollVoteKey {
+        token_id,
+        voter: voter.clone(),
+    });
+    if e.storage().persistent().has(&key) {
+        return Err(Error::AlreadyVoted);
+    }
+
+    // Voters who never enabled checkpoints have no weight.
+    let weight = match invoke_balance_at(e, &poll.voting_token, voter, poll.snapshot_ledger) {
+        Ok(Ok(weight)) => weight,
+        _ => 0,
+    };
+    if weight <= 0 {
+        return Err(Error::NoVotingPower);
+    }
+    if support {
+        poll.votes_for += weight;
+    } else {
+        poll.votes_against += weight;
+    }
+    e.storage().persistent().set(&key, &support);
+    write_poll(e, token_id, &poll);
+    Ok(weight)
+}
+
+pub fn result(e: &Env, token_id: u32) -> PollResult {
+    let poll = match read_poll(e, token_id) {
+        Some(poll) => poll,
+        None => panic_with_error!(e, Error::PollNotFound),
+    };
+    // Ties veto: a flagged document needs a majority to go through.
+    let outcome = if e.ledger().timestamp() < poll.end_time {
+        PollOutcome::Open
+    } else if poll.votes_for > poll.votes_against {
+        PollOutcome::Approved
+    } else {
+        PollOutcome::Vetoed
+    };
+    PollResult {
+        outcome,
+        votes_for: poll.votes_for,
+        votes_against: poll.votes_against,
+    }
+}
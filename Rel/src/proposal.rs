@@ -0,0 +1,128 @@
+use soroban_sdk::{panic_with_error, vec, Address, Env};
+
+use crate::admin::{is_admin, read_admins, read_threshold, write_admins};
+use crate::clawback::clawback;
+use crate::fee::{
+    check_fee_discounts, withdraw, write_creation_fee, write_discount_token, write_fee_discounts,
+    write_fee_schedule, write_fee_tier, write_fee_token,
+};
+use crate::storage_types::{DataKey, PROPOSAL_LIFETIME};
+use crate::{bounty, credit, event, referral, Error, Proposal, ProposalAction};
+
+pub fn read_proposal(e: &Env, proposal_id: u32) -> Option<Proposal> {
+    let key = DataKey::Proposal(proposal_id);
+    e.storage().persistent().get(&key)
+}
+
+fn write_proposal(e: &Env, proposal_id: u32, proposal: &Proposal) {
+    let key = DataKey::Proposal(proposal_id);
+    e.storage().persistent().set(&key, proposal);
+}
+
+fn remove_proposal(e: &Env, proposal_id: u32) {
+    let key = DataKey::Proposal(proposal_id);
+    e.storage().persistent().remove(&key);
+}
+
+fn next_proposal_id(e: &Env) -> u32 {
+    let key = DataKey::NextProposalId;
+    let proposal_id: u32 = e.storage().instance().get(&key).unwrap_or(0);
+    e.storage().instance().set(&key, &(proposal_id + 1));
+    proposal_id
+}
+
+// The proposer's own approval is counted, so a proposal executes right away
+// when the threshold is one.
+pub fn propose(e: &Env, proposer: &Address, action: ProposalAction) -> u32 {
+    if !is_admin(e, proposer) {
+        panic_with_error!(e, Error::NotAnAdmin)
+    }
+
+    let proposal_id = next_proposal_id(e);
+    let proposal = Proposal {
+        action,
+        approvals: vec![e, proposer.clone()],
+        expiration_ledger: e.ledger().sequence() + PROPOSAL_LIFETIME,
+    };
+    event::proposed(e, proposal_id, proposer.clone());
+    execute_if_approved(e, proposal_id, proposal, proposer);
+    proposal_id
+}
+
+pub fn approve(e: &Env, admin: &Address, proposal_id: u32) -> bool {
+    if !is_admin(e, admin) {
+        panic_with_error!(e, Error::NotAnAdmin)
+    }
+    let mut proposal = match read_proposal(e, proposal_id) {
+        Some(proposal) => proposal,
+        None => panic_with_error!(e, Error::ProposalNotFound),
+    };
+    if e.ledger().sequence() > proposal.expiration_ledger {
+        panic_with_error!(e, Error::ProposalExpired)
+    }
+    if proposal.approvals.contains(admin) {
+        panic_with_error!(e, Error::AlreadyApproved)
+    }
+
+    proposal.approvals.push_back(admin.clone());
+    event::approved(e, proposal_id, admin.clone());
+    execute_if_approved(e, proposal_id, proposal, admin)
+}
+
+// Approvals from admins removed since they approved no longer count.
+fn execute_if_approved(e: &Env, proposal_id: u32, proposal: Proposal, approver: &Address) -> bool {
+    let admins = read_admins(e);
+    let approvals = proposal
+        .approvals
+        .iter()
+        .filter(|approval| admins.contains(approval))
+        .count() as u32;
+    if approvals < read_threshold(e) {
+        write_proposal(e, proposal_id, &proposal);
+        return false;
+    }
+
+    remove_proposal(e, proposal_id);
+    match proposal.action {
+        ProposalAction::SetAdmins(admin_set) => {
+            write_admins(e, &admin_set.admins, admin_set.threshold)
+        }
+        ProposalAction::SetCreationFee(fee) => {
+            write_creation_fee(e, fee);
+            event::set_creation_fee(e, approver.clone(), fee);
+        }
+        ProposalAction::SetFeeToken(fee_token) => {
+            write_fee_token(e, &fee_token);
+            event::set_fee_token(e, approver.clone(), fee_token);
+        }
+        ProposalAction::SetFeeSchedule(schedule) => write_fee_schedule(e, &schedule),
+        ProposalAction::SetFeeTier(change) => write_fee_tier(e, &change.addr, change.tier),
+        ProposalAction::SetFeeDiscounts(discounts) => {
+            if let Err(error) = check_fee_discounts(&discounts) {
+                panic_with_error!(e, error)
+            }
+            write_fee_discounts(e, &discounts)
+        }
+        ProposalAction::SetDiscountToken(discount_token) => {
+            write_discount_token(e, &discount_token)
+        }
+        ProposalAction::SetReferralShare(bps) => {
+            if let Err(error) = referral::write_share_bps(e, bps) {
+                panic_with_error!(e, error)
+            }
+        }
+        ProposalAction::GrantCredits(grant) => credit::grant(e, &grant.to, grant.credits),
+        ProposalAction::FundBountyPool(amount) => bounty::fund(e, approver, amount),
+        ProposalAction::WithdrawFees(withdrawal) => {
+            if let Err(error) = withdraw(e, &withdrawal.to, withdrawal.amount) {
+                panic_with_error!(e, error)
+            }
+        }
+        ProposalAction::Clawback(request) => clawback(e, approver, request),
+        ProposalAction::Upgrade(wasm_hash) => {
+            e.deployer().update_current_contract_wasm(wasm_hash);
+        }
+    }
+    event::executed(e, proposal_id);
+    true
+}
@@ -0,0 +1,74 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage_types::{ConfigKey, RateWindowKey};
+use crate::{ExtendedError, RateAction, RateLimit, RateWindow};
+
+pub fn read_rate_limit(e: &Env, action: RateAction) -> Option<RateLimit> {
+    let key = ConfigKey::RateLimit(action);
+    e.storage().instance().get(&key)
+}
+
+pub fn write_rate_limit(e: &Env, action: RateAction, limit: Option<RateLimit>) {
+    let key = ConfigKey::RateLimit(action);
+    match limit {
+        Some(limit) => e.storage().instance().set(&key, &limit),
+        None => e.storage().instance().remove(&key),
+    }
+}
+
+fn window_key(action: RateAction, addr: &Address) -> ConfigKey {
+    ConfigKey::RateWindow(RateWindowKey {
+        action,
+        addr: addr.clone(),
+    })
+}
+
+// The window `addr` is currently in, or a fresh one if its last window has
+// passed. Each address gets a window of `window_ledgers` starting at its
+// first action; once it has passed, the next action opens a new one.
+fn current_window(e: &Env, action: RateAction, addr: &Address, limit: &RateLimit) -> RateWindow {
+    let now = e.ledger().sequence();
+    match e
+        .storage()
+        .temporary()
+        .get::<_, RateWindow>(&window_key(action, addr))
+    {
+        Some(window) if now < window.start_ledger.saturating_add(limit.window_ledgers) => window,
+        _ => RateWindow {
+            start_ledger: now,
+            count: 0,
+        },
+    }
+}
+
+// Whether `addr` may take `action` now, without counting it.
+pub fn check(e: &Env, action: RateAction, addr: &Address) -> Result<(), ExtendedError> {
+    match read_rate_limit(e, action) {
+        Some(limit) if current_window(e, action, addr, &limit).count >= limit.max_actions => {
+            Err(ExtendedError::RateLimited)
+        }
+        _ => Ok(()),
+    }
+}
+
+// Counts one `action` by `addr`. Windows live in temporary storage, since an
+// entry that expires has nothing left to limit anyway.
+pub fn hit(e: &Env, action: RateAction, addr: &Address) -> Result<(), ExtendedError> {
+    let limit = match read_rate_limit(e, action) {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+    let window = current_window(e, action, addr, &limit);
+    if window.count >= limit.max_actions {
+        return Err(ExtendedError::RateLimited);
+    }
+
+    let window = RateWindow {
+        start_ledger: window.start_ledger,
+        count: window.count + 1,
+    };
+    e.storage()
+        .temporary()
+        .set(&window_key(action, addr), &window);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use soroban_sdk::{panic_with_error, token, Address, Env};
+
+use crate::fee::{add_collected, bps_of, read_fee_paid, MAX_BPS};
+use crate::storage_types::{AccessKey, ConfigKey, DocKey, ReferralKey};
+use crate::{event, ExtendedError, ReferralAccount};
+
+pub fn read_share_bps(e: &Env) -> u32 {
+    let key = ConfigKey::ReferralShare;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_share_bps(e: &Env, bps: u32) -> Result<(), ExtendedError> {
+    if bps > MAX_BPS {
+        return Err(ExtendedError::InvalidReferralShare);
+    }
+    let key = ConfigKey::ReferralShare;
+    e.storage().instance().set(&key, &bps);
+    Ok(())
+}
+
+// Shares are paid in the token the referred fee was paid in, so a referrer
+// has one account per fee token.
+pub fn read_account(e: &Env, referrer: &Address, fee_token: &Address) -> ReferralAccount {
+    let key = AccessKey::Referral(ReferralKey {
+        referrer: referrer.clone(),
+        fee_token: fee_token.clone(),
+    });
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(ReferralAccount {
+            referrals: 0,
+            earned: 0,
+            claimed: 0,
+        })
+}
+
+fn write_account(e: &Env, referrer: &Address, fee_token: &Address, account: &ReferralAccount) {
+    let key = AccessKey::Referral(ReferralKey {
+        referrer: referrer.clone(),
+        fee_token: fee_token.clone(),
+    });
+    e.storage().persistent().set(&key, account);
+}
+
+pub fn claimable(account: &ReferralAccount) -> i128 {
+    account.earned - account.claimed
+}
+
+// Credits the referrer with their share of the fee paid for `token_id`. The
+// share stays in the contract until claimed.
+pub fn credit(e: &Env, token_id: u32, payer: &Address, referrer: &Address) {
+    if payer == referrer {
+        panic_with_error!(e, ExtendedError::SelfReferral)
+    }
+    let (fee_token, fee) = match read_fee_paid(e, token_id) {
+        Some(fee_paid) => fee_paid,
+        None => return,
+    };
+    let share = bps_of(fee, read_share_bps(e));
+    if share == 0 {
+        return;
+    }
+    add_collected(e, &fee_token, -share);
+
+    let mut account = read_account(e, referrer, &fee_token);
+    account.referrals += 1;
+    account.earned += share;
+    write_account(e, referrer, &fee_token, &account);
+    let key = DocKey::Referral(token_id);
+    e.storage()
+        .persistent()
+        .set(&key, &(referrer.clone(), fee_token, share));
+    event::referral_credited(e, token_id, referrer.clone(), share);
+}
+
+// Takes back the share credited for `token_id` when its fee is refunded, as
+// far as the referrer hasn't claimed it yet. Returns the part already paid
+// out, which the refund has to hold back.
+pub fn reverse(e: &Env, token_id: u32) -> i128 {
+    let key = DocKey::Referral(token_id);
+    let (referrer, fee_token, share): (Address, Address, i128) =
+        match e.storage().persistent().get(&key) {
+            Some(referral) => referral,
+            None => return 0,
+        };
+    e.storage().persistent().remove(&key);
+
+    let mut account = read_account(e, &referrer, &fee_token);
+    let reversed = share.min(claimable(&account));
+    account.earned -= reversed;
+    write_account(e, &referrer, &fee_token, &account);
+    add_collected(e, &fee_token, reversed);
+    share - reversed
+}
+
+pub fn claim(e: &Env, referrer: &Address, fee_token: &Address) -> i128 {
+    let mut account = read_account(e, referrer, fee_token);
+    let amount = claimable(&account);
+    if amount <= 0 {
+        return 0;
+    }
+
+    account.claimed += amount;
+    write_account(e, referrer, fee_token, &account);
+    token::Client::new(e, fee_token).transfer(&e.current_contract_address(), referrer, &amount);
+    event::referral_claimed(e, referrer.clone(), amount);
+    amount
+}
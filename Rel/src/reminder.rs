@@ -0,0 +1,56 @@
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::storage_types::{DataKey, DocKey, DEFAULT_REMINDER_INTERVAL, MAX_REMINDER_BOUNTIES};
+use crate::Error;
+
+pub fn read_reminder(e: &Env, token_id: u32) -> Option<u64> {
+    let key = DataKey::Reminder(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_reminder(e: &Env, token_id: u32, remind_at: u64, deadline: u64) {
+    if remind_at >= deadline {
+        panic_with_error!(e, Error::InvalidReminder)
+    }
+    let key = DataKey::Reminder(token_id);
+    e.storage().persistent().set(&key, &remind_at);
+}
+
+pub fn read_reminder_interval(e: &Env) -> u32 {
+    let key = DataKey::ReminderInterval;
+    e.storage()
+        .instance()
+        .get(&key)
+        .unwrap_or(DEFAULT_REMINDER_INTERVAL)
+}
+
+pub fn write_reminder_interval(e: &Env, ledgers: u32) {
+    let key = DataKey::ReminderInterval;
+    e.storage().instance().set(&key, &ledgers);
+}
+
+// Enforces the per-document rate limit and records the current ledger.
+pub fn mark_reminder_sent(e: &Env, token_id: u32) -> Result<(), Error> {
+    let key = DataKey::ReminderSent(token_id);
+    let sequence = e.ledger().sequence();
+    if let Some(last_sent) = e.storage().persistent().get::<DataKey, u32>(&key) {
+        if sequence < last_sent.saturating_add(read_reminder_interval(e)) {
+            return Err(Error::ReminderRateLimited);
+        }
+    }
+    e.storage().persistent().set(&key, &sequence);
+    Ok(())
+}
+
+// Only `MAX_REMINDER_BOUNTIES` reminders per document earn a bounty, so a
+// document nobody signs can't be used to drain the pool. Returns whether
+// this one does.
+pub fn take_bounty_slot(e: &Env, token_id: u32) -> bool {
+    let key = DocKey::ReminderBounties(token_id);
+    let paid: u32 = e.storage().persistent().get(&key).unwrap_or(0);
+    if paid >= MAX_REMINDER_BOUNTIES {
+        return false;
+    }
+    e.storage().persistent().set(&key, &(paid + 1));
+    true
+}
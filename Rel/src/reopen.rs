@@ -0,0 +1,55 @@
+use soroban_sdk::{panic_with_error, Env, Map};
+
+use crate::deadline::{read_signer_deadlines, write_signer_deadlines};
+use crate::document::{read_deadline, read_signings, write_deadline, write_signings};
+use crate::history;
+use crate::status::write_status;
+use crate::{DocumentStatus, ExtendedError, SignatureStatus};
+
+// Only expired documents, or rejected ones whose rejections are cleared, can
+// take another round of signing.
+pub fn check_reopenable(
+    status: &DocumentStatus,
+    reset_rejected: bool,
+) -> Result<(), ExtendedError> {
+    match status {
+        DocumentStatus::Expired => Ok(()),
+        DocumentStatus::Rejected if reset_rejected => Ok(()),
+        _ => Err(ExtendedError::DocumentNotReopenable),
+    }
+}
+
+// Moves the document back to Pending with `new_deadline`. Signatures already
+// given are kept; per-signer deadlines that have passed are dropped so the
+// new document deadline applies to those signers.
+pub fn reopen(e: &Env, token_id: u32, new_deadline: u64, reset_rejected: bool) {
+    let now = e.ledger().timestamp();
+    let previous = read_deadline(e, token_id).unwrap_or(0);
+    if new_deadline <= now || new_deadline <= previous {
+        panic_with_error!(e, ExtendedError::InvalidDeadline)
+    }
+
+    if reset_rejected {
+        let mut signings = read_signings(e, token_id).unwrap_or_else(|| Map::new(e));
+        for (signer, status) in signings.clone().iter() {
+            if status == SignatureStatus::Rejected {
+                history::record(e, token_id, &signer, SignatureStatus::Waiting);
+                signings.set(signer, SignatureStatus::Waiting);
+            }
+        }
+        write_signings(e, token_id, &signings);
+    }
+
+    let mut signer_deadlines = read_signer_deadlines(e, token_id);
+    if !signer_deadlines.is_empty() {
+        for (signer, deadline) in signer_deadlines.clone().iter() {
+            if deadline < now {
+                signer_deadlines.remove(signer);
+            }
+        }
+        write_signer_deadlines(e, token_id, &signer_deadlines);
+    }
+
+    write_deadline(e, token_id, new_deadline);
+    write_status(e, token_id, DocumentStatus::Pending);
+}
@@ -0,0 +1,90 @@
+use soroban_sdk::{symbol_short, Address, Env, Map};
+
+use crate::document::{read_signings, write_signings};
+use crate::escrow;
+use crate::history;
+use crate::link::read_links;
+use crate::status::write_status;
+use crate::storage_types::{ConfigKey, DocKey, SignerKey, DEFAULT_MAX_REREQUESTS};
+use crate::{DocumentStatus, ExtendedError, SignatureStatus};
+
+pub fn read_max_rerequests(e: &Env) -> u32 {
+    let key = ConfigKey::MaxRerequests;
+    e.storage()
+        .instance()
+        .get(&key)
+        .unwrap_or(DEFAULT_MAX_REREQUESTS)
+}
+
+pub fn write_max_rerequests(e: &Env, max: u32) {
+    let key = ConfigKey::MaxRerequests;
+    e.storage().instance().set(&key, &max);
+}
+
+pub fn read_requires_amendment(e: &Env) -> bool {
+    let key = ConfigKey::RerequestNeedsAmendment;
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+pub fn write_requires_amendment(e: &Env, required: bool) {
+    let key = ConfigKey::RerequestNeedsAmendment;
+    e.storage().instance().set(&key, &required);
+}
+
+pub fn read_rerequest_count(e: &Env, token_id: u32, signer: Address) -> u32 {
+    let key = DocKey::Rerequests(SignerKey { token_id, signer });
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+// Amendments are documents linked to this one with the `amends` relation.
+fn count_amendments(e: &Env, token_id: u32) -> u32 {
+    let amends = symbol_short!("amends");
+    read_links(e, token_id)
+        .iter()
+        .filter(|link| link.parent_id == token_id && link.relation == amends)
+        .count() as u32
+}
+
+pub fn check_rerequest(
+    e: &Env,
+    token_id: u32,
+    signer: &Address,
+    signings: &Map<Address, SignatureStatus>,
+) -> Result<(), ExtendedError> {
+    if signings.get(signer.clone()) != Some(SignatureStatus::Rejected) {
+        return Err(ExtendedError::SignerNotRejected);
+    }
+    escrow::check_not_refunded(e, token_id)?;
+    let count = read_rerequest_count(e, token_id, signer.clone());
+    if count >= read_max_rerequests(e) {
+        return Err(ExtendedError::TooManyRerequests);
+    }
+    // Every re-request has to follow a new amendment.
+    if read_requires_amendment(e) && count_amendments(e, token_id) <= count {
+        return Err(ExtendedError::AmendmentRequired);
+    }
+    Ok(())
+}
+
+// Puts the signer back to Waiting and returns how often they have now been
+// asked again. The document goes back to Pending once no rejection is left.
+pub fn rerequest(e: &Env, token_id: u32, signer: &Address) -> Result<u32, ExtendedError> {
+    let mut signings = read_signings(e, token_id).unwrap_or_else(|| Map::new(e));
+    check_rerequest(e, token_id, signer, &signings)?;
+
+    signings.set(signer.clone(), SignatureStatus::Waiting);
+    write_signings(e, token_id, &signings);
+    history::record(e, token_id, signer, SignatureStatus::Waiting);
+
+    let count = read_rerequest_count(e, token_id, signer.clone()) + 1;
+    let key = DocKey::Rerequests(SignerKey {
+        token_id,
+        signer: signer.clone(),
+    });
+    e.storage().persistent().set(&key, &count);
+
+    if !signings.values().contains(SignatureStatus::Rejected) {
+        write_status(e, token_id, DocumentStatus::Pending);
+    }
+    Ok(count)
+}
@@ -0,0 +1,53 @@
+use soroban_sdk::{Address, Env, Map};
+
+use crate::document::{read_signings, write_signings};
+use crate::history;
+use crate::storage_types::DocKey;
+use crate::{ExtendedError, SignatureStatus};
+
+// Seconds after signing during which a signer may take their signature back.
+// Zero, the default, means signatures are final.
+pub fn read_window(e: &Env, token_id: u32) -> u64 {
+    let key = DocKey::RevocationWindow(token_id);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn write_window(e: &Env, token_id: u32, seconds: u64) {
+    let key = DocKey::RevocationWindow(token_id);
+    e.storage().persistent().set(&key, &seconds);
+}
+
+// The signing time is the last entry of the signer's history, which
+// `record_signature` writes together with the signature.
+pub fn check_revoke(
+    e: &Env,
+    token_id: u32,
+    signer: &Address,
+    signings: &Map<Address, SignatureStatus>,
+) -> Result<(), ExtendedError> {
+    let window = read_window(e, token_id);
+    match signings.get(signer.clone()) {
+        Some(SignatureStatus::Signed) | Some(SignatureStatus::SignedLate) if window > 0 => {}
+        _ => return Err(ExtendedError::SignatureNotRevocable),
+    }
+    let signed_at = match history::read_history(e, token_id, signer.clone()).last() {
+        Some(change) => change.timestamp,
+        None => return Err(ExtendedError::RevocationWindowClosed),
+    };
+    if e.ledger().timestamp() > signed_at.saturating_add(window) {
+        return Err(ExtendedError::RevocationWindowClosed);
+    }
+    Ok(())
+}
+
+// Puts the signer back to Waiting. Callers make sure the document is still
+// pending, so a completed document can't lose a signature.
+pub fn revoke(e: &Env, token_id: u32, signer: &Address) -> Result<(), ExtendedError> {
+    let mut signings = read_signings(e, token_id).unwrap_or_else(|| Map::new(e));
+    check_revoke(e, token_id, signer, &signings)?;
+
+    signings.set(signer.clone(), SignatureStatus::Waiting);
+    write_signings(e, token_id, &signings);
+    history::record(e, token_id, signer, SignatureStatus::Waiting);
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use soroban_sdk::{panic_with_error, Address, Env, Map, String, Vec};
+
+use crate::attestation::check_attested;
+use crate::deadline::applicable_deadline;
+use crate::document::{has_document_hash, read_document_hash, read_signings, write_signings};
+use crate::grace::read_grace_period;
+use crate::status::read_status;
+use crate::storage_types::{AccessKey, NONCES};
+use crate::witness::read_witnesses;
+use crate::{DeadlineKind, DocumentStatus, Error, SignatureStatus};
+
+// Signers can only sign or reject; `Waiting`, `NotASigner` and `SignedLate`
+// are states the contract assigns itself.
+pub fn check_input_status(status: &SignatureStatus) -> Result<(), Error> {
+    match status {
+        SignatureStatus::Signed | SignatureStatus::Rejected => Ok(()),
+        _ => Err(Error::InvalidStatus),
+    }
+}
+
+// Whether any signer has signed or rejected yet.
+pub fn has_recorded_signatures(signings: &Map<Address, SignatureStatus>) -> bool {
+    signings
+        .values()
+        .iter()
+        .any(|status| status != SignatureStatus::Waiting && status != SignatureStatus::NotASigner)
+}
+
+// Signers that signed before per-signer nonces were introduced keep counting
+// from their entry in the legacy map.
+pub fn read_nonce(e: &Env, signer: &Address) -> u32 {
+    let key = AccessKey::SignNonce(signer.clone());
+    if let Some(nonce) = e.storage().persistent().get(&key) {
+        return nonce;
+    }
+    let legacy: Option<Map<Address, u32>> = e.storage().persistent().get(&NONCES);
+    legacy
+        .and_then(|nonces| nonces.get(signer.clone()))
+        .unwrap_or(0)
+}
+
+pub fn bump_nonce(e: &Env, signer: &Address) {
+    let key = AccessKey::SignNonce(signer.clone());
+    let nonce = read_nonce(e, signer).saturating_add(1);
+    e.storage().persistent().set(&key, &nonce);
+}
+
+// A document's signer map, loaded once per `sign_document` call, checked,
+// updated in memory and written back in a single store. All checks run
+// before anything is written, so no partial state is ever visible to a
+// cross-contract call made afterwards. Witnesses are part of the map but
+// never count towards completion.
+pub struct Signing {
+    token_id: u32,
+    signings: Map<Address, SignatureStatus>,
+    witnesses: Vec<Address>,
+}
+
+impl Signing {
+    pub fn load(e: &Env, token_id: u32, signer: &Address) -> Self {
+        match Self::try_load(e, token_id, signer) {
+            Ok(signing) => signing,
+            Err(error) => panic_with_error!(e, error),
+        }
+    }
+
+    // Witnesses can still sign once the parties have completed the document.
+    pub fn try_load(e: &Env, token_id: u32, signer: &Address) -> Result<Self, Error> {
+        let witnesses = read_witnesses(e, token_id);
+        let open = match read_status(e, token_id) {
+            DocumentStatus::Pending => true,
+            DocumentStatus::Completed => witnesses.contains(signer),
+            _ => false,
+        };
+        if !open {
+            return Err(Error::DocumentNotPending);
+        }
+        match read_signings(e, token_id) {
+            Some(signings) => Ok(Signing {
+                token_id,
+                signings,
+                witnesses,
+            }),
+            None => Err(Error::DocumentSigningsIsEmpty),
+        }
+    }
+
+    pub fn is_witness(&self, signer: &Address) -> bool {
+        self.witnesses.contains(signer)
+    }
+
+    // Returns whether the signature comes after the deadline, inside the
+    // document's grace period, along with the deadline that applied.
+    pub fn validate(
+        &self,
+        e: &Env,
+        signer: &Address,
+        document_hash: &String,
+    ) -> (bool, u64, DeadlineKind) {
+        let checked = self
+            .check_waiting(signer)
+            .and_then(|_| self.check_hash(e, document_hash))
+            .and_then(|_| self.check_timing(e, signer));
+        match checked {
+            Ok(timing) => timing,
+            Err(error) => panic_with_error!(e, error),
+        }
+    }
+
+    // Everything `validate` checks except the document hash, for dry runs
+    // that don't have one.
+    pub fn check_signer(&self, e: &Env, signer: &Address) -> Result<(), Error> {
+        self.check_waiting(signer)?;
+        self.check_timing(e, signer).map(|_| ())
+    }
+
+    fn check_waiting(&self, signer: &Address) -> Result<(), Error> {
+        match self.signings.get(signer.clone()) {
+            Some(SignatureStatus::Waiting) => Ok(()),
+            Some(SignatureStatus::NotASigner) => Err(Error::NotASigner),
+            Some(_) => Err(Error::AlreadySigned),
+            None => Err(Error::SignerDoesNotExist),
+        }
+    }
+
+    fn check_hash(&self, e: &Env, document_hash: &String) -> Result<(), Error> {
+        if read_document_hash(e, self.token_id).is_none() {
+            return Err(Error::HashNotFound);
+        }
+        if !has_document_hash(e, self.token_id, document_hash) {
+            return Err(Error::DocumentHashesDoesNotMatchTokenHash);
+        }
+        Ok(())
+    }
+
+    fn check_timing(&self, e: &Env, signer: &Address) -> Result<(bool, u64, DeadlineKind), Error> {
+        let (deadline, kind) = match applicable_deadline(e, self.token_id, signer) {
+            Some(applicable) => applicable,
+            None => return Err(Error::DeadlineNotFound),
+        };
+        let now = e.ledger().timestamp();
+        if now > deadline.saturating_add(read_grace_period(e, self.token_id)) {
+            return Err(Error::DeadlinePassed);
+        }
+
+        check_attested(e, self.token_id, signer)?;
+        Ok((now > deadline, deadline, kind))
+    }
+
+    pub fn record(&mut self, signer: Address, status: SignatureStatus) {
+        self.signings.set(signer, status);
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.signings
+            .iter()
+            .filter(|(signer, _)| !self.is_witness(signer))
+            .all(|(_, signature)| {
+                signature == SignatureStatus::Signed || signature == SignatureStatus::SignedLate
+            })
+    }
+
+    // Parties still waiting; witnesses aren't counted.
+    pub fn remaining(&self) -> u32 {
+        self.signings
+            .iter()
+            .filter(|(signer, signature)| {
+                *signature == SignatureStatus::Waiting && !self.is_witness(signer)
+            })
+            .count() as u32
+    }
+
+    pub fn store(&self, e: &Env) {
+        write_signings(e, self.token_id, &self.signings);
+    }
+}
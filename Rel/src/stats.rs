@@ -0,0 +1,68 @@
+use soroban_sdk::Env;
+
+use crate::event;
+use crate::storage_types::{ConfigKey, DataKey};
+use crate::ContractStats;
+
+pub fn read_stats(e: &Env) -> ContractStats {
+    let key = DataKey::Stats;
+    e.storage().instance().get(&key).unwrap_or(ContractStats {
+        total_minted: 0,
+        total_completed: 0,
+        total_expired: 0,
+        total_signatures: 0,
+        total_fees_collected: 0,
+    })
+}
+
+// Every `interval` counter updates a snapshot of all counters is published,
+// so monitoring can follow activity from events alone. Zero turns it off.
+pub fn read_metrics_interval(e: &Env) -> u32 {
+    let key = ConfigKey::MetricsInterval;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_metrics_interval(e: &Env, interval: u32) {
+    let key = ConfigKey::MetricsInterval;
+    e.storage().instance().set(&key, &interval);
+}
+
+fn update_stats(e: &Env, update: impl FnOnce(&mut ContractStats)) {
+    let mut stats = read_stats(e);
+    update(&mut stats);
+    let key = DataKey::Stats;
+    e.storage().instance().set(&key, &stats);
+
+    let interval = read_metrics_interval(e);
+    if interval == 0 {
+        return;
+    }
+    let key = ConfigKey::MetricsUpdates;
+    let updates: u32 = e.storage().instance().get(&key).unwrap_or(0) + 1;
+    if updates >= interval {
+        e.storage().instance().set(&key, &0_u32);
+        event::metrics(e, stats);
+    } else {
+        e.storage().instance().set(&key, &updates);
+    }
+}
+
+pub fn record_mint(e: &Env) {
+    update_stats(e, |stats| stats.total_minted += 1);
+}
+
+pub fn record_completed(e: &Env) {
+    update_stats(e, |stats| stats.total_completed += 1);
+}
+
+pub fn record_expired(e: &Env) {
+    update_stats(e, |stats| stats.total_expired += 1);
+}
+
+pub fn record_signature(e: &Env) {
+    update_stats(e, |stats| stats.total_signatures += 1);
+}
+
+pub fn record_fee(e: &Env, amount: i128) {
+    update_stats(e, |stats| stats.total_fees_collected += amount);
+}
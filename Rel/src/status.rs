@@ -0,0 +1,105 @@
+use soroban_sdk::{Env, Vec};
+
+use crate::storage_types::{ConfigKey, DataKey, IndexKey, StatusKey};
+use crate::DocumentStatus;
+
+// Documents minted before statuses were tracked have no entry and are
+// treated as pending.
+pub fn read_status(e: &Env, token_id: u32) -> DocumentStatus {
+    let key = DataKey::Status(token_id);
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or(DocumentStatus::Pending)
+}
+
+// Every transition also moves the token between the per-status index lists.
+pub fn write_status(e: &Env, token_id: u32, status: DocumentStatus) {
+    let key = DataKey::Status(token_id);
+    let previous: Option<DocumentStatus> = e.storage().persistent().get(&key);
+    if previous == Some(status) {
+        return;
+    }
+    if let Some(previous) = previous {
+        remove_from_index(e, token_id, previous);
+    }
+    add_to_index(e, token_id, status);
+    e.storage().persistent().set(&key, &status);
+}
+
+pub fn read_index_len(e: &Env, status: DocumentStatus) -> u32 {
+    let key = IndexKey::ByStatusLen(status);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+fn add_to_index(e: &Env, token_id: u32, status: DocumentStatus) {
+    let storage = e.storage().persistent();
+    let index = read_index_len(e, status);
+    storage.set(&IndexKey::ByStatus(StatusKey { status, index }), &token_id);
+    storage.set(&IndexKey::StatusSlot(token_id), &index);
+    storage.set(&IndexKey::ByStatusLen(status), &(index + 1));
+}
+
+// Swap-remove: the last token of the list takes the removed token's slot.
+fn remove_from_index(e: &Env, token_id: u32, status: DocumentStatus) {
+    let storage = e.storage().persistent();
+    let index: u32 = match storage.get(&IndexKey::StatusSlot(token_id)) {
+        Some(index) => index,
+        None => return,
+    };
+    let last = read_index_len(e, status) - 1;
+    let last_key = IndexKey::ByStatus(StatusKey {
+        status,
+        index: last,
+    });
+    if index != last {
+        let moved: u32 = storage.get(&last_key).unwrap();
+        storage.set(&IndexKey::ByStatus(StatusKey { status, index }), &moved);
+        storage.set(&IndexKey::StatusSlot(moved), &index);
+    }
+    storage.remove(&last_key);
+    storage.remove(&IndexKey::StatusSlot(token_id));
+    storage.set(&IndexKey::ByStatusLen(status), &last);
+}
+
+// Lists tokens by their recorded status. Pending documents past their
+// deadline stay listed as pending until `expire_document` records the
+// expiry, and documents minted before statuses were tracked only show up
+// after their first transition. Order changes as tokens leave a list.
+pub fn read_tokens_by_status(e: &Env, status: DocumentStatus, start: u32, limit: u32) -> Vec<u32> {
+    let end = read_index_len(e, status).min(start.saturating_add(limit));
+    let mut token_ids = Vec::new(e);
+    for index in start..end {
+        let key = IndexKey::ByStatus(StatusKey { status, index });
+        if let Some(token_id) = e.storage().persistent().get(&key) {
+            token_ids.push_back(token_id);
+        }
+    }
+    token_ids
+}
+
+pub fn read_token_at(e: &Env, status: DocumentStatus, index: u32) -> Option<u32> {
+    let key = IndexKey::ByStatus(StatusKey { status, index });
+    e.storage().persistent().get(&key)
+}
+
+// Position in the pending index where the next `sweep_expired` resumes.
+pub fn read_sweep_cursor(e: &Env) -> u32 {
+    let key = ConfigKey::SweepCursor;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_sweep_cursor(e: &Env, cursor: u32) {
+    let key = ConfigKey::SweepCursor;
+    e.storage().instance().set(&key, &cursor);
+}
+
+pub fn read_completed_at(e: &Env, token_id: u32) -> Option<u64> {
+    let key = DataKey::CompletedAt(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_completed_at(e: &Env, token_id: u32, timestamp: u64) {
+    let key = DataKey::CompletedAt(token_id);
+    e.storage().persistent().set(&key, &timestamp);
+}
@@ -1,4 +1,6 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, symbol_short, Address, String, Symbol};
+
+use crate::{BountyAction, DocumentStatus, RateAction};
 
 pub(crate) const INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK: u32 = 34560; // 2 days
 pub(crate) const INSTANCE_BUMP_AMOUNT_LOW_WATERMARK: u32 = 17280; // 1 day
@@ -6,6 +8,32 @@ pub(crate) const INSTANCE_BUMP_AMOUNT_LOW_WATERMARK: u32 = 17280; // 1 day
 pub(crate) const BALANCE_BUMP_AMOUNT_HIGH_WATERMARK: u32 = 518400; // 30 days
 pub(crate) const BALANCE_BUMP_AMOUNT_LOW_WATERMARK: u32 = 259200; // 15 days
 
+// Raw symbol keys written by earlier versions. Everything new goes under one
+// of the typed key enums below; these are only read, until `migrate` (or the
+// next write of the value) has moved their contents.
+pub(crate) const OWNERS: Symbol = symbol_short!("OWNERS");
+pub(crate) const URIS: Symbol = symbol_short!("URIS");
+pub(crate) const NONCES: Symbol = symbol_short!("NONCES");
+pub(crate) const T2DHASH: Symbol = symbol_short!("T2DHASH");
+pub(crate) const DEADLINES: Symbol = symbol_short!("DEADLINES");
+pub(crate) const DOCSIGN: Symbol = symbol_short!("DOCSIGN");
+pub(crate) const CREATION_FEE: Symbol = symbol_short!("crea_fee");
+
+pub(crate) const DEFAULT_REMINDER_INTERVAL: u32 = 17280; // 1 day
+pub(crate) const PROPOSAL_LIFETIME: u32 = 120960; // 7 days
+
+pub(crate) const DEFAULT_MAX_URI_LEN: u32 = 256; // bytes
+pub(crate) const DEFAULT_MAX_HASH_LEN: u32 = 128; // bytes
+pub(crate) const DEFAULT_MAX_BATCH_SIZE: u32 = 50;
+pub(crate) const MAX_LINKS_PER_DOCUMENT: u32 = 20;
+pub(crate) const MAX_ATTRIBUTES: u32 = 16;
+pub(crate) const MAX_ATTRIBUTE_LEN: u32 = 256; // bytes
+pub(crate) const MAX_EXTRA_HASHES: u32 = 4;
+pub(crate) const MAX_NOTE_LEN: u32 = 256; // bytes
+pub(crate) const MAX_SIGNER_HISTORY: u32 = 8;
+pub(crate) const DEFAULT_MAX_REREQUESTS: u32 = 3;
+pub(crate) const MAX_REMINDER_BOUNTIES: u32 = 3; // per document
+
 #[derive(Clone)]
 #[contracttype]
 pub struct AllowanceDataKey {
@@ -19,6 +47,27 @@ pub struct AllowanceValue {
     pub expiration_ledger: u32,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct AuditKey {
+    pub token_id: u32,
+    pub index: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct StatusKey {
+    pub status: DocumentStatus,
+    pub index: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct PollVoteKey {
+    pub token_id: u32,
+    pub voter: Address,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
@@ -26,5 +75,197 @@ pub enum DataKey {
     Balance(Address),
     Nonce(Address),
     State(Address),
-    Admin,
+    // `Admin` and `Owner(u32)` are `petal_common::SharedKey` entries.
+    TokenContract,
+    FeeToken,
+    Status(u32),
+    Escrow(u32),
+    Reminder(u32),
+    ReminderSent(u32),
+    ReminderInterval,
+    AuditLen(u32),
+    AuditEntry(AuditKey),
+    Stats,
+    SignerGroup(Symbol),
+    Template(u32),
+    NextTokenId,
+    Admins,
+    Threshold,
+    Proposal(u32),
+    NextProposalId,
+    ClawbackReason(u32),
+    Uri(u32),
+    DocumentHash(u32),
+    Deadline(u32),
+    Signings(u32),
+    TokenCount,
+    TokenByIndex(u32),
+    MigrationCursor,
+    VotingToken,
+    Poll(u32),
+    PollVote(PollVoteKey),
+    CompletedAt(u32),
+    UriPrefixes,
+    StringLimits,
+    Attestor(Address),
+    Attestation(Address),
+    RequiredAttestation(u32),
+    Callback(u32),
+    TokenByHash(String),
+    UniqueHashes,
+    Creator(u32),
+    GracePeriod,
+    DocGracePeriod(u32),
+    Archived(u32),
+    MaxBatchSize,
+    SignerDeadlines(u32),
+    FeePaid(u32),
+}
+
+// `DataKey` is at the 50 variant limit of a contract type, so lookup indexes
+// added since get their own key type. Variant names stay distinct from the
+// ones above so the two never produce the same storage key.
+#[derive(Clone)]
+#[contracttype]
+pub enum IndexKey {
+    ByStatusLen(DocumentStatus),
+    ByStatus(StatusKey),
+    StatusSlot(u32),
+}
+
+// Per-document settings added after `DataKey` filled up, named apart from
+// its variants for the same reason as `IndexKey`.
+#[derive(Clone)]
+#[contracttype]
+pub enum DocKey {
+    CoOwners(u32),
+    OwnerAuthMode(u32),
+    ViewGrant(ViewGrantKey),
+    Dispute(u32),
+    Arbitration(u32),
+    Arbiter(u32),
+    Links(u32),
+    Attributes(u32),
+    HashAlgo(u32),
+    ExtraHashes(u32),
+    SignerHistory(SignerKey),
+    Rerequests(SignerKey),
+    Referral(u32),
+    PaidWithCredit(u32),
+    TransferableAt(u32),
+    RevocationWindow(u32),
+    Witnesses(u32),
+    ReminderBounties(u32),
+    EscrowRefunded(u32),
+    ArchiveVersion(u32),
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ReferralKey {
+    pub referrer: Address,
+    pub fee_token: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct ViewGrantKey {
+    pub token_id: u32,
+    pub grantee: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct SignerKey {
+    pub token_id: u32,
+    pub signer: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct CollectionItemKey {
+    pub collection_id: u32,
+    pub index: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum CollectionKey {
+    NextCollectionId,
+    Collection(u32),
+    CollectionItem(CollectionItemKey),
+    DocCollection(u32),
+    CollectionSlot(u32),
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct OrgMemberKey {
+    pub org_id: u32,
+    pub member: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct OrgItemKey {
+    pub org_id: u32,
+    pub index: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum OrgKey {
+    NextOrgId,
+    Org(u32),
+    Member(OrgMemberKey),
+    Document(OrgItemKey),
+    DocOrg(u32),
+}
+
+// Contract-wide settings and counters added after `DataKey` filled up.
+#[derive(Clone)]
+#[contracttype]
+pub enum ConfigKey {
+    MetricsInterval,
+    MetricsUpdates,
+    RateLimit(RateAction),
+    RateWindow(RateWindowKey),
+    MinterAllowlist,
+    FeeSchedule,
+    SweepCursor,
+    // Untagged pool from before balances were kept per token.
+    BountyPool,
+    BountyBalance(Address),
+    BountyReward(BountyAction),
+    CreationFee,
+    TestInt,
+    Arbiter,
+    Sealed,
+    // `AdminRenounceable` and `RenounceRequested` are `petal_common::SharedKey`
+    // entries.
+    MaxRerequests,
+    RerequestNeedsAmendment,
+    DiscountToken,
+    FeeDiscounts,
+    ReferralShare,
+    TransferLockLedgers,
+    CollectedFees(Address),
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum AccessKey {
+    Minter(Address),
+    FeeTier(Address),
+    KeeperEarnings(Address),
+    SignNonce(Address),
+    Referral(ReferralKey),
+    Credits(Address),
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RateWindowKey {
+    pub action: RateAction,
+    pub addr: Address,
 }
@@ -0,0 +1,26 @@
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::storage_types::DataKey;
+use crate::{Error, Template};
+
+pub fn read_template(e: &Env, template_id: u32) -> Option<Template> {
+    let key = DataKey::Template(template_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_template(e: &Env, template_id: u32, template: &Template) {
+    if template.required_signers.is_empty() {
+        panic_with_error!(e, Error::SignersListEmpty)
+    }
+    let key = DataKey::Template(template_id);
+    e.storage().persistent().set(&key, template);
+}
+
+// Template mints pick their own token id, continuing after the last one handed
+// out. Ids taken by explicit `safe_mint` calls are skipped by the caller.
+pub fn take_next_token_id(e: &Env) -> u32 {
+    let key = DataKey::NextTokenId;
+    let token_id: u32 = e.storage().instance().get(&key).unwrap_or(1);
+    e.storage().instance().set(&key, &(token_id + 1));
+    token_id
+}
@@ -0,0 +1,4586 @@
+#![cfg(test)]
+extern crate std;
+
+use crate::event::{ExpiredData, MintData, ReopenedData, EVENT_SCHEMA_VERSION};
+use crate::storage_types::{CREATION_FEE, DEADLINES, DOCSIGN, OWNERS, T2DHASH, URIS};
+#[cfg(feature = "token-contract")]
+use crate::token_contract::erc721;
+use crate::{
+    admin, attribute, dispute, document, escrow, fee, limits, link, minters, org, poll, ratelimit,
+    reopen, rerequest, revocation, signing, testutils, uri,
+};
+use crate::{
+    AdminSet, Arbitration, ArbitrationOutcome, AuditAction, BountyAction, Callback, Certificate,
+    ContractStats, CreditGrant, DeadlineKind, Dispute, DocumentCall, DocumentHash, DocumentLink,
+    DocumentOp, DocumentState, DocumentStatus, Error, EscrowDeposit, ExtendedError, FeeDiscount,
+    FeeTierChange, FeeWithdrawal, HashAlgo, MintOption, NativeFeeQuote, OrgRole, OwnerAuth,
+    PetalDocumentsClient, PollOutcome, ProposalAction, RateAction, RateLimit, ReferralAccount,
+    SignReceipt, SignRequest, SignatureStatus, StatusChange, StringLimits, CONTRACT_VERSION,
+};
+use soroban_sdk::{
+    auth::Context,
+    contract, contracterror, contractimpl, map, symbol_short,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events, Ledger},
+    token, vec,
+    xdr::{
+        ContractEventBody, InvokeContractArgs, ScAddress, ScVal, SorobanAddressCredentials,
+        SorobanAuthorizationEntry, SorobanAuthorizedFunction, SorobanAuthorizedInvocation,
+        SorobanCredentials, ToXdr, VecM,
+    },
+    Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
+};
+
+// Events published by the last invocation, as (contract, topics, data).
+fn all_events(e: &Env) -> Vec<(Address, Vec<Val>, Val)> {
+    let mut out = Vec::new(e);
+    for event in e.events().all().events() {
+        let contract = event.contract_id.clone().unwrap();
+        let ContractEventBody::V0(body) = &event.body;
+        let mut topics = Vec::new(e);
+        for topic in body.topics.iter() {
+            topics.push_back(Val::try_from_val(e, topic).unwrap());
+        }
+        out.push_back((
+            Address::try_from_val(e, &ScAddress::Contract(contract)).unwrap(),
+            topics,
+            Val::try_from_val(e, &body.data).unwrap(),
+        ));
+    }
+    out
+}
+
+#[contract]
+struct CallbackReceiver;
+
+#[contractimpl]
+impl CallbackReceiver {
+    pub fn on_final(e: Env, doc_id: u32, status: DocumentStatus) {
+        e.storage().instance().set(&doc_id, &status);
+    }
+}
+
+// A fungible token with per-ledger balance checkpoints, like the workspace
+// token contract, for polls to read their snapshots from.
+#[contract]
+struct SnapshotToken;
+
+#[contractimpl]
+impl SnapshotToken {
+    pub fn set_balance(e: Env, id: Address, balance: i128) {
+        let mut checkpoints: Vec<(u32, i128)> =
+            e.storage().instance().get(&id).unwrap_or(Vec::new(&e));
+        checkpoints.push_back((e.ledger().sequence(), balance));
+        e.storage().instance().set(&id, &checkpoints);
+    }
+
+    pub fn balance_at(e: Env, id: Address, ledger_seq: u32) -> i128 {
+        let checkpoints: Vec<(u32, i128)> = e.storage().instance().get(&id).unwrap_or(Vec::new(&e));
+        checkpoints
+            .iter()
+            .filter(|(ledger, _)| *ledger <= ledger_seq)
+            .last()
+            .map(|(_, balance)| balance)
+            .unwrap_or(0)
+    }
+
+    pub fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        let ledger_seq = e.ledger().sequence();
+        let from_balance = Self::balance_at(e.clone(), from.clone(), ledger_seq);
+        let to_balance = Self::balance_at(e.clone(), to.clone(), ledger_seq);
+        Self::set_balance(e.clone(), from, from_balance - amount);
+        Self::set_balance(e, to, to_balance + amount);
+    }
+}
+
+// A smart wallet standing in for signers that are custom account contracts.
+// It takes a fixed PIN as its signature and counts the `sign` calls it
+// authorized.
+#[contract]
+struct SmartWallet;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+enum WalletError {
+    WrongPin = 1,
+}
+
+const WALLET_PIN: u32 = 1234;
+
+#[contractimpl]
+impl SmartWallet {
+    #[allow(non_snake_case)]
+    pub fn __check_auth(
+        e: Env,
+        _signature_payload: BytesN<32>,
+        pin: u32,
+        auth_contexts: Vec<Context>,
+    ) -> Result<(), WalletError> {
+        if pin != WALLET_PIN {
+            return Err(WalletError::WrongPin);
+        }
+        for context in auth_contexts.iter() {
+            if let Context::Contract(call) = context {
+                if call.fn_name == symbol_short!("sign") {
+                    let count: u32 = e.storage().instance().get(&call.fn_name).unwrap_or(0);
+                    e.storage().instance().set(&call.fn_name, &(count + 1));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// An authorization signed by `wallet` for `contract.fn_name(args)`, checked
+// by the wallet's `__check_auth` rather than mocked.
+fn wallet_auth(
+    e: &Env,
+    wallet: &Address,
+    contract: &Address,
+    fn_name: &str,
+    args: Vec<Val>,
+    nonce: i64,
+) -> SorobanAuthorizationEntry {
+    SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+            address: wallet.into(),
+            nonce,
+            signature_expiration_ledger: e.ledger().sequence() + 100,
+            signature: ScVal::U32(WALLET_PIN),
+        }),
+        root_invocation: SorobanAuthorizedInvocation {
+            function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+                contract_address: contract.into(),
+                function_name: fn_name.try_into().unwrap(),
+                args: args.into(),
+            }),
+            sub_invocations: VecM::default(),
+        },
+    }
+}
+
+fn create_documents<'a>(e: &Env, admin: &Address) -> PetalDocumentsClient<'a> {
+    testutils::register(e, admin)
+}
+
+fn create_fee_token<'a>(e: &Env, admin: &Address) -> token::Client<'a> {
+    token::Client::new(
+        e,
+        &e.register_stellar_asset_contract_v2(admin.clone())
+            .address(),
+    )
+}
+
+#[test]
+fn test_mint_and_sign() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(documents.get_owners().get(1), Some(owner));
+    assert_eq!(
+        documents.get_token_uri(&1),
+        String::from_str(&e, "ipfs://doc1")
+    );
+    assert_eq!(
+        documents.get_document(&1).get(signer1.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+
+    let receipt = documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1);
+    assert_eq!(
+        receipt,
+        SignReceipt {
+            token_id: 1,
+            signer: signer1.clone(),
+            status: SignatureStatus::Signed,
+            remaining_signers: 1,
+            completed: false,
+        }
+    );
+    assert_eq!(documents.version(), CONTRACT_VERSION);
+    let document = documents.get_document(&1);
+    assert_eq!(document.get(signer1), Some(SignatureStatus::Signed));
+    assert_eq!(document.get(signer2), Some(SignatureStatus::Waiting));
+}
+
+#[test]
+fn test_sign_receipt_outcomes() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let witness = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    for (token_id, hash) in [(1, "hash1"), (2, "hash2")] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer1.clone(), signer2.clone()],
+            &String::from_str(&e, hash),
+            &1000,
+            &vec![
+                &e,
+                MintOption::GracePeriod(100),
+                MintOption::Witnesses(vec![&e, witness.clone()]),
+            ],
+        );
+    }
+
+    // The receipt only covers its own document; the witness still waiting
+    // isn't a remaining signer.
+    let hash1 = String::from_str(&e, "hash1");
+    documents.sign_document(&hash1, &signer1, &SignatureStatus::Signed, &1);
+    e.ledger().with_mut(|li| li.timestamp = 1050);
+    assert_eq!(
+        documents.sign_document(&hash1, &signer2, &SignatureStatus::Signed, &1),
+        SignReceipt {
+            token_id: 1,
+            signer: signer2.clone(),
+            status: SignatureStatus::SignedLate,
+            remaining_signers: 0,
+            completed: true,
+        }
+    );
+    // A witness signing afterwards doesn't complete the document again.
+    assert_eq!(
+        documents.sign_document(&hash1, &witness, &SignatureStatus::Signed, &1),
+        SignReceipt {
+            token_id: 1,
+            signer: witness.clone(),
+            status: SignatureStatus::SignedLate,
+            remaining_signers: 0,
+            completed: false,
+        }
+    );
+
+    let hash2 = String::from_str(&e, "hash2");
+    assert_eq!(
+        documents.sign_document(&hash2, &signer1, &SignatureStatus::Rejected, &2),
+        SignReceipt {
+            token_id: 2,
+            signer: signer1,
+            status: SignatureStatus::Rejected,
+            remaining_signers: 1,
+            completed: false,
+        }
+    );
+    assert_eq!(documents.get_document_status(&2), DocumentStatus::Rejected);
+}
+
+#[test]
+fn test_creation_fee() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&owner, &1000);
+
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&100);
+    assert_eq!(documents.get_fee_token(), Some(fee_token.address.clone()));
+    assert_eq!(documents.get_creation_fee(), 100);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(fee_token.balance(&owner), 900);
+    assert_eq!(fee_token.balance(&documents.address), 100);
+
+    // The admin does not pay for its own mints.
+    documents.safe_mint(
+        &admin,
+        &2,
+        &String::from_str(&e, "ipfs://doc2"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash2"),
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(fee_token.balance(&documents.address), 100);
+    assert_eq!(documents.get_stats().total_fees_collected, 100);
+    assert_eq!(documents.get_stats().total_minted, 2);
+
+    assert_eq!(documents.get_collected_fees(), 100);
+    documents.withdraw_fees(&admin, &100);
+    assert_eq!(fee_token.balance(&admin), 100);
+    assert_eq!(fee_token.balance(&documents.address), 0);
+    assert_eq!(documents.get_collected_fees(), 0);
+}
+
+#[test]
+fn test_withdraw_fees_leaves_escrow() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&owner, &1000);
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&100);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![
+            &e,
+            MintOption::Escrow(EscrowDeposit {
+                token: fee_token.address.clone(),
+                amount: 500,
+                payee: Address::generate(&e),
+            }),
+        ],
+    );
+    assert_eq!(fee_token.balance(&documents.address), 600);
+    assert_eq!(documents.get_collected_fees(), 100);
+
+    let to = Address::generate(&e);
+    let result = e.as_contract(&documents.address, || fee::withdraw(&e, &to, 101));
+    assert_eq!(result, Err(ExtendedError::InsufficientFees.into()));
+    documents.withdraw_fees(&to, &100);
+    let result = e.as_contract(&documents.address, || fee::withdraw(&e, &to, 1));
+    assert_eq!(result, Err(ExtendedError::InsufficientFees.into()));
+    assert_eq!(fee_token.balance(&documents.address), 500);
+    assert_eq!(documents.get_escrow(&1).unwrap().amount, 500);
+}
+
+#[test]
+fn test_escrow_released_on_completion() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let payee = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let escrow_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &escrow_token.address).mint(&owner, &500);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![
+            &e,
+            MintOption::Escrow(EscrowDeposit {
+                token: escrow_token.address.clone(),
+                amount: 500,
+                payee: payee.clone(),
+            }),
+        ],
+    );
+    assert_eq!(escrow_token.balance(&documents.address), 500);
+    assert_eq!(documents.get_escrow(&1).unwrap().payee, payee);
+
+    let receipt = documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    assert!(receipt.completed);
+    assert_eq!(receipt.remaining_signers, 0);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Completed);
+
+    documents.claim_escrow(&1);
+    assert_eq!(escrow_token.balance(&payee), 500);
+    assert_eq!(documents.get_escrow(&1), None);
+}
+
+#[test]
+fn test_escrow_refunded_on_expiry() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let payee = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let escrow_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &escrow_token.address).mint(&owner, &500);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![
+            &e,
+            MintOption::Escrow(EscrowDeposit {
+                token: escrow_token.address.clone(),
+                amount: 500,
+                payee,
+            }),
+        ],
+    );
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Pending);
+
+    e.ledger().with_mut(|li| li.timestamp = 1001);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Expired);
+
+    documents.refund_escrow(&1);
+    assert_eq!(documents.get_stats().total_expired, 1);
+    assert_eq!(escrow_token.balance(&owner), 500);
+    assert_eq!(documents.get_escrow(&1), None);
+
+    // Reopening would let the document complete with nothing left to pay.
+    let result = e.as_contract(&documents.address, || escrow::check_not_refunded(&e, 1));
+    assert_eq!(result, Err(ExtendedError::EscrowRefunded));
+}
+
+#[test]
+fn test_emit_reminder() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &vec![&e, MintOption::Reminder(500)],
+    );
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1);
+    assert_eq!(documents.get_reminder(&1), Some(500));
+
+    assert_eq!(
+        documents.try_emit_reminder(&owner, &1),
+        Err(Ok(Error::ReminderNotDue))
+    );
+    e.ledger().with_mut(|li| li.timestamp = 600);
+    assert_eq!(
+        documents.emit_reminder(&owner, &1),
+        vec![&e, signer2.clone()]
+    );
+    assert_eq!(
+        documents.try_emit_reminder(&owner, &1),
+        Err(Ok(Error::ReminderRateLimited))
+    );
+
+    let interval = documents.get_reminder_interval();
+    e.ledger().with_mut(|li| li.sequence_number += interval);
+    assert_eq!(documents.emit_reminder(&owner, &1), vec![&e, signer2]);
+}
+
+#[test]
+fn test_reminder_bounty_cap() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let keeper = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&admin, &1000);
+    documents.set_fee_token(&fee_token.address);
+    documents.fund_bounty_pool(&100);
+    documents.set_bounty_reward(&BountyAction::Reminder, &5);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e, MintOption::Reminder(500)],
+    );
+    e.ledger().with_mut(|li| li.timestamp = 600);
+
+    // The creator reminding about their own document earns nothing.
+    documents.emit_reminder(&owner, &1);
+    assert_eq!(documents.get_keeper_earnings(&owner), 0);
+
+    for _ in 0..5 {
+        let interval = documents.get_reminder_interval();
+        e.ledger().with_mut(|li| li.sequence_number += interval);
+        documents.emit_reminder(&keeper, &1);
+    }
+    assert_eq!(documents.get_keeper_earnings(&keeper), 15);
+    assert_eq!(documents.get_bounty_pool(), 85);
+}
+
+#[test]
+fn test_audit_log() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    e.ledger().with_mut(|li| li.timestamp = 10);
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    e.ledger().with_mut(|li| li.timestamp = 20);
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1);
+    documents.sign_document(&hash, &signer2, &SignatureStatus::Rejected, &1);
+
+    let log = documents.get_audit_log(&1, &0, &10);
+    assert_eq!(log.len(), 3);
+    let minted = log.get(0).unwrap();
+    assert_eq!(minted.action, AuditAction::Minted);
+    assert_eq!(minted.actor, owner);
+    assert_eq!(minted.timestamp, 10);
+    assert_eq!(log.get(1).unwrap().actor, signer1);
+    assert_eq!(log.get(2).unwrap().action, AuditAction::Rejected);
+
+    let page = documents.get_audit_log(&1, &1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().action, AuditAction::Signed);
+}
+
+#[test]
+fn test_get_document_details() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+
+    let details = documents.get_document_details(&1);
+    assert_eq!(details.owner, owner);
+    assert_eq!(details.uri, String::from_str(&e, "ipfs://doc1"));
+    assert_eq!(details.document_hash, hash);
+    assert_eq!(details.deadline, 1000);
+    assert_eq!(details.status, DocumentStatus::Completed);
+    assert_eq!(details.signers.get(signer), Some(SignatureStatus::Signed));
+
+    let stats = documents.get_stats();
+    assert_eq!(stats.total_minted, 1);
+    assert_eq!(stats.total_completed, 1);
+    assert_eq!(stats.total_signatures, 1);
+
+    assert_eq!(
+        documents.try_get_document_details(&2),
+        Err(Ok(Error::TokenDoesNotExist))
+    );
+}
+
+#[test]
+fn test_mint_with_signer_group() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let name = symbol_short!("legal");
+    documents.create_group(&owner, &name, &vec![&e, signer1.clone()]);
+    // The admin may replace a group without taking it over.
+    documents.create_group(&admin, &name, &vec![&e, signer1.clone(), signer2.clone()]);
+    let group = documents.get_group(&name).unwrap();
+    assert_eq!(group.owner, owner);
+    assert_eq!(group.signers.len(), 2);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e, MintOption::Group(name)],
+    );
+    let document = documents.get_document(&1);
+    assert_eq!(document.get(signer1), Some(SignatureStatus::Waiting));
+    assert_eq!(document.get(signer2), Some(SignatureStatus::Waiting));
+}
+
+#[test]
+fn test_mint_from_template() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let nda = String::from_str(&e, "ipfs://nda");
+    documents.create_template(&7, &500, &vec![&e, signer.clone()], &nda);
+    assert_eq!(documents.get_template(&7).unwrap().metadata, nda);
+
+    // Explicitly minted ids are skipped.
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    e.ledger().with_mut(|li| li.timestamp = 100);
+    let token_id = documents.mint_from_template(&7, &owner, &String::from_str(&e, "hash2"));
+    assert_eq!(token_id, 2);
+
+    let details = documents.get_document_details(&token_id);
+    assert_eq!(details.owner, owner);
+    assert_eq!(details.uri, nda);
+    assert_eq!(details.deadline, 600);
+    assert_eq!(
+        details.signers.get(signer.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+
+    documents.create_template(&8, &u64::MAX, &vec![&e, signer], &nda);
+    let token_id = documents.mint_from_template(&8, &owner, &String::from_str(&e, "hash3"));
+    assert_eq!(documents.get_document_details(&token_id).deadline, u64::MAX);
+}
+
+#[test]
+fn test_threshold_approval() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let admin2 = Address::generate(&e);
+    let admin3 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert_eq!(documents.get_admins(), vec![&e, admin.clone()]);
+
+    // A single admin's proposal executes immediately.
+    let admins = vec![&e, admin.clone(), admin2.clone(), admin3.clone()];
+    documents.propose_action(
+        &admin,
+        &ProposalAction::SetAdmins(AdminSet {
+            admins: admins.clone(),
+            threshold: 2,
+        }),
+    );
+    assert_eq!(documents.get_admins(), admins);
+    assert_eq!(documents.get_threshold(), 2);
+
+    let proposal_id = documents.propose_action(&admin2, &ProposalAction::SetCreationFee(50));
+    assert_eq!(documents.get_creation_fee(), 0);
+    assert_eq!(
+        documents
+            .get_proposal(&proposal_id)
+            .unwrap()
+            .approvals
+            .len(),
+        1
+    );
+
+    assert!(documents.approve_action(&admin3, &proposal_id));
+    assert_eq!(documents.get_creation_fee(), 50);
+    assert_eq!(documents.get_proposal(&proposal_id), None);
+
+    // One admin listed twice doesn't make a 2-of-2 set.
+    let doubled = vec![&e, admin.clone(), admin.clone()];
+    assert_eq!(
+        admin::check_admins(&doubled, 2),
+        Err(ExtendedError::DuplicateAdmin.into())
+    );
+    assert_eq!(
+        admin::check_admins(&vec![&e, admin.clone()], 2),
+        Err(Error::InvalidThreshold.into())
+    );
+    assert_eq!(admin::check_admins(&admins, 3), Ok(()));
+}
+
+#[test]
+fn test_fund_movements_need_threshold() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let admin2 = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&owner, &1000);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&admin2, &1000);
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&100);
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+
+    documents.propose_action(
+        &admin,
+        &ProposalAction::SetAdmins(AdminSet {
+            admins: vec![&e, admin.clone(), admin2.clone()],
+            threshold: 2,
+        }),
+    );
+
+    let to = Address::generate(&e);
+    let withdrawal = FeeWithdrawal {
+        to: to.clone(),
+        amount: 100,
+    };
+    let proposal_id = documents.propose_action(&admin, &ProposalAction::WithdrawFees(withdrawal));
+    assert_eq!(fee_token.balance(&to), 0);
+    documents.approve_action(&admin2, &proposal_id);
+    assert_eq!(fee_token.balance(&to), 100);
+    assert_eq!(documents.get_collected_fees(), 0);
+
+    let grant = CreditGrant {
+        to: owner.clone(),
+        credits: 2,
+    };
+    let proposal_id = documents.propose_action(&admin, &ProposalAction::GrantCredits(grant));
+    documents.approve_action(&admin2, &proposal_id);
+    assert_eq!(documents.get_credits(&owner), 2);
+
+    let proposal_id = documents.propose_action(&admin, &ProposalAction::SetReferralShare(500));
+    documents.approve_action(&admin2, &proposal_id);
+    assert_eq!(documents.get_referral_share(), 500);
+
+    // The admin whose approval executes the proposal funds the pool.
+    let proposal_id = documents.propose_action(&admin, &ProposalAction::FundBountyPool(40));
+    documents.approve_action(&admin2, &proposal_id);
+    assert_eq!(documents.get_bounty_pool(), 40);
+    assert_eq!(fee_token.balance(&admin2), 960);
+}
+
+#[test]
+fn test_clawback_document() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let custodian = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+
+    let reason = String::from_str(&e, "court order");
+    documents.clawback_document(&1, &custodian, &reason);
+    assert_eq!(documents.get_owners().get(1), Some(custodian));
+    assert_eq!(
+        documents.get_document_status(&1),
+        DocumentStatus::ClawedBack
+    );
+    assert_eq!(documents.get_clawback_reason(&1), Some(reason));
+    // The creator is unaffected by the ownership change.
+    assert_eq!(documents.get_creator(&1), owner);
+
+    let log = documents.get_audit_log(&1, &0, &10);
+    assert_eq!(log.get(1).unwrap().action, AuditAction::ClawedBack);
+    assert_eq!(log.get(1).unwrap().actor, admin);
+}
+
+#[test]
+fn test_clawback_refunds_escrow() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let payee = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let custodian = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let escrow_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &escrow_token.address).mint(&owner, &500);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![
+            &e,
+            MintOption::Escrow(EscrowDeposit {
+                token: escrow_token.address.clone(),
+                amount: 500,
+                payee: payee.clone(),
+            }),
+        ],
+    );
+
+    let reason = String::from_str(&e, "court order");
+    documents.clawback_document(&1, &custodian, &reason);
+    assert_eq!(escrow_token.balance(&owner), 500);
+    assert_eq!(escrow_token.balance(&documents.address), 0);
+    assert_eq!(documents.get_escrow(&1), None);
+    assert_eq!(
+        documents.try_claim_escrow(&1),
+        Err(Ok(Error::EscrowNotReleasable.into()))
+    );
+
+    // A clawed back document can't be clawed back again.
+    assert_eq!(
+        documents.try_clawback_document(&1, &custodian, &reason),
+        Err(Ok(ExtendedError::DocumentNotClawbackable.into()))
+    );
+}
+
+#[test]
+fn test_clawback_rejects_completed() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let custodian = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    for (token_id, hash) in [(1, "hash1"), (2, "hash2")] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, hash),
+            &1000,
+            &vec![&e],
+        );
+    }
+    documents.sign_document(
+        &String::from_str(&e, "hash1"),
+        &signer,
+        &SignatureStatus::Signed,
+        &1,
+    );
+    documents.sign_document(
+        &String::from_str(&e, "hash2"),
+        &signer,
+        &SignatureStatus::Rejected,
+        &2,
+    );
+
+    let reason = String::from_str(&e, "court order");
+    assert_eq!(
+        documents.try_clawback_document(&1, &custodian, &reason),
+        Err(Ok(ExtendedError::DocumentNotClawbackable.into()))
+    );
+    assert_eq!(documents.get_owners().get(1), Some(owner));
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Completed);
+
+    // A rejected document never completed, so it can still be clawed back.
+    documents.clawback_document(&2, &custodian, &reason);
+    assert_eq!(
+        documents.get_document_status(&2),
+        DocumentStatus::ClawedBack
+    );
+}
+
+#[test]
+fn test_creator_keeps_permissions_after_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let creator = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &creator,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    e.as_contract(&documents.address, || {
+        document::write_owner(&e, 1, &buyer);
+    });
+    assert_eq!(documents.get_owners().get(1), Some(buyer.clone()));
+    assert_eq!(documents.get_creator(&1), creator);
+
+    // The new owner can't manage the document; its creator still can.
+    let xml_hash = String::from_str(&e, "xml-hash");
+    assert_eq!(
+        documents.try_add_document_hash(&buyer, &1, &HashAlgo::Unspecified, &xml_hash),
+        Err(Ok(ExtendedError::NotDocumentCreator.into()))
+    );
+    documents.add_document_hash(&creator, &1, &HashAlgo::Unspecified, &xml_hash);
+
+    documents.sign_document(&hash, &signer, &SignatureStatus::Rejected, &1);
+    assert_eq!(
+        documents.try_reopen_document(&buyer, &1, &2000, &true),
+        Err(Ok(ExtendedError::NotDocumentCreator.into()))
+    );
+    documents.reopen_document(&creator, &1, &2000, &true);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Pending);
+
+    assert_eq!(
+        documents.try_get_creator(&2),
+        Err(Ok(Error::TokenDoesNotExist))
+    );
+
+    // Documents minted before creators were recorded fall back to the owner.
+    e.as_contract(&documents.address, || {
+        e.storage()
+            .persistent()
+            .remove(&crate::storage_types::DataKey::Creator(1));
+    });
+    assert_eq!(documents.get_creator(&1), buyer);
+}
+
+#[test]
+fn test_migrate_legacy_maps() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    // Seed three documents in the pre-migration layout.
+    e.as_contract(&documents.address, || {
+        let mut owners: Map<u32, Address> = Map::new(&e);
+        let mut uris: Map<u32, String> = Map::new(&e);
+        let mut hashes: Map<u32, String> = Map::new(&e);
+        let mut deadlines: Map<u32, u64> = Map::new(&e);
+        let mut signings: Map<u32, Map<Address, SignatureStatus>> = Map::new(&e);
+        for token_id in 1..=3 {
+            owners.set(token_id, owner.clone());
+            uris.set(token_id, String::from_str(&e, "ipfs://legacy"));
+            hashes.set(token_id, String::from_str(&e, "hash"));
+            deadlines.set(token_id, 1000);
+            signings.set(
+                token_id,
+                map![&e, (signer.clone(), SignatureStatus::Waiting)],
+            );
+        }
+        let storage = e.storage().persistent();
+        storage.set(&OWNERS, &owners);
+        storage.set(&URIS, &uris);
+        storage.set(&T2DHASH, &hashes);
+        storage.set(&DEADLINES, &deadlines);
+        storage.set(&DOCSIGN, &signings);
+    });
+    assert_eq!(documents.migration_status().remaining, 3);
+
+    // Legacy documents stay usable while the migration is in progress.
+    documents.migrate(&2);
+    documents.sign_document(
+        &String::from_str(&e, "hash"),
+        &signer,
+        &SignatureStatus::Signed,
+        &3,
+    );
+    assert_eq!(documents.get_owners().len(), 3);
+
+    let status = documents.migrate(&2);
+    assert!(status.complete);
+    assert_eq!(status.migrated, 3);
+    assert_eq!(documents.get_owners().len(), 3);
+    assert_eq!(
+        documents.get_document(&3).get(signer),
+        Some(SignatureStatus::Signed)
+    );
+    assert_eq!(
+        documents.get_token_uri(&1),
+        String::from_str(&e, "ipfs://legacy")
+    );
+    e.as_contract(&documents.address, || {
+        assert!(!e.storage().persistent().has(&DOCSIGN));
+    });
+}
+
+#[test]
+fn test_weighted_poll() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let holder = Address::generate(&e);
+    let other_holder = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let voting_token = SnapshotTokenClient::new(&e, &e.register(SnapshotToken, ()));
+    e.ledger().with_mut(|li| li.sequence_number = 10);
+    voting_token.set_balance(&holder, &300);
+    voting_token.set_balance(&other_holder, &100);
+    documents.set_voting_token(&voting_token.address);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://resolution"),
+        &vec![&e, holder.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+
+    e.ledger().with_mut(|li| li.sequence_number = 11);
+    assert_eq!(documents.create_poll(&owner, &1, &500).snapshot_ledger, 10);
+    assert_eq!(documents.vote(&holder, &1, &true), 300);
+    assert_eq!(documents.vote(&other_holder, &1, &false), 100);
+    assert_eq!(documents.poll_result(&1).outcome, PollOutcome::Open);
+
+    // Tokens moved after the snapshot don't vote a second time.
+    let fresh = Address::generate(&e);
+    voting_token.transfer(&holder, &fresh, &300);
+    let result = e.as_contract(&documents.address, || poll::vote(&e, &fresh, 1, true));
+    assert_eq!(result, Err(Error::NoVotingPower));
+
+    e.ledger().with_mut(|li| li.timestamp = 500);
+    let result = documents.poll_result(&1);
+    assert_eq!(result.outcome, PollOutcome::Approved);
+    assert_eq!(result.votes_for, 300);
+    assert_eq!(result.votes_against, 100);
+}
+
+#[test]
+fn test_events_carry_schema_version() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let version = documents.get_event_schema_version();
+    assert_eq!(version, EVENT_SCHEMA_VERSION);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    let (contract, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(contract, documents.address);
+    assert_eq!(topics, (symbol_short!("mint"), 1_u32, version).into_val(&e));
+    let data: MintData = data.into_val(&e);
+    assert_eq!(
+        data,
+        MintData {
+            to: owner,
+            token_id: 1
+        }
+    );
+}
+
+#[test]
+fn test_certificate() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    let pending_hash = documents.certificate_hash(&1);
+
+    e.ledger().with_mut(|li| li.timestamp = 42);
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+
+    let certificate = documents.get_certificate(&1);
+    assert_eq!(certificate.document_hash, hash);
+    assert_eq!(certificate.status, DocumentStatus::Completed);
+    assert_eq!(certificate.completed_at, Some(42));
+    assert_eq!(certificate.contract, documents.address);
+    let signed = certificate.signers.get(0).unwrap();
+    assert_eq!(signed.signer, signer);
+    assert_eq!(signed.signed_at, Some(42));
+
+    let certificate_hash = documents.certificate_hash(&1);
+    assert_eq!(
+        certificate_hash,
+        e.crypto().sha256(&certificate.to_xdr(&e)).to_bytes()
+    );
+    assert_ne!(certificate_hash, pending_hash);
+    assert_eq!(
+        documents.try_get_certificate(&2),
+        Err(Ok(Error::TokenDoesNotExist))
+    );
+}
+
+#[test]
+fn test_witnesses() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let party = Address::generate(&e);
+    let witness = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, party.clone()],
+        &hash,
+        &1000,
+        &vec![&e, MintOption::Witnesses(vec![&e, witness.clone()])],
+    );
+    assert_eq!(documents.get_witnesses(&1), vec![&e, witness.clone()]);
+    assert_eq!(
+        documents.get_document(&1).get(witness.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+    let result = e.as_contract(&documents.address, || {
+        crate::witness::add(&e, 1, &vec![&e, party.clone()])
+    });
+    assert_eq!(result, Err(ExtendedError::DuplicateSigner));
+
+    // The party alone completes the document.
+    let receipt = documents.sign_document(&hash, &party, &SignatureStatus::Signed, &1);
+    assert!(receipt.completed);
+    assert_eq!(receipt.remaining_signers, 0);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Completed);
+
+    // The witness can still sign afterwards, without touching the status.
+    let receipt = documents.sign_document(&hash, &witness, &SignatureStatus::Signed, &1);
+    assert!(!receipt.completed);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Completed);
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("witnessed"),
+            1_u32,
+            witness.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+    let status: SignatureStatus = data.into_val(&e);
+    assert_eq!(status, SignatureStatus::Signed);
+    let log = documents.get_audit_log(&1, &0, &10);
+    assert_eq!(log.last().unwrap().action, AuditAction::Witnessed);
+
+    let certificate = documents.get_certificate(&1);
+    assert_eq!(certificate.signers.len(), 1);
+    assert_eq!(certificate.signers.get(0).unwrap().signer, party);
+    assert_eq!(certificate.witnesses.len(), 1);
+    let witnessed = certificate.witnesses.get(0).unwrap();
+    assert_eq!(witnessed.signer, witness);
+    assert_eq!(witnessed.status, SignatureStatus::Signed);
+}
+
+#[test]
+fn test_witness_deadline_does_not_expire() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let party = Address::generate(&e);
+    let witness = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let mut deadlines = Map::new(&e);
+    deadlines.set(witness.clone(), 100_u64);
+    deadlines.set(party.clone(), 500_u64);
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, party.clone()],
+        &hash,
+        &1000,
+        &vec![
+            &e,
+            MintOption::Witnesses(vec![&e, witness.clone()]),
+            MintOption::SignerDeadlines(deadlines),
+        ],
+    );
+
+    // The witness missing their deadline leaves the party free to sign.
+    e.ledger().with_mut(|li| li.timestamp = 200);
+    assert_eq!(documents.sweep_expired(&owner, &10), 0);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Pending);
+
+    // The party's own deadline still expires the document.
+    e.ledger().with_mut(|li| li.timestamp = 600);
+    assert_eq!(documents.sweep_expired(&owner, &10), 1);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Expired);
+}
+
+#[test]
+fn test_uri_prefixes() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let ipfs_uri = String::from_str(
+        &e,
+        "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+    );
+    let http_uri = String::from_str(&e, "http://example.com/doc1");
+
+    e.as_contract(&documents.address, || {
+        assert!(uri::is_allowed(&e, &http_uri));
+    });
+
+    let prefixes = vec![
+        &e,
+        String::from_str(&e, "ipfs://"),
+        String::from_str(&e, "https://docs.example.com/"),
+    ];
+    documents.set_uri_prefixes(&prefixes);
+    assert_eq!(documents.get_uri_prefixes(), prefixes);
+
+    e.as_contract(&documents.address, || {
+        assert!(uri::is_allowed(&e, &ipfs_uri));
+        assert!(uri::is_allowed(
+            &e,
+            &String::from_str(&e, "https://docs.example.com/doc1")
+        ));
+        assert!(!uri::is_allowed(&e, &http_uri));
+        assert!(!uri::is_allowed(&e, &String::from_str(&e, "ipfs:/")));
+    });
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &ipfs_uri,
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(documents.get_token_uri(&1), ipfs_uri);
+}
+
+#[test]
+fn test_string_limits() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert_eq!(
+        documents.get_string_limits(),
+        StringLimits {
+            max_uri_len: 256,
+            max_hash_len: 128,
+        }
+    );
+
+    let limits = StringLimits {
+        max_uri_len: 11,
+        max_hash_len: 5,
+    };
+    documents.set_string_limits(&limits);
+    assert_eq!(documents.get_string_limits(), limits);
+
+    // Exactly at both limits.
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(
+        documents.get_token_uri(&1),
+        String::from_str(&e, "ipfs://doc1")
+    );
+}
+
+#[test]
+fn test_attestation_gate() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let attestor = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e, MintOption::AttestationLevel(2)],
+    );
+    assert_eq!(documents.get_required_attestation(&1), 2);
+    assert_eq!(documents.get_attestation(&signer), 0);
+
+    documents.set_attestor(&attestor, &true);
+    assert!(documents.is_attestor(&attestor));
+    documents.attest(&attestor, &signer, &2);
+    assert_eq!(documents.get_attestation(&signer), 2);
+
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Completed);
+}
+
+#[test]
+fn test_completion_callback() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let receiver = e.register(CallbackReceiver, ());
+    let callback = Callback {
+        contract: receiver.clone(),
+        function: Symbol::new(&e, "on_final"),
+    };
+
+    let hash = String::from_str(&e, "hash1");
+    for token_id in [1, 2] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &hash,
+            &1000,
+            &vec![&e, MintOption::Callback(callback.clone())],
+        );
+    }
+    assert_eq!(documents.get_callback(&1), Some(callback));
+
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    e.ledger().with_mut(|li| li.timestamp = 1001);
+    documents.expire_document(&2);
+
+    e.as_contract(&receiver, || {
+        let storage = e.storage().instance();
+        assert_eq!(
+            storage.get::<u32, DocumentStatus>(&1),
+            Some(DocumentStatus::Completed)
+        );
+        assert_eq!(
+            storage.get::<u32, DocumentStatus>(&2),
+            Some(DocumentStatus::Expired)
+        );
+    });
+    assert_eq!(documents.get_callback(&1), None);
+}
+
+#[test]
+fn test_sign_status_input() {
+    assert_eq!(
+        signing::check_input_status(&SignatureStatus::Waiting),
+        Err(Error::InvalidStatus)
+    );
+    assert_eq!(
+        signing::check_input_status(&SignatureStatus::NotASigner),
+        Err(Error::InvalidStatus)
+    );
+    assert_eq!(
+        signing::check_input_status(&SignatureStatus::Signed),
+        Ok(())
+    );
+    assert_eq!(
+        signing::check_input_status(&SignatureStatus::Rejected),
+        Ok(())
+    );
+}
+
+#[test]
+fn test_signing_checks_before_writing() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let other = Address::generate(&e);
+    let stranger = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone(), other.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    let rejected_hash = String::from_str(&e, "hash2");
+    documents.safe_mint(
+        &owner,
+        &2,
+        &String::from_str(&e, "ipfs://doc2"),
+        &vec![&e, other.clone()],
+        &rejected_hash,
+        &1000,
+        &vec![&e],
+    );
+    let audit_len = documents.get_audit_log(&1, &0, &10).len();
+
+    // A failed check leaves the signer map, nonce and audit log untouched.
+    assert_eq!(
+        documents.try_sign_document(
+            &String::from_str(&e, "other hash"),
+            &signer,
+            &SignatureStatus::Signed,
+            &1
+        ),
+        Err(Ok(Error::DocumentHashesDoesNotMatchTokenHash.into()))
+    );
+    assert_eq!(
+        documents.get_document(&1).get(signer.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+    assert_eq!(documents.get_nonces(&signer), 0);
+    assert_eq!(documents.get_audit_log(&1, &0, &10).len(), audit_len);
+
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    e.as_contract(&documents.address, || {
+        let signing = signing::Signing::load(&e, 1, &signer);
+        assert_eq!(signing.check_signer(&e, &signer), Err(Error::AlreadySigned));
+        assert_eq!(
+            signing.check_signer(&e, &stranger),
+            Err(Error::SignerDoesNotExist)
+        );
+        assert_eq!(signing.check_signer(&e, &other), Ok(()));
+        assert_eq!(signing.remaining(), 1);
+        assert!(!signing.is_complete());
+        assert_eq!(
+            signing::Signing::try_load(&e, 3, &signer).err(),
+            Some(Error::DocumentSigningsIsEmpty)
+        );
+    });
+
+    // Once rejected, a document no longer loads for signing.
+    documents.sign_document(&rejected_hash, &other, &SignatureStatus::Rejected, &2);
+    e.as_contract(&documents.address, || {
+        assert_eq!(
+            signing::Signing::try_load(&e, 2, &other).err(),
+            Some(Error::DocumentNotPending)
+        );
+    });
+
+    e.ledger().with_mut(|li| li.timestamp = 2000);
+    e.as_contract(&documents.address, || {
+        let signing = signing::Signing::load(&e, 1, &other);
+        assert_eq!(signing.check_signer(&e, &other), Err(Error::DeadlinePassed));
+    });
+}
+
+#[test]
+fn test_token_by_hash() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash7");
+    documents.safe_mint(
+        &owner,
+        &7,
+        &String::from_str(&e, "ipfs://doc7"),
+        &vec![&e, signer],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    assert!(documents.hash_exists(&hash));
+    assert_eq!(documents.get_token_by_hash(&hash), 7);
+
+    let unknown = String::from_str(&e, "unknown");
+    assert!(!documents.hash_exists(&unknown));
+    assert_eq!(
+        documents.try_get_token_by_hash(&unknown),
+        Err(Ok(Error::HashNotFound))
+    );
+
+    // Legacy documents resolve before they are migrated.
+    e.as_contract(&documents.address, || {
+        let hashes: Map<u32, String> = map![&e, (3, String::from_str(&e, "legacy"))];
+        e.storage().persistent().set(&T2DHASH, &hashes);
+    });
+    assert_eq!(
+        documents.get_token_by_hash(&String::from_str(&e, "legacy")),
+        3
+    );
+}
+
+#[test]
+fn test_unique_hashes() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert!(!documents.get_unique_hashes());
+    documents.set_unique_hashes(&true);
+    assert!(documents.get_unique_hashes());
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+
+    e.as_contract(&documents.address, || {
+        assert!(document::is_duplicate_hash(&e, 2, &hash));
+        assert!(!document::is_duplicate_hash(&e, 1, &hash));
+        assert!(!document::is_duplicate_hash(
+            &e,
+            2,
+            &String::from_str(&e, "hash2")
+        ));
+    });
+}
+
+#[test]
+fn test_ledger_time_helpers() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let approver = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+
+    e.ledger().with_mut(|li| li.timestamp = 400);
+    assert_eq!(documents.get_time(), 400);
+    assert_eq!(documents.time_until_deadline(&1), 600);
+
+    e.ledger().with_mut(|li| li.timestamp = 1250);
+    assert_eq!(documents.time_until_deadline(&1), -250);
+    assert_eq!(documents.time_until_deadline(&2), i64::MAX);
+
+    // The approver's own deadline and the grace period decide when the
+    // document expires, and so what is left.
+    documents.safe_mint(
+        &owner,
+        &2,
+        &String::from_str(&e, "ipfs://doc2"),
+        &vec![&e, signer, approver.clone()],
+        &String::from_str(&e, "hash2"),
+        &5000,
+        &vec![
+            &e,
+            MintOption::GracePeriod(100),
+            MintOption::SignerDeadlines(map![&e, (approver, 2000)]),
+        ],
+    );
+    assert_eq!(documents.time_until_deadline(&2), 850);
+    e.ledger().with_mut(|li| li.timestamp = 2050);
+    assert_eq!(documents.get_document_status(&2), DocumentStatus::Pending);
+    assert_eq!(documents.time_until_deadline(&2), 50);
+
+    // Deadlines beyond the range of `i64` saturate.
+    documents.safe_mint(
+        &owner,
+        &3,
+        &String::from_str(&e, "ipfs://doc3"),
+        &vec![&e, Address::generate(&e)],
+        &String::from_str(&e, "hash3"),
+        &u64::MAX,
+        &vec![&e],
+    );
+    assert_eq!(documents.time_until_deadline(&3), i64::MAX);
+}
+
+#[test]
+fn test_grace_period() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    documents.set_grace_period(&100);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &vec![&e, MintOption::GracePeriod(300)],
+    );
+    assert_eq!(documents.get_default_grace_period(), 100);
+    assert_eq!(documents.get_grace_period(&1), 300);
+
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1);
+
+    e.ledger().with_mut(|li| li.timestamp = 1200);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Pending);
+    let receipt = documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &1);
+    assert_eq!(receipt.status, SignatureStatus::SignedLate);
+    assert!(receipt.completed);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Completed);
+
+    let log = documents.get_audit_log(&1, &0, &10);
+    assert_eq!(log.get(1).unwrap().action, AuditAction::Signed);
+    assert_eq!(log.get(2).unwrap().action, AuditAction::SignedLate);
+}
+
+#[test]
+fn test_archive_document() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    let certificate = documents.get_certificate(&1);
+    let certificate_hash = documents.certificate_hash(&1);
+    assert!(!documents.is_archived(&1));
+
+    let archived = documents.archive_document(&owner, &1);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (symbol_short!("archived"), 1_u32, EVENT_SCHEMA_VERSION).into_val(&e)
+    );
+    let snapshot: Certificate = data.into_val(&e);
+    assert_eq!(snapshot, certificate);
+
+    assert!(documents.is_archived(&1));
+    assert_eq!(documents.get_archive(&1), Some(archived.clone()));
+    assert_eq!(archived.certificate_hash, certificate_hash);
+    assert_eq!(documents.certificate_hash(&1), certificate_hash);
+    assert_eq!(documents.get_document(&1).len(), 0);
+    assert_eq!(documents.get_audit_log(&1, &0, &10).len(), 0);
+    assert_eq!(documents.get_owners().get(1), Some(owner));
+    assert_eq!(documents.get_token_by_hash(&hash), 1);
+    assert_eq!(documents.get_archive_version(&1), Some(CONTRACT_VERSION));
+
+    // Documents archived before the version was recorded report none.
+    e.as_contract(&documents.address, || {
+        e.storage()
+            .persistent()
+            .remove(&crate::storage_types::DocKey::ArchiveVersion(1));
+    });
+    assert_eq!(documents.get_archive_version(&1), None);
+    assert_eq!(documents.get_archive_version(&2), None);
+}
+
+#[test]
+fn test_get_documents_by_ids() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert_eq!(documents.get_max_batch_size(), 50);
+    documents.set_max_batch_size(&3);
+    assert_eq!(documents.get_max_batch_size(), 3);
+
+    for token_id in [1, 2] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, "hash"),
+            &1000,
+            &vec![&e],
+        );
+    }
+
+    let found = documents.get_documents_by_ids(&vec![&e, 2, 9, 1]);
+    assert_eq!(found.len(), 2);
+    assert_eq!(found.get(0).unwrap(), documents.get_document_details(&2));
+    assert_eq!(found.get(1).unwrap(), documents.get_document_details(&1));
+}
+
+#[test]
+fn test_get_documents_by_status() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash");
+    for token_id in [1, 2, 3] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &hash,
+            &1000,
+            &vec![&e],
+        );
+    }
+    assert_eq!(
+        documents.get_documents_by_status(&DocumentStatus::Pending, &0, &10),
+        vec![&e, 1, 2, 3]
+    );
+
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    assert_eq!(
+        documents.get_documents_by_status(&DocumentStatus::Pending, &0, &10),
+        vec![&e, 3, 2]
+    );
+    assert_eq!(
+        documents.get_documents_by_status(&DocumentStatus::Completed, &0, &10),
+        vec![&e, 1]
+    );
+
+    e.ledger().with_mut(|li| li.timestamp = 1001);
+    documents.expire_document(&2);
+    assert_eq!(
+        documents.get_documents_by_status(&DocumentStatus::Pending, &0, &10),
+        vec![&e, 3]
+    );
+    assert_eq!(
+        documents.get_documents_by_status(&DocumentStatus::Expired, &0, &1),
+        vec![&e, 2]
+    );
+}
+
+#[test]
+fn test_signing_progress() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let signer3 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer1.clone(), signer2.clone(), signer3.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(documents.get_signed_count(&1), (0, 3));
+
+    documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &1);
+    assert_eq!(documents.get_signed_count(&1), (1, 3));
+    let pending = documents.get_pending_signers(&1);
+    assert_eq!(pending.len(), 2);
+    assert!(pending.contains(&signer1));
+    assert!(pending.contains(&signer3));
+}
+
+#[test]
+fn test_seeded_document_completes() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "seeded");
+    let signers = vec![&e, signer1.clone(), signer2.clone()];
+    testutils::seed_document(&e, &documents.address, 7, &owner, &signers, &hash, 1000);
+    testutils::seed_signature(&e, &documents.address, 7, &signer1, SignatureStatus::Signed);
+    assert_eq!(documents.get_signed_count(&7), (1, 2));
+    assert_eq!(documents.get_token_by_hash(&hash), 7);
+
+    let receipt = documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &7);
+    assert!(receipt.completed);
+    assert_eq!(documents.get_document_status(&7), DocumentStatus::Completed);
+}
+
+#[test]
+fn test_signer_deadlines() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let cfo = Address::generate(&e);
+    let counterparty = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let signers = vec![&e, cfo.clone(), counterparty.clone()];
+    let deadlines = map![&e, (cfo.clone(), 300_u64)];
+    let hash1 = String::from_str(&e, "hash1");
+    let hash2 = String::from_str(&e, "hash2");
+    for (token_id, hash) in [(1_u32, &hash1), (2_u32, &hash2)] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &signers,
+            hash,
+            &1000,
+            &vec![&e, MintOption::SignerDeadlines(deadlines.clone())],
+        );
+    }
+    assert_eq!(documents.get_signer_deadlines(&1), deadlines);
+    assert_eq!(documents.get_signer_deadline(&1, &cfo), 300);
+    assert_eq!(documents.get_signer_deadline(&1, &counterparty), 1000);
+
+    e.ledger().with_mut(|li| li.timestamp = 200);
+    documents.sign_document(&hash1, &cfo, &SignatureStatus::Signed, &1);
+
+    // The CFO missed their deadline on the second document, so it can no
+    // longer complete; the first one only waits on the counterparty.
+    e.ledger().with_mut(|li| li.timestamp = 400);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Pending);
+    assert_eq!(documents.get_document_status(&2), DocumentStatus::Expired);
+
+    documents.expire_document(&2);
+    let (_, _, data) = all_events(&e).last().unwrap();
+    let data: ExpiredData = data.into_val(&e);
+    assert_eq!(
+        data,
+        ExpiredData {
+            deadline: 300,
+            kind: DeadlineKind::Signer,
+        }
+    );
+
+    let receipt = documents.sign_document(&hash1, &counterparty, &SignatureStatus::Signed, &1);
+    assert!(receipt.completed);
+}
+
+#[test]
+fn test_reopen_document() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1);
+
+    e.ledger().with_mut(|li| li.timestamp = 1500);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Expired);
+
+    documents.reopen_document(&owner, &1, &3000, &false);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Pending);
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("reopen"),
+            1_u32,
+            owner.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+    let data: ReopenedData = data.into_val(&e);
+    assert_eq!(data.new_deadline, 3000);
+    let log = documents.get_audit_log(&1, &0, &10);
+    assert_eq!(log.last().unwrap().action, AuditAction::Reopened);
+
+    documents.sign_document(&hash, &signer2, &SignatureStatus::Rejected, &1);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Rejected);
+    assert_eq!(
+        reopen::check_reopenable(&DocumentStatus::Rejected, false),
+        Err(ExtendedError::DocumentNotReopenable)
+    );
+
+    // The admin can reopen it too, asking the rejecting signer again.
+    documents.reopen_document(&admin, &1, &4000, &true);
+    assert_eq!(documents.get_signed_count(&1), (1, 2));
+    let receipt = documents.sign_document(&hash, &signer2, &SignatureStatus::Signed, &1);
+    assert!(receipt.completed);
+}
+
+#[test]
+fn test_transfer_lockup() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    documents.set_transfer_lockup(&100);
+    assert_eq!(documents.get_transfer_lockup(), 100);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(documents.transferable_at(&1), None);
+
+    e.ledger().with_mut(|li| li.sequence_number = 50);
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    assert_eq!(documents.transferable_at(&1), Some(150));
+}
+
+#[test]
+fn test_signer_history() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    e.ledger().with_mut(|li| li.timestamp = 100);
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    e.ledger().with_mut(|li| li.timestamp = 200);
+    documents.sign_document(&hash, &signer, &SignatureStatus::Rejected, &1);
+    e.ledger().with_mut(|li| li.timestamp = 300);
+    documents.reopen_document(&owner, &1, &2000, &true);
+    e.ledger().with_mut(|li| li.timestamp = 400);
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+
+    let change = |status, timestamp| StatusChange { status, timestamp };
+    assert_eq!(
+        documents.get_signer_history(&1, &signer),
+        vec![
+            &e,
+            change(SignatureStatus::Waiting, 100),
+            change(SignatureStatus::Rejected, 200),
+            change(SignatureStatus::Waiting, 300),
+            change(SignatureStatus::Signed, 400),
+        ]
+    );
+    assert_eq!(documents.get_signer_history(&1, &owner), vec![&e]);
+}
+
+#[test]
+fn test_rerequest_signature() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    documents.set_rerequest_policy(&1, &true);
+    documents.sign_document(&hash, &signer, &SignatureStatus::Rejected, &1);
+    let result = e.as_contract(&documents.address, || rerequest::rerequest(&e, 1, &signer));
+    assert_eq!(result, Err(ExtendedError::AmendmentRequired));
+
+    let amend = |child_id: u32, hash: &str| {
+        documents.safe_mint(
+            &owner,
+            &child_id,
+            &String::from_str(&e, "ipfs://amendment"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, hash),
+            &1000,
+            &vec![&e],
+        );
+        documents.link_documents(&owner, &1, &child_id, &symbol_short!("amends"));
+    };
+    amend(2, "hash2");
+    assert_eq!(documents.rerequest_signature(&owner, &1, &signer), 1);
+    let (_, topics, _) = all_events(&e).last().unwrap();
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Pending);
+    assert_eq!(documents.get_rerequest_count(&1, &signer), 1);
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("rerequest"),
+            1_u32,
+            signer.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+
+    documents.sign_document(&hash, &signer, &SignatureStatus::Rejected, &1);
+    amend(3, "hash3");
+    let result = e.as_contract(&documents.address, || rerequest::rerequest(&e, 1, &signer));
+    assert_eq!(result, Err(ExtendedError::TooManyRerequests));
+    assert_eq!(documents.get_config().max_rerequests, 1);
+}
+
+#[test]
+fn test_revoke_signature() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &vec![&e, MintOption::RevocationWindow(60)],
+    );
+    assert_eq!(documents.get_revocation_window(&1), 60);
+    let result = e.as_contract(&documents.address, || revocation::revoke(&e, 1, &signer1));
+    assert_eq!(result, Err(ExtendedError::SignatureNotRevocable));
+
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1);
+    e.ledger().with_mut(|li| li.timestamp += 60);
+    documents.revoke_signature(&signer1, &1);
+    let (_, topics, _) = all_events(&e).last().unwrap();
+    assert_eq!(
+        documents.get_document(&1).get(signer1.clone()),
+        Some(SignatureStatus::Waiting)
+    );
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Pending);
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("revoked"),
+            1_u32,
+            signer1.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+    let log = documents.get_audit_log(&1, &0, &10);
+    assert_eq!(log.last().unwrap().action, AuditAction::SignatureRevoked);
+
+    documents.sign_document(&hash, &signer1, &SignatureStatus::Signed, &1);
+    e.ledger().with_mut(|li| li.timestamp += 61);
+    let result = e.as_contract(&documents.address, || revocation::revoke(&e, 1, &signer1));
+    assert_eq!(result, Err(ExtendedError::RevocationWindowClosed));
+
+    // Without a window signatures are final.
+    documents.safe_mint(
+        &owner,
+        &2,
+        &String::from_str(&e, "ipfs://doc2"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &String::from_str(&e, "hash2"),
+        &1000,
+        &vec![&e],
+    );
+    documents.sign_document(
+        &String::from_str(&e, "hash2"),
+        &signer1,
+        &SignatureStatus::Signed,
+        &2,
+    );
+    let result = e.as_contract(&documents.address, || revocation::revoke(&e, 2, &signer1));
+    assert_eq!(result, Err(ExtendedError::SignatureNotRevocable));
+}
+
+#[test]
+fn test_cancel_document() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let payee = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&owner, &1000);
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&100);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![
+            &e,
+            MintOption::Escrow(EscrowDeposit {
+                token: fee_token.address.clone(),
+                amount: 500,
+                payee,
+            }),
+        ],
+    );
+    assert_eq!(fee_token.balance(&owner), 400);
+
+    documents.cancel_document(&1);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Cancelled);
+    assert_eq!(fee_token.balance(&owner), 1000);
+    assert_eq!(fee_token.balance(&documents.address), 0);
+    assert_eq!(documents.get_escrow(&1), None);
+    assert_eq!(documents.get_stats().total_fees_collected, 0);
+    let log = documents.get_audit_log(&1, &0, &10);
+    assert_eq!(log.last().unwrap().action, AuditAction::Cancelled);
+
+    let signings = map![&e, (signer.clone(), SignatureStatus::Waiting)];
+    assert!(!signing::has_recorded_signatures(&signings));
+    let signings = map![&e, (signer.clone(), SignatureStatus::Rejected)];
+    assert!(signing::has_recorded_signatures(&signings));
+
+    // The refund comes in the token the fee was paid in, even after the
+    // admin switched fee tokens.
+    documents.safe_mint(
+        &owner,
+        &2,
+        &String::from_str(&e, "ipfs://doc2"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash2"),
+        &1000,
+        &vec![&e],
+    );
+    let new_fee_token = create_fee_token(&e, &admin);
+    documents.set_fee_token(&new_fee_token.address);
+    documents.cancel_document(&2);
+    assert_eq!(fee_token.balance(&owner), 1000);
+    assert_eq!(new_fee_token.balance(&owner), 0);
+}
+
+#[test]
+fn test_cancel_after_fees_withdrawn() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&owner, &1000);
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&100);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![
+            &e,
+            MintOption::Escrow(EscrowDeposit {
+                token: fee_token.address.clone(),
+                amount: 500,
+                payee: Address::generate(&e),
+            }),
+        ],
+    );
+    documents.withdraw_fees(&admin, &100);
+    assert_eq!(documents.get_collected_fees(), 0);
+
+    // The withdrawn fee can't be refunded out of the escrow deposit.
+    let result = e.as_contract(&documents.address, || {
+        fee::refund_creation_fee(&e, 1, &owner)
+    });
+    assert_eq!(result, Err(ExtendedError::InsufficientFees.into()));
+    assert_eq!(fee_token.balance(&documents.address), 500);
+    assert_eq!(documents.get_escrow(&1).unwrap().amount, 500);
+}
+
+#[test]
+fn test_co_owners() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let partner = Address::generate(&e);
+    let outsider = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![
+            &e,
+            MintOption::CoOwners(vec![&e, partner.clone()]),
+            MintOption::OwnerAuth(OwnerAuth::All),
+        ],
+    );
+    assert_eq!(
+        documents.get_document_owners(&1),
+        vec![&e, owner.clone(), partner.clone()]
+    );
+    assert!(documents.is_owner(&1, &partner));
+    assert!(!documents.is_owner(&1, &outsider));
+    assert_eq!(documents.get_owner_auth(&1), OwnerAuth::All);
+
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    documents.archive_document(&partner, &1);
+    let signers: std::vec::Vec<Address> = e.auths().into_iter().map(|(addr, _)| addr).collect();
+    assert_eq!(signers, std::vec![owner, partner]);
+    assert!(documents.is_archived(&1));
+}
+
+// A documents contract delegating to a token contract it administers, as
+// minting and URIs need.
+#[cfg(feature = "token-contract")]
+fn create_documents_with_token<'a>(
+    e: &Env,
+    admin: &Address,
+) -> (PetalDocumentsClient<'a>, erc721::Client<'a>) {
+    let documents_address = Address::generate(e);
+    let nft = erc721::Client::new(
+        e,
+        &e.register(
+            erc721::WASM,
+            (
+                documents_address.clone(),
+                0_u32,
+                String::from_str(e, "Petal"),
+                String::from_str(e, "PTL"),
+                String::from_str(e, ""),
+                None::<i128>,
+            ),
+        ),
+    );
+    let documents = PetalDocumentsClient::new(
+        e,
+        &e.register_at(
+            &documents_address,
+            crate::PetalDocuments,
+            (admin.clone(), Some(nft.address.clone())),
+        ),
+    );
+    (documents, nft)
+}
+
+// Needs the token wasm, see `make test-with-token`.
+#[test]
+#[cfg(feature = "token-contract")]
+fn test_token_contract_owns_documents() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let partner = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let (documents, nft) = create_documents_with_token(&e, &admin);
+
+    let uri = String::from_str(&e, "ipfs://doc1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &uri,
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e, MintOption::CoOwners(vec![&e, partner.clone()])],
+    );
+    assert_eq!(nft.owner_of(&1), Some(owner.clone()));
+    assert_eq!(nft.token_uri(&1), uri);
+    assert_eq!(documents.get_token_uri(&1), uri);
+    assert_eq!(
+        documents.get_document_owners(&1),
+        vec![&e, owner.clone(), partner.clone()]
+    );
+    assert_eq!(
+        documents.try_get_document_owners(&2),
+        Err(Ok(Error::TokenDoesNotExist))
+    );
+
+    // A transfer on the token contract moves the document, and the
+    // co-owners stay behind with the previous owner.
+    nft.set_owners(&owner, &1, &buyer);
+    assert_eq!(documents.get_document_owners(&1), vec![&e, buyer.clone()]);
+    assert!(!documents.is_owner(&1, &partner));
+    assert!(!documents.is_owner(&1, &owner));
+}
+
+#[test]
+#[cfg(feature = "token-contract")]
+fn test_token_contract_follows_updates() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let custodian = Address::generate(&e);
+    let (documents, nft) = create_documents_with_token(&e, &admin);
+    documents.set_transfer_lockup(&100);
+
+    let hash = String::from_str(&e, "hash1");
+    for (token_id, hash) in [(1, hash.clone()), (2, String::from_str(&e, "hash2"))] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &hash,
+            &1000,
+            &vec![&e],
+        );
+    }
+
+    let new_uri = String::from_str(&e, "ipfs://doc1-v2");
+    documents.multicall(
+        &owner,
+        &vec![
+            &e,
+            DocumentCall {
+                doc_id: 1,
+                op: DocumentOp::SetMetaUri(new_uri.clone()),
+            },
+        ],
+    );
+    assert_eq!(nft.token_uri(&1), new_uri);
+    assert_eq!(documents.get_token_uri(&1), new_uri);
+
+    // Completion locks the token on the token contract too.
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    let until = documents.transferable_at(&1).unwrap();
+    assert_eq!(nft.transferable_at(&1), until);
+
+    documents.clawback_document(&2, &custodian, &String::from_str(&e, "court order"));
+    assert_eq!(nft.owner_of(&2), Some(custodian.clone()));
+    assert_eq!(documents.get_document_owners(&2), vec![&e, custodian]);
+}
+
+#[test]
+#[cfg(feature = "token-contract")]
+fn test_token_contract_id_taken() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let (documents, nft) = create_documents_with_token(&e, &admin);
+
+    // Ids minted on the token contract directly can't be minted as documents,
+    // and the failed mint leaves no document behind.
+    nft.mint(&1, &owner);
+    assert!(documents
+        .try_safe_mint(
+            &owner,
+            &1,
+            &String::from_str(&e, "ipfs://doc1"),
+            &vec![&e, Address::generate(&e)],
+            &String::from_str(&e, "hash1"),
+            &1000,
+            &vec![&e],
+        )
+        .is_err());
+    assert_eq!(documents.get_document(&1).len(), 0);
+}
+
+// Without the feature there is no token client to delegate to.
+#[test]
+#[cfg(not(feature = "token-contract"))]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_token_contract_unavailable() {
+    let e = Env::default();
+    let admin = Address::generate(&e);
+    let token_contract = Address::generate(&e);
+    e.register(crate::PetalDocuments, (admin, Some(token_contract)));
+}
+
+#[test]
+fn test_clawback_drops_co_owners() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let partner = Address::generate(&e);
+    let custodian = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e, MintOption::CoOwners(vec![&e, partner.clone()])],
+    );
+    assert!(documents.is_owner(&1, &partner));
+
+    documents.clawback_document(&1, &custodian, &String::from_str(&e, "court order"));
+    assert_eq!(
+        documents.get_document_owners(&1),
+        vec![&e, custodian.clone()]
+    );
+    assert!(!documents.is_owner(&1, &partner));
+    assert!(!documents.has_view_access(&1, &partner));
+}
+
+#[test]
+fn test_organizations() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let founder = Address::generate(&e);
+    let manager = Address::generate(&e);
+    let clerk = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let org_id = documents.create_org(&founder, &String::from_str(&e, "Acme"));
+    documents.add_member(&founder, &org_id, &manager, &OrgRole::Manager);
+    documents.add_member(&founder, &org_id, &clerk, &OrgRole::Member);
+    assert_eq!(
+        documents.get_org_role(&org_id, &clerk),
+        Some(OrgRole::Member)
+    );
+
+    documents.safe_mint(
+        &clerk,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e, MintOption::Org(org_id)],
+    );
+    assert_eq!(documents.get_document_org(&1), Some(org_id));
+    assert_eq!(documents.get_org_documents(&org_id, &0, &10), vec![&e, 1]);
+
+    // The manager doesn't own the document but can still manage it.
+    let new_signer = Address::generate(&e);
+    documents.multicall(
+        &manager,
+        &vec![
+            &e,
+            DocumentCall {
+                doc_id: 1,
+                op: DocumentOp::ExtendDeadline(2000),
+            },
+            DocumentCall {
+                doc_id: 1,
+                op: DocumentOp::AddSigner(new_signer),
+            },
+        ],
+    );
+    assert_eq!(documents.get_deadlines().get(1), Some(2000));
+    assert_eq!(documents.get_pending_signers(&1).len(), 2);
+
+    e.as_contract(&documents.address, || {
+        assert!(org::may_manage(&e, 1, &manager));
+        assert!(!org::may_manage(&e, 1, &clerk));
+        assert_eq!(
+            org::remove_member(&e, org_id, &founder),
+            Err(ExtendedError::LastOrgAdmin)
+        );
+    });
+
+    documents.remove_member(&manager, &org_id, &manager);
+    let org = documents.get_org(&org_id).unwrap();
+    assert_eq!((org.members, org.admins, org.documents), (2, 1, 1));
+}
+
+#[test]
+fn test_collections() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    for token_id in 1..=3_u32 {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, ["hash1", "hash2", "hash3"][token_id as usize - 1]),
+            &1000,
+            &vec![&e],
+        );
+    }
+
+    let deal = documents.create_collection(&owner, &String::from_str(&e, "Deal A"));
+    let other = documents.create_collection(&owner, &String::from_str(&e, "Deal B"));
+    assert_eq!(deal, 1);
+    assert_eq!(other, 2);
+
+    documents.assign_to_collection(&owner, &1, &deal);
+    documents.assign_to_collection(&owner, &2, &deal);
+    documents.assign_to_collection(&owner, &3, &deal);
+    assert_eq!(documents.get_collection(&deal).unwrap().size, 3);
+    assert_eq!(
+        documents.get_collection_documents(&deal, &0, &10),
+        vec![&e, 1, 2, 3]
+    );
+    assert_eq!(
+        documents.get_collection_documents(&deal, &1, &2),
+        vec![&e, 3]
+    );
+
+    // Moving a document takes it out of its previous collection.
+    documents.assign_to_collection(&owner, &1, &other);
+    assert_eq!(documents.get_document_collection(&1), Some(other));
+    assert_eq!(
+        documents.get_collection_documents(&deal, &0, &10),
+        vec![&e, 3, 2]
+    );
+    assert_eq!(
+        documents.get_collection_documents(&other, &0, &10),
+        vec![&e, 1]
+    );
+}
+
+#[test]
+fn test_metrics_events() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    documents.set_metrics_interval(&3);
+    assert_eq!(documents.get_metrics_interval(), 3);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    // Mint, signature and completion make three counter updates.
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+
+    let metrics = symbol_short!("metrics").into_val(&e);
+    let snapshots: std::vec::Vec<ContractStats> = all_events(&e)
+        .iter()
+        .filter(|(_, topics, _)| topics.get(0).unwrap().shallow_eq(&metrics))
+        .map(|(_, _, data)| data.into_val(&e))
+        .collect();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].total_minted, 1);
+    assert_eq!(snapshots[0].total_signatures, 1);
+    assert_eq!(snapshots[0].total_completed, 1);
+}
+
+#[test]
+fn test_stats_count_each_event_once() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let party = Address::generate(&e);
+    let witness = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert_eq!(
+        documents.get_stats(),
+        ContractStats {
+            total_minted: 0,
+            total_completed: 0,
+            total_expired: 0,
+            total_signatures: 0,
+            total_fees_collected: 0,
+        }
+    );
+
+    for (token_id, hash) in [(1, "hash1"), (2, "hash2"), (3, "hash3")] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, party.clone()],
+            &String::from_str(&e, hash),
+            &1000,
+            &vec![&e, MintOption::Witnesses(vec![&e, witness.clone()])],
+        );
+    }
+    // A mint that fails leaves the counters alone.
+    assert!(documents
+        .try_safe_mint(
+            &owner,
+            &1,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, party.clone()],
+            &String::from_str(&e, "hash4"),
+            &1000,
+            &vec![&e],
+        )
+        .is_err());
+    assert_eq!(documents.get_stats().total_minted, 3);
+
+    // Rejections and witness signatures aren't counted as signatures.
+    documents.sign_document(
+        &String::from_str(&e, "hash1"),
+        &party,
+        &SignatureStatus::Signed,
+        &1,
+    );
+    documents.sign_document(
+        &String::from_str(&e, "hash1"),
+        &witness,
+        &SignatureStatus::Signed,
+        &1,
+    );
+    documents.sign_document(
+        &String::from_str(&e, "hash2"),
+        &party,
+        &SignatureStatus::Rejected,
+        &2,
+    );
+    let stats = documents.get_stats();
+    assert_eq!(stats.total_signatures, 1);
+    assert_eq!(stats.total_completed, 1);
+
+    // Reading an expired status doesn't count it; the sweep does, once.
+    e.ledger().with_mut(|li| li.timestamp = 2000);
+    assert_eq!(documents.get_document_status(&3), DocumentStatus::Expired);
+    assert_eq!(documents.get_stats().total_expired, 0);
+    assert_eq!(documents.sweep_expired(&owner, &10), 1);
+    assert_eq!(documents.sweep_expired(&owner, &10), 0);
+    assert_eq!(documents.get_stats().total_expired, 1);
+}
+
+#[test]
+fn test_rate_limit() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert_eq!(documents.get_rate_limit(&RateAction::Mint), None);
+
+    let limit = RateLimit {
+        max_actions: 2,
+        window_ledgers: 10,
+    };
+    documents.set_rate_limit(&RateAction::Mint, &Some(limit.clone()));
+    assert_eq!(documents.get_rate_limit(&RateAction::Mint), Some(limit));
+    assert_eq!(documents.get_rate_limit(&RateAction::Sign), None);
+
+    e.as_contract(&documents.address, || {
+        assert_eq!(ratelimit::hit(&e, RateAction::Mint, &owner), Ok(()));
+        assert_eq!(ratelimit::hit(&e, RateAction::Mint, &owner), Ok(()));
+        assert_eq!(
+            ratelimit::hit(&e, RateAction::Mint, &owner),
+            Err(ExtendedError::RateLimited)
+        );
+        // Signing has no limit configured.
+        assert_eq!(ratelimit::hit(&e, RateAction::Sign, &owner), Ok(()));
+    });
+
+    // A new window opens once the old one has passed.
+    e.ledger().with_mut(|li| li.sequence_number += 10);
+    e.as_contract(&documents.address, || {
+        assert_eq!(ratelimit::hit(&e, RateAction::Mint, &owner), Ok(()));
+    });
+
+    documents.set_rate_limit(&RateAction::Mint, &None);
+    assert_eq!(documents.get_rate_limit(&RateAction::Mint), None);
+}
+
+#[test]
+fn test_minter_allowlist() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let minter = Address::generate(&e);
+    let stranger = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert!(!documents.is_minter_allowlist_enabled());
+
+    documents.set_minter_allowlist(&true);
+    assert!(documents.is_minter_allowlist_enabled());
+    documents.add_minter(&minter);
+    assert!(documents.is_minter(&minter));
+    assert!(!documents.is_minter(&stranger));
+
+    let signer = Address::generate(&e);
+    documents.safe_mint(
+        &minter,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    documents.safe_mint(
+        &admin,
+        &2,
+        &String::from_str(&e, "ipfs://doc2"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash2"),
+        &1000,
+        &vec![&e],
+    );
+    assert!(documents.is_owner(&1, &minter));
+    assert!(documents.is_owner(&2, &admin));
+
+    e.as_contract(&documents.address, || {
+        assert!(!minters::may_mint(&e, &stranger));
+    });
+
+    documents.remove_minter(&minter);
+    assert!(!documents.is_minter(&minter));
+    e.as_contract(&documents.address, || {
+        assert!(!minters::may_mint(&e, &minter));
+    });
+
+    // Turning the allowlist off opens minting to everyone again.
+    documents.set_minter_allowlist(&false);
+    e.as_contract(&documents.address, || {
+        assert!(minters::may_mint(&e, &stranger));
+    });
+}
+
+#[test]
+fn test_fee_tiers() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let partner = Address::generate(&e);
+    let public = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&partner, &1000);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&public, &1000);
+
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&100);
+    documents.set_fee_schedule(&map![&e, (1, 0), (2, 40)]);
+    documents.set_fee_tier(&partner, &Some(1));
+    assert_eq!(documents.get_fee_tier(&partner), Some(1));
+    assert_eq!(documents.get_fee_tier(&public), None);
+    assert_eq!(documents.get_creation_fee_for(&partner), 0);
+    assert_eq!(documents.get_creation_fee_for(&public), 100);
+
+    documents.safe_mint(
+        &partner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    documents.safe_mint(
+        &public,
+        &2,
+        &String::from_str(&e, "ipfs://doc2"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash2"),
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(fee_token.balance(&partner), 1000);
+    assert_eq!(fee_token.balance(&public), 900);
+
+    documents.set_fee_tier(&partner, &Some(2));
+    documents.safe_mint(
+        &partner,
+        &3,
+        &String::from_str(&e, "ipfs://doc3"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash3"),
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(fee_token.balance(&partner), 960);
+    assert_eq!(documents.get_stats().total_fees_collected, 140);
+
+    documents.set_fee_tier(&partner, &None);
+    assert_eq!(documents.get_creation_fee_for(&partner), 100);
+
+    // With more than one approval required, fee changes go through proposals.
+    let admin2 = Address::generate(&e);
+    documents.propose_action(
+        &admin,
+        &ProposalAction::SetAdmins(AdminSet {
+            admins: vec![&e, admin.clone(), admin2.clone()],
+            threshold: 2,
+        }),
+    );
+    let change = FeeTierChange {
+        addr: partner.clone(),
+        tier: Some(1),
+    };
+    let proposal_id = documents.propose_action(&admin, &ProposalAction::SetFeeTier(change));
+    assert_eq!(documents.get_fee_tier(&partner), None);
+    documents.approve_action(&admin2, &proposal_id);
+    assert_eq!(documents.get_fee_tier(&partner), Some(1));
+    let schedule = map![&e, (1, 10)];
+    let proposal_id =
+        documents.propose_action(&admin, &ProposalAction::SetFeeSchedule(schedule.clone()));
+    documents.approve_action(&admin2, &proposal_id);
+    assert_eq!(documents.get_fee_schedule(), schedule);
+}
+
+#[test]
+fn test_sweep_expired() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let keeper = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    for (token_id, hash, deadline) in [(1, "hash1", 1000), (2, "hash2", 5000), (3, "hash3", 1000)] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, hash),
+            &deadline,
+            &vec![&e],
+        );
+    }
+    assert_eq!(documents.sweep_expired(&keeper, &10), 0);
+
+    e.ledger().with_mut(|li| li.timestamp = 2000);
+    assert_eq!(documents.sweep_expired(&keeper, &1), 1);
+    assert_eq!(documents.sweep_expired(&keeper, &10), 1);
+    assert_eq!(documents.sweep_expired(&keeper, &10), 0);
+    assert_eq!(
+        documents.get_documents_by_status(&DocumentStatus::Pending, &0, &10),
+        vec![&e, 2]
+    );
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Expired);
+    assert_eq!(documents.get_document_status(&3), DocumentStatus::Expired);
+    assert_eq!(documents.get_stats().total_expired, 2);
+}
+
+#[test]
+fn test_keeper_bounties() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let keeper = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&admin, &1000);
+    documents.set_fee_token(&fee_token.address);
+
+    documents.fund_bounty_pool(&25);
+    documents.set_bounty_reward(&BountyAction::Sweep, &10);
+    documents.set_bounty_reward(&BountyAction::Reminder, &5);
+    assert_eq!(documents.get_bounty_pool(), 25);
+    assert_eq!(documents.get_bounty_reward(&BountyAction::Sweep), 10);
+    assert_eq!(fee_token.balance(&documents.address), 25);
+
+    for (token_id, hash, options) in [
+        (1, "hash1", vec![&e, MintOption::Reminder(500)]),
+        (2, "hash2", vec![&e]),
+        (3, "hash3", vec![&e]),
+    ] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, hash),
+            &1000,
+            &options,
+        );
+    }
+
+    e.ledger().with_mut(|li| li.timestamp = 600);
+    documents.emit_reminder(&keeper, &1);
+    assert_eq!(documents.get_keeper_earnings(&keeper), 5);
+
+    // Three expiries would earn 30, but only 20 is left in the pool.
+    e.ledger().with_mut(|li| li.timestamp = 2000);
+    assert_eq!(documents.sweep_expired(&keeper, &10), 3);
+    assert_eq!(documents.get_keeper_earnings(&keeper), 25);
+    assert_eq!(documents.get_bounty_pool(), 0);
+    assert_eq!(fee_token.balance(&keeper), 25);
+}
+
+#[test]
+fn test_sweep_skips_own_documents() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&admin, &1000);
+    documents.set_fee_token(&fee_token.address);
+    documents.fund_bounty_pool(&100);
+    documents.set_bounty_reward(&BountyAction::Sweep, &10);
+
+    let keeper = Address::generate(&e);
+    for (token_id, creator) in [(1, owner.clone()), (2, keeper.clone())] {
+        documents.safe_mint(
+            &creator,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, if token_id == 1 { "hash1" } else { "hash2" }),
+            &1000,
+            &vec![&e],
+        );
+    }
+
+    // Both expire, but only the one the keeper didn't create pays out.
+    e.ledger().with_mut(|li| li.timestamp = 2000);
+    assert_eq!(documents.sweep_expired(&keeper, &10), 2);
+    assert_eq!(documents.get_keeper_earnings(&keeper), 10);
+    assert_eq!(documents.get_bounty_pool(), 90);
+}
+
+#[test]
+fn test_bounty_pool_per_token() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let keeper = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let old_token = create_fee_token(&e, &admin);
+    let new_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &old_token.address).mint(&admin, &1000);
+    token::StellarAssetClient::new(&e, &new_token.address).mint(&admin, &1000);
+    token::StellarAssetClient::new(&e, &new_token.address).mint(&documents.address, &500);
+    documents.set_fee_token(&old_token.address);
+    documents.fund_bounty_pool(&100);
+    documents.set_bounty_reward(&BountyAction::Sweep, &10);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+
+    // The pool was funded in the old token, so after the switch nothing is
+    // paid in the new one even though the contract holds some of it.
+    documents.set_fee_token(&new_token.address);
+    assert_eq!(documents.get_bounty_pool(), 0);
+    assert_eq!(documents.get_bounty_pool_of(&old_token.address), 100);
+    e.ledger().with_mut(|li| li.timestamp = 2000);
+    assert_eq!(documents.sweep_expired(&keeper, &10), 1);
+    assert_eq!(documents.get_keeper_earnings(&keeper), 0);
+    assert_eq!(new_token.balance(&documents.address), 500);
+
+    documents.fund_bounty_pool(&30);
+    assert_eq!(documents.get_bounty_pool(), 30);
+    documents.set_fee_token(&old_token.address);
+    assert_eq!(documents.get_bounty_pool(), 100);
+}
+
+#[test]
+fn test_bounty_pool_legacy_balance() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let old_token = create_fee_token(&e, &admin);
+    let new_token = create_fee_token(&e, &admin);
+    documents.set_fee_token(&old_token.address);
+
+    // A pool written before balances were kept per token belongs to the fee
+    // token of the time and stays with it when the fee token changes.
+    e.as_contract(&documents.address, || {
+        e.storage()
+            .instance()
+            .set(&crate::storage_types::ConfigKey::BountyPool, &40_i128);
+    });
+    assert_eq!(documents.get_bounty_pool(), 40);
+    documents.set_fee_token(&new_token.address);
+    assert_eq!(documents.get_bounty_pool(), 0);
+    assert_eq!(documents.get_bounty_pool_of(&old_token.address), 40);
+}
+
+#[test]
+fn test_mint_rejects_past_deadline() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    e.ledger().with_mut(|li| li.timestamp = 1000);
+
+    for deadline in [999, 1000] {
+        assert_eq!(
+            documents.try_safe_mint(
+                &owner,
+                &1,
+                &String::from_str(&e, "ipfs://doc"),
+                &vec![&e, Address::generate(&e)],
+                &String::from_str(&e, "hash1"),
+                &deadline,
+                &vec![&e],
+            ),
+            Err(Ok(ExtendedError::InvalidDeadline.into()))
+        );
+    }
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc"),
+        &vec![&e, Address::generate(&e)],
+        &String::from_str(&e, "hash1"),
+        &1001,
+        &vec![&e],
+    );
+}
+
+#[test]
+fn test_multicall() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer1.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+
+    let new_uri = String::from_str(&e, "ipfs://doc1-v2");
+    documents.multicall(
+        &owner,
+        &vec![
+            &e,
+            DocumentCall {
+                doc_id: 1,
+                op: DocumentOp::ExtendDeadline(2000),
+            },
+            DocumentCall {
+                doc_id: 1,
+                op: DocumentOp::AddSigner(signer2.clone()),
+            },
+            DocumentCall {
+                doc_id: 1,
+                op: DocumentOp::SetMetaUri(new_uri.clone()),
+            },
+        ],
+    );
+
+    assert_eq!(documents.get_deadlines().get(1), Some(2000));
+    assert_eq!(documents.get_token_uri(&1), new_uri);
+    let pending = documents.get_pending_signers(&1);
+    assert_eq!(pending.len(), 2);
+    assert!(pending.contains(&signer1) && pending.contains(&signer2));
+    let actions: std::vec::Vec<AuditAction> = documents
+        .get_audit_log(&1, &0, &10)
+        .iter()
+        .map(|entry| entry.action)
+        .collect();
+    assert_eq!(
+        actions,
+        [
+            AuditAction::Minted,
+            AuditAction::DeadlineExtended,
+            AuditAction::SignersChanged,
+            AuditAction::Amended
+        ]
+    );
+}
+
+#[test]
+fn test_dry_run_checks() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let stranger = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    assert_eq!(documents.try_can_mint(&owner, &1), Ok(Ok(())));
+    assert_eq!(
+        documents.try_can_sign(&1, &signer),
+        Err(Ok(Error::TokenNotMinted.into()))
+    );
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(
+        documents.try_can_mint(&owner, &1),
+        Err(Ok(Error::TokenAlreadyMinted.into()))
+    );
+    assert_eq!(documents.try_can_sign(&1, &signer), Ok(Ok(())));
+    assert_eq!(
+        documents.try_can_sign(&1, &stranger),
+        Err(Ok(Error::SignerDoesNotExist.into()))
+    );
+
+    documents.set_minter_allowlist(&true);
+    assert_eq!(
+        documents.try_can_mint(&owner, &2),
+        Err(Ok(ExtendedError::NotAllowedMinter.into()))
+    );
+    assert_eq!(documents.try_can_mint(&admin, &2), Ok(Ok(())));
+
+    e.ledger().with_mut(|li| li.timestamp = 2000);
+    assert_eq!(
+        documents.try_can_sign(&1, &signer),
+        Err(Ok(Error::DeadlinePassed.into()))
+    );
+
+    // Dry runs leave the document untouched.
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Expired);
+    assert_eq!(
+        documents.get_documents_by_status(&DocumentStatus::Pending, &0, &10),
+        vec![&e, 1]
+    );
+}
+
+#[test]
+fn test_legacy_creation_fee_key() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    // Fees set by earlier versions sit under a raw symbol key.
+    e.as_contract(&documents.address, || {
+        e.storage().instance().set(&CREATION_FEE, &70i128);
+    });
+    assert_eq!(documents.get_creation_fee(), 70);
+
+    documents.set_creation_fee(&80);
+    assert_eq!(documents.get_creation_fee(), 80);
+    e.as_contract(&documents.address, || {
+        assert!(!e.storage().instance().has(&CREATION_FEE));
+    });
+}
+
+#[test]
+fn test_view_grants() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let auditor = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    assert!(documents.has_view_access(&1, &owner));
+    assert!(documents.has_view_access(&1, &signer));
+    assert!(!documents.has_view_access(&1, &auditor));
+    assert!(!documents.has_view_access(&2, &owner));
+
+    documents.grant_view(&owner, &1, &auditor, &500);
+    assert_eq!(documents.get_view_grant(&1, &auditor), Some(500));
+    assert!(documents.has_view_access(&1, &auditor));
+
+    e.ledger().with_mut(|li| li.timestamp = 500);
+    assert!(!documents.has_view_access(&1, &auditor));
+
+    documents.grant_view(&owner, &1, &auditor, &900);
+    assert!(documents.has_view_access(&1, &auditor));
+    documents.revoke_view(&owner, &1, &auditor);
+    assert_eq!(documents.get_view_grant(&1, &auditor), None);
+    assert!(!documents.has_view_access(&1, &auditor));
+}
+
+#[test]
+fn test_disputes() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let arbiter = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    documents.set_arbiter(&Some(arbiter.clone()));
+    assert_eq!(documents.get_arbiter(), Some(arbiter.clone()));
+    assert!(!documents.is_disputed(&1));
+
+    e.ledger().with_mut(|li| li.timestamp = 50);
+    let reason = String::from_str(&e, "signed under duress");
+    documents.raise_dispute(&signer, &1, &reason);
+    assert!(documents.is_disputed(&1));
+    assert_eq!(
+        documents.get_dispute(&1),
+        Some(Dispute {
+            raised_by: signer.clone(),
+            reason,
+            raised_at: 50,
+        })
+    );
+    // The signatures stand while the dispute is open.
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Completed);
+
+    documents.resolve_dispute(&arbiter, &1, &ArbitrationOutcome::Restored);
+    assert!(!documents.is_disputed(&1));
+    let log = documents.get_audit_log(&1, &0, &10);
+    let last = log.get(log.len() - 1).unwrap();
+    assert_eq!(last.action, AuditAction::DisputeRestored);
+    assert_eq!(last.actor, arbiter);
+    assert_eq!(
+        log.get(log.len() - 2).unwrap().action,
+        AuditAction::Disputed
+    );
+}
+
+#[test]
+fn test_document_arbiter() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let arbiter = Address::generate(&e);
+    let payee = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let escrow_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &escrow_token.address).mint(&owner, &500);
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![
+            &e,
+            MintOption::Arbiter(arbiter.clone()),
+            MintOption::Escrow(EscrowDeposit {
+                token: escrow_token.address.clone(),
+                amount: 500,
+                payee,
+            }),
+        ],
+    );
+    documents.sign_document(&hash, &signer, &SignatureStatus::Signed, &1);
+    assert_eq!(documents.get_document_arbiter(&1), Some(arbiter.clone()));
+
+    documents.raise_dispute(&owner, &1, &String::from_str(&e, "forged"));
+    e.as_contract(&documents.address, || {
+        assert!(dispute::may_resolve(&e, 1, &arbiter));
+        // The document's arbiter replaces the admin.
+        assert!(!dispute::may_resolve(&e, 1, &admin));
+    });
+
+    e.ledger().with_mut(|li| li.timestamp = 70);
+    documents.resolve_dispute(&arbiter, &1, &ArbitrationOutcome::Voided);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Voided);
+    assert_eq!(
+        documents.get_arbitration(&1),
+        Some(Arbitration {
+            arbiter,
+            outcome: ArbitrationOutcome::Voided,
+            resolved_at: 70,
+        })
+    );
+
+    // The escrow of a voided document goes back to the depositor.
+    documents.refund_escrow(&1);
+    assert_eq!(escrow_token.balance(&owner), 500);
+}
+
+#[test]
+fn test_document_links() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    for (token_id, hash) in [(1, "hash1"), (2, "hash2"), (3, "hash3")] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, hash),
+            &1000,
+            &vec![&e],
+        );
+    }
+
+    documents.link_documents(&owner, &1, &2, &symbol_short!("amends"));
+    documents.link_documents(&owner, &1, &3, &symbol_short!("annex"));
+    let amends = DocumentLink {
+        parent_id: 1,
+        child_id: 2,
+        relation: symbol_short!("amends"),
+    };
+    let annex = DocumentLink {
+        parent_id: 1,
+        child_id: 3,
+        relation: symbol_short!("annex"),
+    };
+    assert_eq!(documents.get_links(&1), vec![&e, amends.clone(), annex]);
+    assert_eq!(documents.get_links(&2), vec![&e, amends.clone()]);
+    assert_eq!(documents.get_links(&4), vec![&e]);
+
+    e.as_contract(&documents.address, || {
+        assert_eq!(
+            link::check_link(&e, &amends),
+            Err(ExtendedError::InvalidLink)
+        );
+        let self_link = DocumentLink {
+            parent_id: 2,
+            child_id: 2,
+            relation: symbol_short!("amends"),
+        };
+        assert_eq!(
+            link::check_link(&e, &self_link),
+            Err(ExtendedError::InvalidLink)
+        );
+    });
+}
+
+#[test]
+fn test_attributes() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+
+    let jurisdiction = symbol_short!("juris");
+    documents.set_attribute(&owner, &1, &jurisdiction, &String::from_str(&e, "DE"));
+    documents.set_attribute(&owner, &1, &jurisdiction, &String::from_str(&e, "NL"));
+    documents.set_attribute(
+        &owner,
+        &1,
+        &symbol_short!("value"),
+        &String::from_str(&e, "1000 EUR"),
+    );
+    assert_eq!(
+        documents.get_attributes(&1),
+        map![
+            &e,
+            (jurisdiction.clone(), String::from_str(&e, "NL")),
+            (symbol_short!("value"), String::from_str(&e, "1000 EUR"))
+        ]
+    );
+
+    documents.remove_attribute(&owner, &1, &jurisdiction);
+    assert_eq!(documents.get_attributes(&1).len(), 1);
+
+    e.as_contract(&documents.address, || {
+        for index in 1..16u32 {
+            let name = Symbol::new(&e, &std::format!("key{}", index));
+            attribute::set_attribute(&e, 1, name, String::from_str(&e, "x")).unwrap();
+        }
+        assert_eq!(
+            attribute::set_attribute(&e, 1, symbol_short!("extra"), String::from_str(&e, "x")),
+            Err(ExtendedError::TooManyAttributes.into())
+        );
+        let long = String::from_str(&e, &"x".repeat(257));
+        assert_eq!(
+            attribute::set_attribute(&e, 1, symbol_short!("value"), long),
+            Err(Error::StringTooLong.into())
+        );
+    });
+}
+
+#[test]
+fn test_export_state() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    for (token_id, hash) in [(1, "hash1"), (2, "hash2"), (3, "hash3")] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, hash),
+            &1000,
+            &vec![&e],
+        );
+    }
+    documents.sign_document(
+        &String::from_str(&e, "hash2"),
+        &signer,
+        &SignatureStatus::Signed,
+        &2,
+    );
+
+    let chunk = documents.export_state(&0, &2);
+    assert_eq!(chunk.total, 3);
+    assert_eq!(chunk.next_cursor, 2);
+    assert_eq!(chunk.documents.len(), 2);
+    assert_eq!(
+        chunk.documents.get(1).unwrap(),
+        DocumentState {
+            token_id: 2,
+            owner: owner.clone(),
+            creator: owner.clone(),
+            uri: String::from_str(&e, "ipfs://doc"),
+            document_hash: String::from_str(&e, "hash2"),
+            hash_algo: HashAlgo::Unspecified,
+            extra_hashes: vec![&e],
+            deadline: 1000,
+            status: DocumentStatus::Completed,
+            signings: map![&e, (signer.clone(), SignatureStatus::Signed)],
+        }
+    );
+
+    let chunk = documents.export_state(&chunk.next_cursor, &2);
+    assert_eq!(chunk.next_cursor, chunk.total);
+    assert_eq!(chunk.documents.len(), 1);
+    assert_eq!(chunk.documents.get(0).unwrap().token_id, 3);
+}
+
+#[test]
+fn test_import_state() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+    let source = create_documents(&e, &admin);
+    let hash = String::from_str(&e, "hash7");
+    source.safe_mint(
+        &owner,
+        &7,
+        &String::from_str(&e, "ipfs://doc7"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    source.sign_document(&hash, &signer1, &SignatureStatus::Signed, &7);
+
+    let target = create_documents(&e, &admin);
+    assert!(!target.is_sealed());
+    target.import_state(&source.export_state(&0, &10));
+    target.seal();
+    assert!(target.is_sealed());
+
+    assert!(target.is_owner(&7, &owner));
+    assert_eq!(
+        target.get_token_uri(&7),
+        String::from_str(&e, "ipfs://doc7")
+    );
+    assert_eq!(target.get_document(&7), source.get_document(&7));
+    assert_eq!(target.get_document_status(&7), DocumentStatus::Pending);
+
+    // Signing picks up where the source deployment left off.
+    target.sign_document(&hash, &signer2, &SignatureStatus::Signed, &7);
+    assert_eq!(target.get_document_status(&7), DocumentStatus::Completed);
+}
+
+#[test]
+fn test_constructor_blocks_init() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let attacker = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert_eq!(documents.get_admins(), vec![&e, admin.clone()]);
+
+    assert!(documents.try_init(&attacker, &0, &None).is_err());
+    assert_eq!(documents.get_admins(), vec![&e, admin]);
+}
+
+#[test]
+fn test_renounce_admin() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert!(documents.has_admin());
+    assert!(!documents.is_admin_renounceable());
+
+    documents.set_admin_renounceable(&true);
+    assert!(documents.is_admin_renounceable());
+    documents.request_renounce_admin();
+    e.ledger().with_mut(|li| li.sequence_number += 1);
+    documents.renounce_admin();
+    assert!(!documents.has_admin());
+    assert!(documents.is_admin_renounced());
+    assert_eq!(documents.get_admins().len(), 0);
+
+    // Documents can still be minted without an admin around.
+    let owner = Address::generate(&e);
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, Address::generate(&e)],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    assert!(documents.is_owner(&1, &owner));
+}
+
+#[test]
+fn test_hash_algo_tagging() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let sha256 = String::from_str(
+        &e,
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+    );
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, Address::generate(&e)],
+        &sha256,
+        &1000,
+        &vec![&e, MintOption::HashAlgo(HashAlgo::Sha256)],
+    );
+    documents.safe_mint(
+        &owner,
+        &2,
+        &String::from_str(&e, "ipfs://doc2"),
+        &vec![&e, Address::generate(&e)],
+        &String::from_str(&e, "hash2"),
+        &1000,
+        &vec![&e],
+    );
+
+    assert_eq!(documents.get_hash_algo(&1), HashAlgo::Sha256);
+    assert_eq!(documents.get_hash_algo(&2), HashAlgo::Unspecified);
+    assert_eq!(documents.get_certificate(&1).hash_algo, HashAlgo::Sha256);
+
+    e.as_contract(&documents.address, || {
+        limits::check_hash_algo(&e, HashAlgo::Unspecified, &String::from_str(&e, "x"));
+        limits::check_hash_algo(&e, HashAlgo::Keccak256, &sha256);
+    });
+}
+
+#[test]
+fn test_multiple_document_hashes() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "pdf-hash"),
+        &1000,
+        &vec![&e],
+    );
+    let xml_hash = String::from_str(&e, "xml-hash");
+    documents.add_document_hash(&owner, &1, &HashAlgo::Unspecified, &xml_hash);
+
+    let hashes = documents.get_document_hashes(&1);
+    assert_eq!(hashes.len(), 2);
+    assert_eq!(hashes.get(1).unwrap().hash, xml_hash);
+    assert_eq!(documents.get_token_by_hash(&xml_hash), 1);
+
+    e.as_contract(&documents.address, || {
+        let entry = DocumentHash {
+            algo: HashAlgo::Unspecified,
+            hash: String::from_str(&e, "pdf-hash"),
+        };
+        assert_eq!(
+            document::add_extra_hash(&e, 1, &entry),
+            Err(ExtendedError::DuplicateDocumentHash)
+        );
+        for hash in ["a", "b", "c"] {
+            let entry = DocumentHash {
+                algo: HashAlgo::Unspecified,
+                hash: String::from_str(&e, hash),
+            };
+            assert_eq!(document::add_extra_hash(&e, 1, &entry), Ok(()));
+        }
+        let entry = DocumentHash {
+            algo: HashAlgo::Unspecified,
+            hash: String::from_str(&e, "d"),
+        };
+        assert_eq!(
+            document::add_extra_hash(&e, 1, &entry),
+            Err(ExtendedError::TooManyHashes)
+        );
+    });
+
+    // Signing against the added hash counts like the minted one.
+    documents.sign_document(&xml_hash, &signer, &SignatureStatus::Signed, &1);
+    assert_eq!(documents.get_document_status(&1), DocumentStatus::Completed);
+}
+
+#[test]
+fn test_sign_request() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    for token_id in 1..=2 {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, if token_id == 1 { "hash1" } else { "hash2" }),
+            &1000,
+            &vec![&e],
+        );
+    }
+    assert_eq!(documents.get_nonces(&signer), 0);
+
+    let note = String::from_str(&e, "signed on behalf of ACME");
+    let receipt = documents.sign(
+        &signer,
+        &SignRequest {
+            doc_id: 1,
+            hash: String::from_str(&e, "hash1"),
+            status: SignatureStatus::Signed,
+            note: note.clone(),
+            expected_nonce: 0,
+        },
+    );
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert!(receipt.completed);
+    assert_eq!(documents.get_nonces(&signer), 1);
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("sign_note"),
+            1u32,
+            signer.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+    let data: String = data.into_val(&e);
+    assert_eq!(data, note);
+
+    // The positional shim still works and moves the nonce along.
+    documents.sign_document(
+        &String::from_str(&e, "hash2"),
+        &signer,
+        &SignatureStatus::Signed,
+        &2,
+    );
+    assert_eq!(documents.get_nonces(&signer), 2);
+}
+
+#[test]
+fn test_sign_auth_bound_to_action() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+    documents.sign(
+        &signer,
+        &SignRequest {
+            doc_id: 1,
+            hash: hash.clone(),
+            status: SignatureStatus::Signed,
+            note: String::from_str(&e, "ok"),
+            expected_nonce: 0,
+        },
+    );
+
+    // The signer authorizes every field of the request, not the call as a
+    // whole.
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            signer,
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    documents.address.clone(),
+                    symbol_short!("sign"),
+                    (
+                        1u32,
+                        hash,
+                        SignatureStatus::Signed,
+                        String::from_str(&e, "ok"),
+                        0u32,
+                    )
+                        .into_val(&e),
+                )),
+                sub_invocations: std::vec![],
+            }
+        )]
+    );
+}
+
+#[test]
+fn test_smart_wallet_signer() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let wallet = e.register(SmartWallet, ());
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, wallet.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+
+    // From here on the wallet's own `__check_auth` has to approve.
+    let request = SignRequest {
+        doc_id: 1,
+        hash,
+        status: SignatureStatus::Signed,
+        note: String::from_str(&e, ""),
+        expected_nonce: documents.get_nonces(&wallet),
+    };
+    e.set_auths(&[wallet_auth(
+        &e,
+        &wallet,
+        &documents.address,
+        "sign",
+        sign_args(&e, &request),
+        1,
+    )]);
+    let receipt = documents.sign(&wallet, &request);
+
+    assert!(receipt.completed);
+    assert_eq!(documents.get_nonces(&wallet), 1);
+    let checks: u32 = e.as_contract(&wallet, || {
+        e.storage().instance().get(&symbol_short!("sign")).unwrap()
+    });
+    assert_eq!(checks, 1);
+}
+
+// The arguments a signer authorizes for `sign(request)`.
+fn sign_args(e: &Env, request: &SignRequest) -> Vec<Val> {
+    vec![
+        e,
+        request.doc_id.into_val(e),
+        request.hash.into_val(e),
+        request.status.into_val(e),
+        request.note.into_val(e),
+        request.expected_nonce.into_val(e),
+    ]
+}
+
+#[test]
+fn test_sign_auth_covers_note_and_nonce() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let wallet = e.register(SmartWallet, ());
+    let documents = create_documents(&e, &admin);
+
+    let hash = String::from_str(&e, "hash1");
+    documents.safe_mint(
+        &owner,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, wallet.clone()],
+        &hash,
+        &1000,
+        &vec![&e],
+    );
+
+    let signed = SignRequest {
+        doc_id: 1,
+        hash,
+        status: SignatureStatus::Signed,
+        note: String::from_str(&e, "approved"),
+        expected_nonce: 0,
+    };
+    let auth = wallet_auth(
+        &e,
+        &wallet,
+        &documents.address,
+        "sign",
+        sign_args(&e, &signed),
+        1,
+    );
+
+    // The same authorization can't carry a different note.
+    let mut request = signed.clone();
+    request.note = String::from_str(&e, "approved, see attachment");
+    e.set_auths(core::slice::from_ref(&auth));
+    assert!(documents.try_sign(&wallet, &request).is_err());
+
+    // Nor is an authorization made for a later nonce good for this one.
+    let mut later = signed.clone();
+    later.expected_nonce = 1;
+    e.set_auths(&[wallet_auth(
+        &e,
+        &wallet,
+        &documents.address,
+        "sign",
+        sign_args(&e, &later),
+        2,
+    )]);
+    assert!(documents.try_sign(&wallet, &signed).is_err());
+
+    e.set_auths(&[auth]);
+    assert!(documents.sign(&wallet, &signed).completed);
+    assert_eq!(documents.get_nonces(&wallet), 1);
+}
+
+#[test]
+fn test_sorted_getters() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    let signers = vec![
+        &e,
+        Address::generate(&e),
+        Address::generate(&e),
+        Address::generate(&e),
+    ];
+    for token_id in [3_u32, 1, 2] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &signers,
+            &String::from_str(&e, &std::format!("hash{}", token_id)),
+            &1000,
+            &vec![&e],
+        );
+    }
+
+    let page = documents.get_owners_sorted(&0, &2);
+    assert_eq!(page, vec![&e, (1, owner.clone()), (2, owner.clone())]);
+    let page = documents.get_owners_sorted(&3, &2);
+    assert_eq!(page, vec![&e, (3, owner.clone())]);
+
+    let sorted = documents.get_signers_sorted(&1);
+    assert_eq!(sorted.len(), 3);
+    for index in 1..sorted.len() {
+        assert!(sorted.get(index - 1).unwrap().0 < sorted.get(index).unwrap().0);
+    }
+}
+
+#[test]
+fn test_dashboard() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user = Address::generate(&e);
+    let other = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+
+    // Owned by the user, signed by someone else.
+    documents.safe_mint(
+        &user,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, other.clone()],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    // Waiting on the user.
+    documents.safe_mint(
+        &other,
+        &2,
+        &String::from_str(&e, "ipfs://doc2"),
+        &vec![&e, user.clone(), other.clone()],
+        &String::from_str(&e, "hash2"),
+        &1000,
+        &vec![&e],
+    );
+    // Signed by the user.
+    documents.safe_mint(
+        &other,
+        &3,
+        &String::from_str(&e, "ipfs://doc3"),
+        &vec![&e, user.clone()],
+        &String::from_str(&e, "hash3"),
+        &1000,
+        &vec![&e],
+    );
+    documents.sign_document(
+        &String::from_str(&e, "hash3"),
+        &user,
+        &SignatureStatus::Signed,
+        &3,
+    );
+    // Unrelated.
+    documents.safe_mint(
+        &other,
+        &4,
+        &String::from_str(&e, "ipfs://doc4"),
+        &vec![&e, other.clone()],
+        &String::from_str(&e, "hash4"),
+        &1000,
+        &vec![&e],
+    );
+    // Co-owned by the user.
+    documents.safe_mint(
+        &other,
+        &5,
+        &String::from_str(&e, "ipfs://doc5"),
+        &vec![&e, other.clone()],
+        &String::from_str(&e, "hash5"),
+        &1000,
+        &vec![&e, MintOption::CoOwners(vec![&e, user.clone()])],
+    );
+
+    let dashboard = documents.get_dashboard(&user);
+    assert_eq!(dashboard.owned, vec![&e, 1, 5]);
+    assert_eq!(dashboard.awaiting_signature, vec![&e, 2]);
+    assert_eq!(dashboard.signed, vec![&e, 3]);
+    assert_eq!(
+        dashboard.status_counts.get(DocumentStatus::Pending),
+        Some(3)
+    );
+    assert_eq!(
+        dashboard.status_counts.get(DocumentStatus::Completed),
+        Some(1)
+    );
+    assert_eq!(dashboard.status_counts.len(), 2);
+}
+
+#[test]
+fn test_holder_fee_discounts() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let holder = Address::generate(&e);
+    let whale = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&holder, &1000);
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&101);
+
+    let discounts = vec![
+        &e,
+        FeeDiscount {
+            min_balance: 10,
+            discount_bps: 2_500,
+        },
+        FeeDiscount {
+            min_balance: 100,
+            discount_bps: 10_000,
+        },
+    ];
+    documents.set_fee_discounts(&discounts);
+    // No discount token and no token contract: everyone pays the full fee.
+    assert_eq!(documents.get_creation_fee_for(&holder), 101);
+
+    let discount_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &discount_token.address).mint(&holder, &10);
+    token::StellarAssetClient::new(&e, &discount_token.address).mint(&whale, &500);
+    documents.set_discount_token(&discount_token.address);
+    assert_eq!(documents.get_fee_discounts(), discounts);
+    assert_eq!(documents.get_creation_fee_for(&holder), 76);
+    assert_eq!(documents.get_creation_fee_for(&whale), 0);
+    assert_eq!(documents.get_creation_fee_for(&signer), 101);
+
+    documents.safe_mint(
+        &holder,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer],
+        &String::from_str(&e, "hash1"),
+        &1000,
+        &vec![&e],
+    );
+    assert_eq!(fee_token.balance(&holder), 924);
+    assert_eq!(
+        fee::check_fee_discounts(&vec![
+            &e,
+            FeeDiscount {
+                min_balance: 1,
+                discount_bps: 10_001,
+            }
+        ]),
+        Err(ExtendedError::InvalidDiscount)
+    );
+
+    // With more than one approval required, discounts go through proposals.
+    let admin2 = Address::generate(&e);
+    documents.propose_action(
+        &admin,
+        &ProposalAction::SetAdmins(AdminSet {
+            admins: vec![&e, admin.clone(), admin2.clone()],
+            threshold: 2,
+        }),
+    );
+    let discounts = vec![
+        &e,
+        FeeDiscount {
+            min_balance: 10,
+            discount_bps: 5_000,
+        },
+    ];
+    let proposal_id =
+        documents.propose_action(&admin, &ProposalAction::SetFeeDiscounts(discounts.clone()));
+    assert_eq!(documents.get_creation_fee_for(&holder), 76);
+    documents.approve_action(&admin2, &proposal_id);
+    assert_eq!(documents.get_fee_discounts(), discounts);
+    assert_eq!(documents.get_creation_fee_for(&holder), 51);
+    let proposal_id =
+        documents.propose_action(&admin, &ProposalAction::SetDiscountToken(fee_token.address));
+    documents.approve_action(&admin2, &proposal_id);
+    assert_eq!(documents.get_creation_fee_for(&whale), 101);
+    assert_eq!(documents.get_creation_fee_for(&holder), 51);
+}
+
+#[test]
+fn test_mint_credits() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let org = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&org, &1000);
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&100);
+
+    assert_eq!(documents.buy_credits(&org, &2), 200);
+    documents.grant_credits(&org, &1);
+    assert_eq!(documents.get_credits(&org), 3);
+    assert_eq!(fee_token.balance(&org), 800);
+
+    let mint = |token_id: u32, hash: &str| {
+        documents.safe_mint(
+            &org,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, hash),
+            &1000,
+            &vec![&e],
+        );
+    };
+    for (token_id, hash) in [(1, "hash1"), (2, "hash2"), (3, "hash3")] {
+        mint(token_id, hash);
+    }
+    assert_eq!(documents.get_credits(&org), 0);
+    assert_eq!(fee_token.balance(&org), 800);
+
+    // Cancelling gives the credit back rather than tokens.
+    documents.cancel_document(&3);
+    assert_eq!(documents.get_credits(&org), 1);
+    assert_eq!(fee_token.balance(&org), 800);
+
+    mint(4, "hash4");
+    mint(5, "hash5");
+    assert_eq!(documents.get_credits(&org), 0);
+    assert_eq!(fee_token.balance(&org), 700);
+}
+
+#[test]
+fn test_referral_earnings() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let partner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    token::StellarAssetClient::new(&e, &fee_token.address).mint(&owner, &1000);
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&100);
+    documents.set_referral_share(&2_000);
+
+    for (token_id, hash) in [(1, "hash1"), (2, "hash2")] {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, hash),
+            &1000,
+            &vec![&e, MintOption::Referrer(partner.clone())],
+        );
+    }
+    let fee_token_id = fee_token.address.clone();
+    assert_eq!(documents.get_referral_earnings(&partner, &fee_token_id), 40);
+    assert_eq!(documents.get_collected_fees(), 160);
+
+    // A refunded fee takes the unclaimed share back with it.
+    documents.cancel_document(&2);
+    assert_eq!(fee_token.balance(&owner), 900);
+    assert_eq!(
+        documents.get_referral_account(&partner, &fee_token_id),
+        ReferralAccount {
+            referrals: 2,
+            earned: 20,
+            claimed: 0,
+        }
+    );
+
+    assert_eq!(
+        documents.claim_referral_earnings(&partner, &fee_token_id),
+        20
+    );
+    assert_eq!(fee_token.balance(&partner), 20);
+    assert_eq!(documents.get_referral_earnings(&partner, &fee_token_id), 0);
+    assert_eq!(
+        documents.claim_referral_earnings(&partner, &fee_token_id),
+        0
+    );
+
+    // Once claimed, the share is held back from the refund instead, and it
+    // stays in the token the fee was paid in.
+    documents.safe_mint(
+        &owner,
+        &3,
+        &String::from_str(&e, "ipfs://doc"),
+        &vec![&e, signer.clone()],
+        &String::from_str(&e, "hash3"),
+        &1000,
+        &vec![&e, MintOption::Referrer(partner.clone())],
+    );
+    documents.set_fee_token(&create_fee_token(&e, &admin).address);
+    assert_eq!(
+        documents.claim_referral_earnings(&partner, &fee_token_id),
+        20
+    );
+    documents.cancel_document(&3);
+    assert_eq!(fee_token.balance(&owner), 880);
+    assert_eq!(fee_token.balance(&partner), 40);
+    assert_eq!(fee_token.balance(&documents.address), 80);
+    documents.set_fee_token(&fee_token_id);
+    assert_eq!(documents.get_collected_fees(), 80);
+}
+
+#[test]
+fn test_native_fee_token() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    assert_eq!(documents.get_native_fee_quote(), None);
+
+    let native = e
+        .deployer()
+        .with_stellar_asset(Bytes::from_array(&e, &[0; 4]))
+        .deploy();
+    assert_eq!(documents.set_native_fee_token(), native);
+    assert_eq!(documents.get_fee_token(), Some(native.clone()));
+
+    documents.set_creation_fee_xlm(&3);
+    assert_eq!(documents.get_creation_fee(), 30_000_000);
+    documents.set_creation_fee(&25_000_001);
+    assert_eq!(
+        documents.get_native_fee_quote(),
+        Some(NativeFeeQuote {
+            fee_token: native,
+            stroops: 25_000_001,
+            xlm: 2,
+            remainder_stroops: 5_000_001,
+        })
+    );
+    assert_eq!(fee::xlm_to_stroops(i128::MAX), Err(Error::InvalidFeeAmount));
+}
+
+#[test]
+fn test_supports() {
+    let e = Env::default();
+    let documents = create_documents(&e, &Address::generate(&e));
+
+    assert!(documents.supports(&symbol_short!("escrow")));
+    assert!(!documents.supports(&Symbol::new(&e, "nft_transfer")));
+    assert!(!documents.supports(&symbol_short!("royalties")));
+    assert!(!documents.supports(&Symbol::new(&e, "ordered_signing")));
+}
+
+#[test]
+fn test_get_config() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let fee_token = create_fee_token(&e, &admin);
+    documents.set_fee_token(&fee_token.address);
+    documents.set_creation_fee(&25);
+
+    let config = documents.get_config();
+    assert_eq!(config.version, CONTRACT_VERSION);
+    assert_eq!(config.event_schema_version, EVENT_SCHEMA_VERSION);
+    assert_eq!(config.fee_token, vec![&e, fee_token.address.clone()]);
+    assert_eq!(config.creation_fee, 25);
+    assert_eq!(config.string_limits, documents.get_string_limits());
+    assert_eq!(config.max_batch_size, documents.get_max_batch_size());
+    assert!(config.instance_ttl_threshold < config.instance_ttl_extend_to);
+    assert!(!config.sealed);
+    assert!(!config.admin_renounced);
+}
+
+#[test]
+fn test_document_event_topics() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let documents = create_documents(&e, &admin);
+    let version = EVENT_SCHEMA_VERSION;
+
+    let has_topics = |topics: soroban_sdk::Vec<soroban_sdk::Val>| {
+        all_events(&e).iter().any(|(contract, event_topics, _)| {
+            contract == documents.address && event_topics == topics
+        })
+    };
+
+    for token_id in 1..=3_u32 {
+        documents.safe_mint(
+            &owner,
+            &token_id,
+            &String::from_str(&e, "ipfs://doc"),
+            &vec![&e, signer.clone()],
+            &String::from_str(&e, &std::format!("hash{}", token_id)),
+            &1000,
+            &vec![&e],
+        );
+        assert!(has_topics(
+            (symbol_short!("mint"), token_id, version).into_val(&e)
+        ));
+    }
+
+    documents.sign_document(
+        &String::from_str(&e, "hash1"),
+        &signer,
+        &SignatureStatus::Signed,
+        &1,
+    );
+    assert!(has_topics(
+        (symbol_short!("sign"), 1_u32, signer.clone(), version).into_val(&e)
+    ));
+    assert!(has_topics(
+        (symbol_short!("complete"), 1_u32, version).into_val(&e)
+    ));
+
+    documents.sign_document(
+        &String::from_str(&e, "hash2"),
+        &signer,
+        &SignatureStatus::Rejected,
+        &2,
+    );
+    assert!(has_topics(
+        (symbol_short!("reject"), 2_u32, version).into_val(&e)
+    ));
+
+    e.ledger().with_mut(|li| li.timestamp = 1001);
+    documents.expire_document(&3);
+    assert!(has_topics(
+        (symbol_short!("expire"), 3_u32, version).into_val(&e)
+    ));
+}
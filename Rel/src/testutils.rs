@@ -0,0 +1,67 @@
+#![cfg(any(test, feature = "testutils"))]
+
+//! Helpers for driving the contract from other crates' tests. Enabled with
+//! the `testutils` feature.
+
+use soroban_sdk::{Address, Env, Map, String, Vec};
+
+use crate::document::{
+    add_to_index, read_signings, write_creator, write_deadline, write_document_hash, write_owner,
+    write_signings, write_uri,
+};
+use crate::status::write_status;
+use crate::{DocumentStatus, PetalDocuments, PetalDocumentsClient, SignatureStatus};
+
+/// Registers a fresh contract with `admin` and no token contract.
+pub fn register<'a>(e: &Env, admin: &Address) -> PetalDocumentsClient<'a> {
+    PetalDocumentsClient::new(e, &e.register(PetalDocuments, (admin, None::<Address>)))
+}
+
+/// Writes a pending document straight into storage, skipping auth, fees and
+/// the mint-time checks.
+pub fn seed_document(
+    e: &Env,
+    contract: &Address,
+    token_id: u32,
+    owner: &Address,
+    signers: &Vec<Address>,
+    document_hash: &String,
+    deadline: u64,
+) {
+    e.as_contract(contract, || {
+        let mut signings: Map<Address, SignatureStatus> = Map::new(e);
+        for signer in signers.iter() {
+            signings.set(signer, SignatureStatus::Waiting);
+        }
+
+        write_owner(e, token_id, owner);
+        write_uri(e, token_id, &String::from_str(e, ""));
+        write_creator(e, token_id, owner);
+        write_document_hash(e, token_id, document_hash);
+        write_deadline(e, token_id, deadline);
+        write_signings(e, token_id, &signings);
+        add_to_index(e, token_id);
+        write_status(e, token_id, DocumentStatus::Pending);
+    });
+}
+
+/// Overrides one signer's status without going through `sign_document`, so
+/// no audit entry or completion is recorded.
+pub fn seed_signature(
+    e: &Env,
+    contract: &Address,
+    token_id: u32,
+    signer: &Address,
+    status: SignatureStatus,
+) {
+    e.as_contract(contract, || {
+        let mut signings = read_signings(e, token_id).unwrap_or(Map::new(e));
+        signings.set(signer.clone(), status);
+        write_signings(e, token_id, &signings);
+    });
+}
+
+/// Forces the document status, e.g. to test paths for completed documents.
+pub fn seed_status(e: &Env, contract: &Address, token_id: u32, status: DocumentStatus) {
+    e.as_contract(contract, || write_status(e, token_id, status));
+}
@@ -0,0 +1,124 @@
+use soroban_sdk::{panic_with_error, Address, Env, Map, String};
+
+use crate::storage_types::DataKey;
+use crate::Error;
+
+// Built from the workspace token crate, see `make build-with-token`.
+#[cfg(feature = "token-contract")]
+pub(crate) mod erc721 {
+    soroban_sdk::contractimport!(
+        file = "../token/target/wasm32v1-none/release/soroban_token_contract.wasm"
+    );
+}
+
+pub fn read_token_contract(e: &Env) -> Option<Address> {
+    let key = DataKey::TokenContract;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_token_contract(e: &Env, token_contract: &Address) {
+    if !cfg!(feature = "token-contract") {
+        panic_with_error!(e, Error::TokenContractUnavailable)
+    }
+    let key = DataKey::TokenContract;
+    e.storage().instance().set(&key, token_contract);
+}
+
+#[cfg(feature = "token-contract")]
+pub fn mint(e: &Env, token_contract: &Address, token_id: u32, to: &Address, token_uri: &String) {
+    let client = erc721::Client::new(e, token_contract);
+    client.mint(&token_id, to);
+    client.set_token_uri(&e.current_contract_address(), &token_id, token_uri);
+}
+
+#[cfg(feature = "token-contract")]
+pub fn set_owner(e: &Env, token_contract: &Address, token_id: u32, owner: &Address) {
+    // The token contract only lets its admin reassign tokens it doesn't hold,
+    // so this contract has to be the token admin.
+    erc721::Client::new(e, token_contract).set_owners(
+        &e.current_contract_address(),
+        &token_id,
+        owner,
+    );
+}
+
+#[cfg(feature = "token-contract")]
+pub fn lock_transfer(e: &Env, token_contract: &Address, token_id: u32, until_ledger: u32) {
+    erc721::Client::new(e, token_contract).lock_transfer(&token_id, &until_ledger);
+}
+
+#[cfg(feature = "token-contract")]
+pub fn set_token_uri(e: &Env, token_contract: &Address, token_id: u32, token_uri: &String) {
+    // Like `set_owner`, this relies on this contract being the token admin.
+    erc721::Client::new(e, token_contract).set_token_uri(
+        &e.current_contract_address(),
+        &token_id,
+        token_uri,
+    );
+}
+
+#[cfg(feature = "token-contract")]
+pub fn require_minted(e: &Env, token_contract: &Address, token_id: u32) -> bool {
+    erc721::Client::new(e, token_contract).require_minted(&token_id)
+}
+
+#[cfg(feature = "token-contract")]
+pub fn owner_of(e: &Env, token_contract: &Address, token_id: u32) -> Option<Address> {
+    erc721::Client::new(e, token_contract).owner_of(&token_id)
+}
+
+#[cfg(feature = "token-contract")]
+pub fn owners(e: &Env, token_contract: &Address) -> Map<u32, Address> {
+    erc721::Client::new(e, token_contract).get_owners()
+}
+
+#[cfg(feature = "token-contract")]
+pub fn token_uri(e: &Env, token_contract: &Address, token_id: u32) -> String {
+    erc721::Client::new(e, token_contract).token_uri(&token_id)
+}
+
+#[cfg(not(feature = "token-contract"))]
+pub fn mint(
+    e: &Env,
+    _token_contract: &Address,
+    _token_id: u32,
+    _to: &Address,
+    _token_uri: &String,
+) {
+    panic_with_error!(e, Error::TokenContractUnavailable)
+}
+
+#[cfg(not(feature = "token-contract"))]
+pub fn set_owner(e: &Env, _token_contract: &Address, _token_id: u32, _owner: &Address) {
+    panic_with_error!(e, Error::TokenContractUnavailable)
+}
+
+#[cfg(not(feature = "token-contract"))]
+pub fn lock_transfer(e: &Env, _token_contract: &Address, _token_id: u32, _until_ledger: u32) {
+    panic_with_error!(e, Error::TokenContractUnavailable)
+}
+
+#[cfg(not(feature = "token-contract"))]
+pub fn set_token_uri(e: &Env, _token_contract: &Address, _token_id: u32, _token_uri: &String) {
+    panic_with_error!(e, Error::TokenContractUnavailable)
+}
+
+#[cfg(not(feature = "token-contract"))]
+pub fn require_minted(e: &Env, _token_contract: &Address, _token_id: u32) -> bool {
+    panic_with_error!(e, Error::TokenContractUnavailable)
+}
+
+#[cfg(not(feature = "token-contract"))]
+pub fn owner_of(e: &Env, _token_contract: &Address, _token_id: u32) -> Option<Address> {
+    panic_with_error!(e, Error::TokenContractUnavailable)
+}
+
+#[cfg(not(feature = "token-contract"))]
+pub fn owners(e: &Env, _token_contract: &Address) -> Map<u32, Address> {
+    panic_with_error!(e, Error::TokenContractUnavailable)
+}
+
+#[cfg(not(feature = "token-contract"))]
+pub fn token_uri(e: &Env, _token_contract: &Address, _token_id: u32) -> String {
+    panic_with_error!(e, Error::TokenContractUnavailable)
+}
@@ -0,0 +1,38 @@
+use soroban_sdk::{panic_with_error, xdr::ToXdr, Bytes, Env, String, Vec};
+
+use crate::storage_types::DataKey;
+use crate::Error;
+
+// An empty list, the default, turns validation off.
+pub fn read_uri_prefixes(e: &Env) -> Vec<String> {
+    let key = DataKey::UriPrefixes;
+    e.storage().instance().get(&key).unwrap_or(Vec::new(e))
+}
+
+pub fn write_uri_prefixes(e: &Env, prefixes: &Vec<String>) {
+    let key = DataKey::UriPrefixes;
+    e.storage().instance().set(&key, prefixes);
+}
+
+// `String` has no byte access of its own, so read it back out of its XDR:
+// a 4 byte type tag and a 4 byte length come before the contents.
+fn to_bytes(e: &Env, value: &String) -> Bytes {
+    value.clone().to_xdr(e).slice(8..8 + value.len())
+}
+
+pub fn is_allowed(e: &Env, uri: &String) -> bool {
+    let prefixes = read_uri_prefixes(e);
+    if prefixes.is_empty() {
+        return true;
+    }
+    let uri = to_bytes(e, uri);
+    prefixes.iter().any(|prefix| {
+        prefix.len() <= uri.len() && uri.slice(0..prefix.len()) == to_bytes(e, &prefix)
+    })
+}
+
+pub fn validate(e: &Env, uri: &String) {
+    if !is_allowed(e, uri) {
+        panic_with_error!(e, Error::InvalidUri)
+    }
+}
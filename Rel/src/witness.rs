@@ -0,0 +1,36 @@
+use soroban_sdk::{Address, Env, Map, Vec};
+
+use crate::document::{read_signings, write_signings};
+use crate::history;
+use crate::storage_types::DocKey;
+use crate::{ExtendedError, SignatureStatus};
+
+// Witnesses sign in the same signer map as the parties, but are listed here
+// so completion and certificates can tell the two apart.
+pub fn read_witnesses(e: &Env, token_id: u32) -> Vec<Address> {
+    let key = DocKey::Witnesses(token_id);
+    e.storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Vec::new(e))
+}
+
+// Adds `witnesses` as waiting signers. An address can't be both a party and
+// a witness on the same document.
+pub fn add(e: &Env, token_id: u32, witnesses: &Vec<Address>) -> Result<(), ExtendedError> {
+    let mut signings = read_signings(e, token_id).unwrap_or_else(|| Map::new(e));
+    let mut listed = read_witnesses(e, token_id);
+    for witness in witnesses.iter() {
+        if signings.contains_key(witness.clone()) {
+            return Err(ExtendedError::DuplicateSigner);
+        }
+        signings.set(witness.clone(), SignatureStatus::Waiting);
+        history::record(e, token_id, &witness, SignatureStatus::Waiting);
+        listed.push_back(witness);
+    }
+    write_signings(e, token_id, &signings);
+    e.storage()
+        .persistent()
+        .set(&DocKey::Witnesses(token_id), &listed);
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage_types::{
+    SharedKey, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK, INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+};
+
+// Every admin-gated entrypoint reads the admin, so touching it keeps the
+// instance entry from being archived.
+fn extend_instance_ttl(e: &Env) {
+    e.storage().instance().extend_ttl(
+        INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+        INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+    );
+}
+
+pub fn has_administrator(e: &Env) -> bool {
+    let key = SharedKey::Admin;
+    e.storage().instance().has(&key)
+}
+
+pub fn read_administrator(e: &Env) -> Address {
+    try_read_administrator(e).unwrap_or_else(|| panic!("Admin not found"))
+}
+
+// For contracts that fail with their own error when there is no admin.
+pub fn try_read_administrator(e: &Env) -> Option<Address> {
+    let key = SharedKey::Admin;
+    let admin = e.storage().instance().get(&key);
+    if admin.is_some() {
+        extend_instance_ttl(e);
+    }
+    admin
+}
+
+pub fn write_administrator(e: &Env, id: &Address) {
+    let key = SharedKey::Admin;
+    e.storage().instance().set(&key, id);
+    extend_instance_ttl(e);
+}
+
+// A renounced contract has no admin and can't be initialised again.
+pub fn clear_administrator(e: &Env) {
+    e.storage().instance().remove(&SharedKey::Admin);
+    e.storage()
+        .instance()
+        .set(&SharedKey::AdminRenounced, &true);
+    extend_instance_ttl(e);
+}
+
+pub fn is_renounced(e: &Env) -> bool {
+    let key = SharedKey::AdminRenounced;
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+// Renouncing is opt-in; contracts check this before clearing the admin.
+pub fn read_renounceable(e: &Env) -> bool {
+    let key = SharedKey::AdminRenounceable;
+    e.storage().instance().get(&key).unwrap_or(false)
+}
+
+pub fn write_renounceable(e: &Env, enabled: bool) {
+    let key = SharedKey::AdminRenounceable;
+    e.storage().instance().set(&key, &enabled);
+}
+
+// Renouncing takes two steps: the request records its ledger, and the
+// confirmation is only accepted in a later one, so a single transaction
+// can't drop the admin by accident.
+pub fn read_renounce_request(e: &Env) -> Option<u32> {
+    let key = SharedKey::RenounceRequested;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_renounce_request(e: &Env) {
+    let key = SharedKey::RenounceRequested;
+    e.storage().instance().set(&key, &e.ledger().sequence());
+}
+
+pub fn clear_renounce_request(e: &Env) {
+    e.storage().instance().remove(&SharedKey::RenounceRequested);
+}
+
+pub fn is_renounce_confirmable(e: &Env) -> bool {
+    match read_renounce_request(e) {
+        Some(requested) => requested < e.ledger().sequence(),
+        None => false,
+    }
+}
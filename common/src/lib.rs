@@ -0,0 +1,17 @@
+#![no_std]
+
+//! Storage helpers and event builders shared by the Petal contracts.
+//!
+//! Keys are encoded exactly like the `DataKey` variants of the same name in
+//! the contracts, so a contract can move to these helpers without migrating
+//! any entries.
+
+pub mod admin;
+pub mod event;
+pub mod owner;
+mod storage_types;
+mod test;
+pub mod uri;
+
+pub use crate::owner::OwnerStore;
+pub use crate::storage_types::SharedKey;
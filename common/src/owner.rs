@@ -0,0 +1,48 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage_types::{SharedKey, OWNER_BUMP_AMOUNT, OWNER_LIFETIME_THRESHOLD};
+
+/// Per-token owner entries in persistent storage, one entry per token so
+/// that reads don't grow with the number of minted tokens. Reads and writes
+/// extend the entry's TTL, so owned tokens don't get archived.
+pub struct OwnerStore<'a> {
+    e: &'a Env,
+}
+
+impl<'a> OwnerStore<'a> {
+    pub fn new(e: &'a Env) -> Self {
+        OwnerStore { e }
+    }
+
+    pub fn get(&self, token_id: u32) -> Option<Address> {
+        let key = SharedKey::Owner(token_id);
+        let owner = self.e.storage().persistent().get(&key);
+        if owner.is_some() {
+            self.extend_ttl(&key);
+        }
+        owner
+    }
+
+    pub fn has(&self, token_id: u32) -> bool {
+        let key = SharedKey::Owner(token_id);
+        self.e.storage().persistent().has(&key)
+    }
+
+    pub fn set(&self, token_id: u32, owner: &Address) {
+        let key = SharedKey::Owner(token_id);
+        self.e.storage().persistent().set(&key, owner);
+        self.extend_ttl(&key);
+    }
+
+    pub fn remove(&self, token_id: u32) {
+        let key = SharedKey::Owner(token_id);
+        self.e.storage().persistent().remove(&key);
+    }
+
+    fn extend_ttl(&self, key: &SharedKey) {
+        self.e
+            .storage()
+            .persistent()
+            .extend_ttl(key, OWNER_LIFETIME_THRESHOLD, OWNER_BUMP_AMOUNT);
+    }
+}
@@ -0,0 +1,17 @@
+use soroban_sdk::contracttype;
+
+pub(crate) const INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK: u32 = 34560; // 2 days
+pub(crate) const INSTANCE_BUMP_AMOUNT_LOW_WATERMARK: u32 = 17280; // 1 day
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
+pub(crate) const OWNER_BUMP_AMOUNT: u32 = 518400; // 30 days
+pub(crate) const OWNER_LIFETIME_THRESHOLD: u32 = OWNER_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+#[derive(Clone)]
+#[contracttype]
+pub enum SharedKey {
+    Admin,
+    Owner(u32),
+    AdminRenounced,
+    AdminRenounceable,
+    RenounceRequested,
+}
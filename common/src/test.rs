@@ -0,0 +1,163 @@
+#![cfg(test)]
+
+use crate::admin::{
+    clear_administrator, clear_renounce_request, has_administrator, is_renounce_confirmable,
+    is_renounced, read_administrator, read_renounce_request, read_renounceable,
+    try_read_administrator, write_administrator, write_renounce_request, write_renounceable,
+};
+use crate::uri::{concat_token_uri, MAX_TOKEN_URI_LEN};
+use crate::{OwnerStore, SharedKey};
+use soroban_sdk::{
+    contract, contracttype,
+    testutils::{storage::Persistent, Address as _, Ledger},
+    Address, Env, String,
+};
+
+#[contract]
+struct Host;
+
+// Mirrors the layout of a contract's own `DataKey`.
+#[contracttype]
+enum DataKey {
+    Admin,
+    Owner(u32),
+    AdminRenounceable,
+    RenounceRequested,
+}
+
+#[test]
+fn keys_match_contract_data_keys() {
+    let e = Env::default();
+    let host = e.register(Host, ());
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+
+    e.as_contract(&host, || {
+        assert!(!has_administrator(&e));
+        e.storage().instance().set(&DataKey::Admin, &admin);
+        assert_eq!(read_administrator(&e), admin);
+
+        let owners = OwnerStore::new(&e);
+        owners.set(7, &owner);
+        let stored: Address = e.storage().persistent().get(&DataKey::Owner(7)).unwrap();
+        assert_eq!(stored, owner);
+        assert!(e.storage().persistent().has(&SharedKey::Owner(7)));
+    });
+}
+
+#[test]
+fn owner_store_round_trip() {
+    let e = Env::default();
+    let host = e.register(Host, ());
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+
+    e.as_contract(&host, || {
+        write_administrator(&e, &admin);
+        assert!(has_administrator(&e));
+
+        let owners = OwnerStore::new(&e);
+        assert_eq!(owners.get(1), None);
+        owners.set(1, &owner);
+        assert!(owners.has(1));
+        assert_eq!(owners.get(1), Some(owner.clone()));
+        owners.remove(1);
+        assert!(!owners.has(1));
+    });
+}
+
+#[test]
+fn administrator_can_be_cleared() {
+    let e = Env::default();
+    let host = e.register(Host, ());
+    let admin = Address::generate(&e);
+
+    e.as_contract(&host, || {
+        write_administrator(&e, &admin);
+        assert_eq!(read_administrator(&e), admin);
+
+        assert!(!is_renounced(&e));
+        clear_administrator(&e);
+        assert!(!has_administrator(&e));
+        assert!(is_renounced(&e));
+    });
+}
+
+#[test]
+fn renounceable_matches_contract_key() {
+    let e = Env::default();
+    let host = e.register(Host, ());
+
+    e.as_contract(&host, || {
+        assert_eq!(try_read_administrator(&e), None);
+        assert!(!read_renounceable(&e));
+        e.storage()
+            .instance()
+            .set(&DataKey::AdminRenounceable, &true);
+        assert!(read_renounceable(&e));
+        write_renounceable(&e, false);
+        assert!(!read_renounceable(&e));
+    });
+}
+
+#[test]
+fn renounce_request_confirmable_later() {
+    let e = Env::default();
+    let host = e.register(Host, ());
+    e.ledger().with_mut(|li| li.sequence_number = 10);
+
+    e.as_contract(&host, || {
+        assert!(!is_renounce_confirmable(&e));
+        write_renounce_request(&e);
+        assert_eq!(read_renounce_request(&e), Some(10));
+        let stored: u32 = e
+            .storage()
+            .instance()
+            .get(&DataKey::RenounceRequested)
+            .unwrap();
+        assert_eq!(stored, 10);
+        assert!(!is_renounce_confirmable(&e));
+    });
+
+    e.ledger().with_mut(|li| li.sequence_number = 11);
+    e.as_contract(&host, || {
+        assert!(is_renounce_confirmable(&e));
+        clear_renounce_request(&e);
+        assert_eq!(read_renounce_request(&e), None);
+        assert!(!is_renounce_confirmable(&e));
+    });
+}
+
+#[test]
+fn owner_store_extends_ttl() {
+    let e = Env::default();
+    let host = e.register(Host, ());
+    let owner = Address::generate(&e);
+
+    e.as_contract(&host, || {
+        let owners = OwnerStore::new(&e);
+        owners.set(1, &owner);
+        let key = SharedKey::Owner(1);
+        assert_eq!(e.storage().persistent().get_ttl(&key), 518400);
+    });
+}
+
+#[test]
+fn token_uri_appends_id() {
+    let e = Env::default();
+    let base = String::from_str(&e, "https://petal.example/");
+    assert_eq!(
+        concat_token_uri(&e, &base, 0),
+        Some(String::from_str(&e, "https://petal.example/0"))
+    );
+    assert_eq!(
+        concat_token_uri(&e, &base, u32::MAX),
+        Some(String::from_str(&e, "https://petal.example/4294967295"))
+    );
+
+    // The id has to fit after the base.
+    let long = [b'a'; MAX_TOKEN_URI_LEN - 1];
+    let base = String::from_bytes(&e, &long);
+    assert!(concat_token_uri(&e, &base, 7).is_some());
+    assert_eq!(concat_token_uri(&e, &base, 10), None);
+}
@@ -0,0 +1,31 @@
+use soroban_sdk::{Env, String};
+
+pub const MAX_TOKEN_URI_LEN: usize = 256;
+
+// `base_uri` followed by the decimal token id, or `None` when the result
+// would be longer than `MAX_TOKEN_URI_LEN` bytes.
+pub fn concat_token_uri(e: &Env, base_uri: &String, token_id: u32) -> Option<String> {
+    let base_len = base_uri.len() as usize;
+    let mut digits = [0u8; 10];
+    let mut digit_count = 0;
+    let mut id = token_id;
+    loop {
+        digits[digit_count] = b'0' + (id % 10) as u8;
+        digit_count += 1;
+        id /= 10;
+        if id == 0 {
+            break;
+        }
+    }
+
+    if base_len + digit_count > MAX_TOKEN_URI_LEN {
+        return None;
+    }
+
+    let mut buf = [0u8; MAX_TOKEN_URI_LEN];
+    base_uri.copy_into_slice(&mut buf[..base_len]);
+    for i in 0..digit_count {
+        buf[base_len + i] = digits[digit_count - 1 - i];
+    }
+    Some(String::from_bytes(e, &buf[..base_len + digit_count]))
+}
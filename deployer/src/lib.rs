@@ -1,12 +1,160 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Symbol, Val, Vec};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, panic_with_error, Address, Bytes, BytesN,
+    Env, String, Symbol, Val, Vec,
+};
+
+mod test;
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    ApprovedWasm(BytesN<32>),
+    // Labels are scoped to the deploying address, so nobody can take a
+    // label another deployer resolves through.
+    Labeled(Address, String),
+    Release(String),
+    LatestRelease,
+    // The Wasm hash each contract deployed through this deployer runs.
+    DeployedWasm(Address),
+}
+
+// A PetalDocuments release clients can resolve from chain. `deployed_at` is
+// the ledger sequence it was registered in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct Release {
+    pub version: String,
+    pub wasm_hash: BytesN<32>,
+    pub contract: Address,
+    pub deployed_at: u32,
+}
+
+const MAX_LABEL_LEN: u32 = 64; // bytes
+const MAX_VERSION_LEN: u32 = 32; // bytes
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum DeployerError {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    WasmNotApproved = 3,
+    LabelTaken = 4,
+    LabelTooLong = 5,
+    ReleaseExists = 6,
+    InvalidVersion = 7,
+    ReleaseWasmMismatch = 8,
+}
+
+fn read_admin(env: &Env) -> Address {
+    match env.storage().instance().get(&DataKey::Admin) {
+        Some(admin) => admin,
+        None => panic_with_error!(env, DeployerError::NotInitialized),
+    }
+}
+
+// The salt for a labeled deployment is the SHA-256 of the label's UTF-8
+// bytes, so the address can be worked out off-chain from the deploying
+// address and the label.
+pub(crate) fn label_salt(env: &Env, label: &String) -> BytesN<32> {
+    if label.len() > MAX_LABEL_LEN {
+        panic_with_error!(env, DeployerError::LabelTooLong)
+    }
+    let mut buf = [0u8; MAX_LABEL_LEN as usize];
+    let bytes = &mut buf[..label.len() as usize];
+    label.copy_into_slice(bytes);
+    env.crypto().sha256(&Bytes::from_slice(env, bytes)).into()
+}
+
+// Records where `deployer` deployed `label`. Each deployer can use a label
+// once.
+pub(crate) fn record_label(
+    env: &Env,
+    deployer: &Address,
+    label: &String,
+    contract: &Address,
+) -> Result<(), DeployerError> {
+    let key = DataKey::Labeled(deployer.clone(), label.clone());
+    if env.storage().persistent().has(&key) {
+        return Err(DeployerError::LabelTaken);
+    }
+    env.storage().persistent().set(&key, contract);
+    Ok(())
+}
+
+// Parses a `MAJOR.MINOR.PATCH` version. Pre-release and build suffixes
+// aren't accepted, so versions always compare numerically.
+pub(crate) fn parse_version(version: &String) -> Result<(u32, u32, u32), DeployerError> {
+    if version.len() > MAX_VERSION_LEN {
+        return Err(DeployerError::InvalidVersion);
+    }
+    let mut buf = [0u8; MAX_VERSION_LEN as usize];
+    let bytes = &mut buf[..version.len() as usize];
+    version.copy_into_slice(bytes);
+
+    let mut parts = [0u32; 3];
+    let mut index = 0;
+    let mut digits = 0;
+    for byte in bytes.iter() {
+        match byte {
+            b'0'..=b'9' => {
+                parts[index] = parts[index]
+                    .checked_mul(10)
+                    .and_then(|part| part.checked_add((byte - b'0') as u32))
+                    .ok_or(DeployerError::InvalidVersion)?;
+                digits += 1;
+            }
+            b'.' if digits > 0 && index < 2 => {
+                index += 1;
+                digits = 0;
+            }
+            _ => return Err(DeployerError::InvalidVersion),
+        }
+    }
+    if index != 2 || digits == 0 {
+        return Err(DeployerError::InvalidVersion);
+    }
+    Ok((parts[0], parts[1], parts[2]))
+}
+
+pub(crate) fn record_deployment(env: &Env, contract: &Address, wasm_hash: &BytesN<32>) {
+    let key = DataKey::DeployedWasm(contract.clone());
+    env.storage().persistent().set(&key, wasm_hash);
+}
 
 #[contract]
 pub struct PetalDeployer;
 
 #[contractimpl]
 impl PetalDeployer {
+    pub fn init(env: Env, admin: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(&env, DeployerError::AlreadyInitialized)
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    // Only approved Wasm hashes can be deployed.
+    pub fn approve_wasm(env: Env, wasm_hash: BytesN<32>) {
+        read_admin(&env).require_auth();
+        let key = DataKey::ApprovedWasm(wasm_hash);
+        env.storage().persistent().set(&key, &true);
+    }
+
+    pub fn revoke_wasm(env: Env, wasm_hash: BytesN<32>) {
+        read_admin(&env).require_auth();
+        let key = DataKey::ApprovedWasm(wasm_hash);
+        env.storage().persistent().remove(&key);
+    }
+
+    pub fn is_approved(env: Env, wasm_hash: BytesN<32>) -> bool {
+        let key = DataKey::ApprovedWasm(wasm_hash);
+        env.storage().persistent().has(&key)
+    }
+
     pub fn deploy(
         env: Env,
         deployer: Address,
@@ -20,13 +168,156 @@ impl PetalDeployer {
             deployer.require_auth();
         }
 
+        Self::deploy_with_salt(env, deployer, wasm_hash, salt, init_fn, init_args)
+    }
+
+    // Like `deploy`, for contracts with a `__constructor`. The constructor
+    // runs with `constructor_args` as part of the deployment, so there is no
+    // separate init call anyone could get in ahead of.
+    pub fn deploy_v2(
+        env: Env,
+        deployer: Address,
+        wasm_hash: BytesN<32>,
+        salt: BytesN<32>,
+        constructor_args: Vec<Val>,
+    ) -> Address {
+        if deployer != env.current_contract_address() {
+            deployer.require_auth();
+        }
+        if !Self::is_approved(env.clone(), wasm_hash.clone()) {
+            panic_with_error!(&env, DeployerError::WasmNotApproved)
+        }
+
+        let deployed_address = env
+            .deployer()
+            .with_address(deployer, salt)
+            .deploy_v2(wasm_hash.clone(), constructor_args);
+        record_deployment(&env, &deployed_address, &wasm_hash);
+        deployed_address
+    }
+
+    // Like `deploy`, with the salt derived from `label`. Each deployer can
+    // deploy a label once, and it resolves to its address through
+    // `get_labeled` together with the deployer.
+    pub fn deploy_labeled(
+        env: Env,
+        deployer: Address,
+        wasm_hash: BytesN<32>,
+        label: String,
+        init_fn: Symbol,
+        init_args: Vec<Val>,
+    ) -> (Address, Val) {
+        if deployer != env.current_contract_address() {
+            deployer.require_auth();
+        }
+        if Self::get_labeled(env.clone(), deployer.clone(), label.clone()).is_some() {
+            panic_with_error!(&env, DeployerError::LabelTaken)
+        }
+
+        let salt = label_salt(&env, &label);
+        let deployed = Self::deploy_with_salt(
+            env.clone(),
+            deployer.clone(),
+            wasm_hash,
+            salt,
+            init_fn,
+            init_args,
+        );
+        if let Err(error) = record_label(&env, &deployer, &label, &deployed.0) {
+            panic_with_error!(&env, error)
+        }
+        deployed
+    }
+
+    pub fn get_labeled(env: Env, deployer: Address, label: String) -> Option<Address> {
+        let key = DataKey::Labeled(deployer, label);
+        env.storage().persistent().get(&key)
+    }
+
+    // Records `version` (a `MAJOR.MINOR.PATCH` string) for `contract`, which
+    // must have been deployed through this deployer from `wasm_hash`.
+    // Versions can't be registered twice, and the latest release is the
+    // highest version, so backporting a patch to an older line doesn't
+    // replace it.
+    pub fn register_release(
+        env: Env,
+        version: String,
+        wasm_hash: BytesN<32>,
+        contract: Address,
+    ) -> Release {
+        read_admin(&env).require_auth();
+        if !Self::is_approved(env.clone(), wasm_hash.clone()) {
+            panic_with_error!(&env, DeployerError::WasmNotApproved)
+        }
+        let parsed = match parse_version(&version) {
+            Ok(parsed) => parsed,
+            Err(error) => panic_with_error!(&env, error),
+        };
+        let deployed_key = DataKey::DeployedWasm(contract.clone());
+        let deployed: Option<BytesN<32>> = env.storage().persistent().get(&deployed_key);
+        if deployed != Some(wasm_hash.clone()) {
+            panic_with_error!(&env, DeployerError::ReleaseWasmMismatch)
+        }
+        let key = DataKey::Release(version.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(&env, DeployerError::ReleaseExists)
+        }
+
+        let release = Release {
+            version: version.clone(),
+            wasm_hash,
+            contract,
+            deployed_at: env.ledger().sequence(),
+        };
+        env.storage().persistent().set(&key, &release);
+        let latest: Option<String> = env.storage().instance().get(&DataKey::LatestRelease);
+        let is_latest = match latest {
+            // Stored versions were parsed when they were registered.
+            Some(latest) => parse_version(&latest).map_or(true, |latest| parsed > latest),
+            None => true,
+        };
+        if is_latest {
+            env.storage()
+                .instance()
+                .set(&DataKey::LatestRelease, &version);
+        }
+        release
+    }
+
+    pub fn latest_release(env: Env) -> Option<Release> {
+        let version: String = env.storage().instance().get(&DataKey::LatestRelease)?;
+        Self::get_release(env, version)
+    }
+
+    pub fn get_release(env: Env, version: String) -> Option<Release> {
+        let key = DataKey::Release(version);
+        env.storage().persistent().get(&key)
+    }
+
+    fn deploy_with_salt(
+        env: Env,
+        deployer: Address,
+        wasm_hash: BytesN<32>,
+        salt: BytesN<32>,
+        init_fn: Symbol,
+        init_args: Vec<Val>,
+    ) -> (Address, Val) {
+        if !Self::is_approved(env.clone(), wasm_hash.clone()) {
+            panic_with_error!(&env, DeployerError::WasmNotApproved)
+        }
+
         // Deploy the contract using the uploaded Wasm with given hash.
         let deployed_address = env
             .deployer()
             .with_address(deployer, salt)
-            .deploy(wasm_hash);
+            .deploy_v2(wasm_hash.clone(), ());
+        record_deployment(&env, &deployed_address, &wasm_hash);
 
-        // Invoke the init function with the given arguments.
+        // Invoke the init function with the given arguments. This runs in
+        // the same invocation as the deployment, so nobody can initialize
+        // the new contract first. The deployment passes no constructor
+        // arguments, so contracts whose constructor takes any go through
+        // `deploy_v2` instead.
         let res: Val = env.invoke_contract(&deployed_address, &init_fn, init_args);
         // Return the contract ID of the deployed contract and the result of
         // invoking the init result.
@@ -0,0 +1,185 @@
+#![cfg(test)]
+
+use crate::{
+    label_salt, parse_version, record_deployment, record_label, DeployerError, PetalDeployer,
+    PetalDeployerClient,
+};
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, Ledger},
+    vec, Address, Bytes, BytesN, Env, IntoVal, String, Symbol,
+};
+
+// Stores `init_value` under `init_key` in its constructor. Copied from the
+// SDK's `test_constructor` test contract.
+mod constructor {
+    soroban_sdk::contractimport!(file = "test_wasms/constructor.wasm");
+}
+
+#[test]
+fn test_wasm_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let deployer = PetalDeployerClient::new(&env, &env.register(PetalDeployer, ()));
+    deployer.init(&Address::generate(&env));
+
+    let wasm_hash = BytesN::from_array(&env, &[7; 32]);
+    assert!(!deployer.is_approved(&wasm_hash));
+
+    deployer.approve_wasm(&wasm_hash);
+    assert!(deployer.is_approved(&wasm_hash));
+
+    deployer.revoke_wasm(&wasm_hash);
+    assert!(!deployer.is_approved(&wasm_hash));
+}
+
+#[test]
+fn test_deploy_v2_runs_constructor() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let deployer = PetalDeployerClient::new(&env, &env.register(PetalDeployer, ()));
+    deployer.init(&Address::generate(&env));
+    let owner = Address::generate(&env);
+    let wasm_hash = env.deployer().upload_contract_wasm(constructor::WASM);
+    let salt = BytesN::from_array(&env, &[0; 32]);
+    let args = vec![&env, 5_u32.into_val(&env), 42_i64.into_val(&env)];
+
+    assert_eq!(
+        deployer.try_deploy_v2(&owner, &wasm_hash, &salt, &args),
+        Err(Ok(DeployerError::WasmNotApproved.into()))
+    );
+
+    deployer.approve_wasm(&wasm_hash);
+    let contract = deployer.deploy_v2(&owner, &wasm_hash, &salt, &args);
+    assert_eq!(
+        env.auths()[0].1.function,
+        AuthorizedFunction::Contract((
+            deployer.address.clone(),
+            Symbol::new(&env, "deploy_v2"),
+            (owner.clone(), wasm_hash.clone(), salt, args).into_val(&env),
+        ))
+    );
+    let deployed = constructor::Client::new(&env, &contract);
+    assert_eq!(
+        deployed.get_data(&constructor::DataKey::Persistent(5)),
+        Some(42)
+    );
+
+    // The deployment is on record, so it can be registered as a release.
+    let version = String::from_str(&env, "1.0.0");
+    let release = deployer.register_release(&version, &wasm_hash, &contract);
+    assert_eq!(release.contract, contract);
+}
+
+#[test]
+fn test_label_salt() {
+    let env = Env::default();
+    let contract_id = env.register(PetalDeployer, ());
+    let deployer = PetalDeployerClient::new(&env, &contract_id);
+
+    let label = String::from_str(&env, "documents-prod");
+    let owner = Address::generate(&env);
+    assert_eq!(deployer.get_labeled(&owner, &label), None);
+    env.as_contract(&contract_id, || {
+        let expected = env
+            .crypto()
+            .sha256(&Bytes::from_slice(&env, b"documents-prod"))
+            .to_bytes();
+        assert_eq!(label_salt(&env, &label), expected);
+    });
+}
+
+#[test]
+fn test_labels_scoped_to_deployer() {
+    let env = Env::default();
+    let contract_id = env.register(PetalDeployer, ());
+    let deployer = PetalDeployerClient::new(&env, &contract_id);
+
+    let label = String::from_str(&env, "documents-prod");
+    let owner = Address::generate(&env);
+    let squatter = Address::generate(&env);
+    let owner_instance = Address::generate(&env);
+    let squatter_instance = Address::generate(&env);
+    let (first, second, again) = env.as_contract(&contract_id, || {
+        (
+            record_label(&env, &owner, &label, &owner_instance),
+            record_label(&env, &squatter, &label, &squatter_instance),
+            record_label(&env, &squatter, &label, &owner_instance),
+        )
+    });
+    assert_eq!(first, Ok(()));
+    assert_eq!(second, Ok(()));
+    assert_eq!(again, Err(DeployerError::LabelTaken));
+
+    // Reusing the label elsewhere never changes what the owner's resolves to.
+    assert_eq!(deployer.get_labeled(&owner, &label), Some(owner_instance));
+    assert_eq!(
+        deployer.get_labeled(&squatter, &label),
+        Some(squatter_instance)
+    );
+}
+
+#[test]
+fn test_release_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(PetalDeployer, ());
+    let deployer = PetalDeployerClient::new(&env, &contract_id);
+    deployer.init(&Address::generate(&env));
+    assert_eq!(deployer.latest_release(), None);
+
+    // Stands in for `deploy`, which needs the uploaded Wasm.
+    let deploy = |seed: u8| {
+        let wasm_hash = BytesN::from_array(&env, &[seed; 32]);
+        let contract = Address::generate(&env);
+        deployer.approve_wasm(&wasm_hash);
+        env.as_contract(&contract_id, || {
+            record_deployment(&env, &contract, &wasm_hash)
+        });
+        (wasm_hash, contract)
+    };
+
+    let (wasm_hash, contract) = deploy(1);
+    let v1 = String::from_str(&env, "1.9.0");
+    let first = deployer.register_release(&v1, &wasm_hash, &contract);
+
+    env.ledger().with_mut(|li| li.sequence_number = 10);
+    let (wasm_hash, contract) = deploy(2);
+    let v2 = String::from_str(&env, "1.10.0");
+    let second = deployer.register_release(&v2, &wasm_hash, &contract);
+
+    assert_eq!(second.deployed_at, 10);
+    assert_eq!(deployer.latest_release(), Some(second.clone()));
+    assert_eq!(deployer.get_release(&v1), Some(first));
+    assert_eq!(deployer.get_release(&String::from_str(&env, "2.0.0")), None);
+
+    // A patch to the older line registered afterwards isn't the latest.
+    let (wasm_hash, contract) = deploy(3);
+    let backport = String::from_str(&env, "1.9.1");
+    deployer.register_release(&backport, &wasm_hash, &contract);
+    assert_eq!(deployer.latest_release(), Some(second));
+    assert!(deployer.get_release(&backport).is_some());
+}
+
+#[test]
+fn test_parse_version() {
+    let env = Env::default();
+    let parse = |version: &str| parse_version(&String::from_str(&env, version));
+
+    assert_eq!(parse("1.10.0"), Ok((1, 10, 0)));
+    assert_eq!(parse("0.0.12"), Ok((0, 0, 12)));
+    for invalid in [
+        "",
+        "1",
+        "1.0",
+        "1.0.0.0",
+        "v1.0.0",
+        "1..0",
+        "1.0.0-rc1",
+        "99999999999.0.0",
+    ] {
+        assert_eq!(parse(invalid), Err(DeployerError::InvalidVersion));
+    }
+}
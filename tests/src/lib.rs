@@ -0,0 +1,6 @@
+#![no_std]
+
+//! Runs the deployer, token and documents contracts from their release wasm
+//! in a single `Env`. See the Makefile for building the artifacts.
+
+mod test;
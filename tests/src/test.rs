@@ -0,0 +1,110 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, vec, Address, BytesN, Env, IntoVal, String};
+
+mod deployer {
+    soroban_sdk::contractimport!(
+        file = "../deployer/target/wasm32v1-none/release/petal_deployer_contract.wasm"
+    );
+}
+
+mod token {
+    soroban_sdk::contractimport!(
+        file = "../token/target/wasm32v1-none/release/soroban_token_contract.wasm"
+    );
+}
+
+mod documents {
+    soroban_sdk::contractimport!(file = "../Rel/target/wasm32v1-none/release/petal_documents.wasm");
+}
+
+fn deploy_documents<'a>(e: &Env, admin: &Address) -> documents::Client<'a> {
+    let deployer = deployer::Client::new(e, &e.register(deployer::WASM, ()));
+    deployer.init(admin);
+    let wasm_hash = e.deployer().upload_contract_wasm(documents::WASM);
+    deployer.approve_wasm(&wasm_hash);
+    let constructor_args = vec![e, admin.into_val(e), Option::<Address>::None.into_val(e)];
+
+    let documents_id = deployer.deploy_v2(
+        admin,
+        &wasm_hash,
+        &BytesN::from_array(e, &[0; 32]),
+        &constructor_args,
+    );
+    documents::Client::new(e, &documents_id)
+}
+
+fn create_token<'a>(e: &Env, admin: &Address) -> token::Client<'a> {
+    token::Client::new(
+        e,
+        &e.register(
+            token::WASM,
+            (
+                admin,
+                7_u32,
+                String::from_str(e, "Petal"),
+                String::from_str(e, "PTL"),
+                String::from_str(e, "https://petal.example/"),
+                None::<i128>,
+            ),
+        ),
+    )
+}
+
+#[test]
+fn deploy_mint_and_sign() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let creator = Address::generate(&e);
+    let signer1 = Address::generate(&e);
+    let signer2 = Address::generate(&e);
+
+    let documents = deploy_documents(&e, &admin);
+    assert_eq!(documents.get_admin(), admin);
+
+    let token = create_token(&e, &admin);
+    token.mint_amount(&creator, &1000);
+    documents.set_fee_token(&token.address);
+    documents.set_creation_fee(&100);
+
+    let hash = String::from_str(&e, "hash1");
+    let token_id = documents.safe_mint(
+        &creator,
+        &1,
+        &String::from_str(&e, "ipfs://doc1"),
+        &vec![&e, signer1.clone(), signer2.clone()],
+        &hash,
+        &(e.ledger().timestamp() + 1000),
+        &vec![&e],
+    );
+    assert_eq!(token_id, 1);
+    assert_eq!(token.balance(&creator), 900);
+    assert_eq!(token.balance(&documents.address), 100);
+    assert_eq!(
+        documents.get_document_status(&token_id),
+        documents::DocumentStatus::Pending
+    );
+
+    let receipt = documents.sign_document(
+        &hash,
+        &signer1,
+        &documents::SignatureStatus::Signed,
+        &token_id,
+    );
+    assert_eq!(receipt.remaining_signers, 1);
+    assert!(!receipt.completed);
+
+    let receipt = documents.sign_document(
+        &hash,
+        &signer2,
+        &documents::SignatureStatus::Signed,
+        &token_id,
+    );
+    assert!(receipt.completed);
+    assert_eq!(
+        documents.get_document_status(&token_id),
+        documents::DocumentStatus::Completed
+    );
+}
@@ -1,19 +1,34 @@
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{panic_with_error, Address, Env};
 
-use crate::storage_types::DataKey;
+use crate::error::TokenError;
 
-pub fn has_administrator(e: &Env) -> bool {
-    let key = DataKey::Admin;
-    e.storage().instance().has(&key)
-}
+use petal_common::admin as shared;
+pub use petal_common::admin::{
+    clear_renounce_request, has_administrator, is_renounced, read_renounceable,
+    write_administrator, write_renounceable,
+};
 
 pub fn read_administrator(e: &Env) -> Address {
-    let key = DataKey::Admin;
-    e.storage().instance().get(&key).unwrap_or_else(|| panic!("Admin not found"))
-    // e.storage().instance().get(&key).unwrap()
+    shared::try_read_administrator(e)
+        .unwrap_or_else(|| panic_with_error!(e, TokenError::NotInitialized))
+}
+
+// The confirmation of a renounce request has to come in a later ledger.
+pub fn request_renounce(e: &Env) {
+    if !read_renounceable(e) {
+        panic_with_error!(e, TokenError::RenounceDisabled)
+    }
+    shared::write_renounce_request(e);
 }
 
-pub fn write_administrator(e: &Env, id: &Address) {
-    let key = DataKey::Admin;
-    e.storage().instance().set(&key, id);
+// A renounced token has no admin and can't be initialized again.
+pub fn clear_administrator(e: &Env) {
+    if !read_renounceable(e) {
+        panic_with_error!(e, TokenError::RenounceDisabled)
+    }
+    if !shared::is_renounce_confirmable(e) {
+        panic_with_error!(e, TokenError::RenounceNotRequested)
+    }
+    shared::clear_renounce_request(e);
+    shared::clear_administrator(e);
 }
@@ -1,10 +1,25 @@
-use crate::storage_types::{AllowanceDataKey, AllowanceValue, DataKey};
-use soroban_sdk::{Address, Env};
+use crate::error::TokenError;
+use crate::storage_types::{
+    AllowanceDataKey, AllowanceEntry, AllowanceValue, DataKey, BALANCE_BUMP_AMOUNT,
+    BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
 
+fn is_expired(e: &Env, allowance: &AllowanceValue) -> bool {
+    allowance.expiration_ledger < e.ledger().sequence()
+}
+
+// Expired entries are deleted as soon as they are read, so they stop showing
+// up in the spender list too.
 pub fn read_allowance(e: &Env, from: Address, spender: Address) -> AllowanceValue {
-    let key = DataKey::Allowance(AllowanceDataKey { from, spender });
+    let key = DataKey::Allowance(AllowanceDataKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
     if let Some(allowance) = e.storage().temporary().get::<_, AllowanceValue>(&key) {
-        if allowance.expiration_ledger < e.ledger().sequence() {
+        if is_expired(e, &allowance) {
+            e.storage().temporary().remove(&key);
+            remove_spender(e, from, spender);
             AllowanceValue {
                 amount: 0,
                 expiration_ledger: allowance.expiration_ledger,
@@ -33,26 +48,31 @@ pub fn write_allowance(
     };
 
     if amount > 0 && expiration_ledger < e.ledger().sequence() {
-        panic!("expiration_ledger is less than ledger seq when amount > 0")
+        panic_with_error!(e, TokenError::InvalidExpirationLedger)
+    }
+
+    if amount > 0 {
+        add_spender(e, from.clone(), spender.clone());
     }
 
     let key = DataKey::Allowance(AllowanceDataKey { from, spender });
     e.storage().temporary().set(&key.clone(), &allowance);
 
     if amount > 0 {
-        e.storage().temporary().bump(
-            &key,
-            expiration_ledger
-                .checked_sub(e.ledger().sequence())
-                .unwrap(),
-        )
+        let live_for = expiration_ledger
+            .checked_sub(e.ledger().sequence())
+            .unwrap();
+        e.storage().temporary().extend_ttl(&key, live_for, live_for)
     }
 }
 
 pub fn spend_allowance(e: &Env, from: Address, spender: Address, amount: i128) {
     let allowance = read_allowance(e, from.clone(), spender.clone());
+    if allowance.amount < amount && allowance.expiration_ledger != 0 && is_expired(e, &allowance) {
+        panic_with_error!(e, TokenError::AllowanceExpired);
+    }
     if allowance.amount < amount {
-        panic!("insufficient allowance");
+        panic_with_error!(e, TokenError::InsufficientAllowance);
     }
     write_allowance(
         e,
@@ -62,3 +82,114 @@ pub fn spend_allowance(e: &Env, from: Address, spender: Address, amount: i128) {
         allowance.expiration_ledger,
     );
 }
+
+pub fn read_spenders(e: &Env, from: Address) -> Vec<Address> {
+    let key = DataKey::AllowanceSpenders(from);
+    if let Some(spenders) = e.storage().persistent().get::<DataKey, Vec<Address>>(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        spenders
+    } else {
+        Vec::new(e)
+    }
+}
+
+fn write_spenders(e: &Env, from: Address, spenders: &Vec<Address>) {
+    let key = DataKey::AllowanceSpenders(from);
+    if spenders.is_empty() {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, spenders);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    }
+}
+
+fn add_spender(e: &Env, from: Address, spender: Address) {
+    let mut spenders = read_spenders(e, from.clone());
+    if !spenders.contains(&spender) {
+        spenders.push_back(spender);
+        write_spenders(e, from, &spenders);
+    }
+}
+
+fn remove_spender(e: &Env, from: Address, spender: Address) {
+    let mut spenders = read_spenders(e, from.clone());
+    if let Some(index) = spenders.first_index_of(&spender) {
+        spenders.remove(index);
+        write_spenders(e, from, &spenders);
+    }
+}
+
+// Drops up to `limit` spenders whose allowance expired or was spent, along
+// with their entries. Returns how many were removed.
+pub fn purge_expired_allowances(e: &Env, from: Address, limit: u32) -> u32 {
+    let spenders = read_spenders(e, from.clone());
+    let mut remaining = Vec::new(e);
+    let mut purged = 0;
+    for spender in spenders.iter() {
+        let key = DataKey::Allowance(AllowanceDataKey {
+            from: from.clone(),
+            spender: spender.clone(),
+        });
+        let allowance = e.storage().temporary().get::<_, AllowanceValue>(&key);
+        let stale = match &allowance {
+            Some(allowance) => allowance.amount == 0 || is_expired(e, allowance),
+            None => true,
+        };
+        if stale && purged < limit {
+            e.storage().temporary().remove(&key);
+            purged += 1;
+        } else {
+            remaining.push_back(spender);
+        }
+    }
+    if purged > 0 {
+        write_spenders(e, from, &remaining);
+    }
+    purged
+}
+
+pub fn read_active_allowances(
+    e: &Env,
+    from: Address,
+    page: u32,
+    limit: u32,
+) -> Vec<AllowanceEntry> {
+    let mut entries = Vec::new(e);
+    let mut skip = page.saturating_mul(limit);
+    for spender in read_spenders(e, from.clone()).iter() {
+        if entries.len() >= limit {
+            break;
+        }
+        let allowance = read_allowance(e, from.clone(), spender.clone());
+        if allowance.amount == 0 {
+            continue;
+        }
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+        entries.push_back(AllowanceEntry {
+            spender,
+            amount: allowance.amount,
+            expiration_ledger: allowance.expiration_ledger,
+        });
+    }
+    entries
+}
+
+pub fn revoke_allowances(e: &Env, from: Address) -> Vec<Address> {
+    let spenders = read_spenders(e, from.clone());
+    for spender in spenders.iter() {
+        let key = DataKey::Allowance(AllowanceDataKey {
+            from: from.clone(),
+            spender,
+        });
+        e.storage().temporary().remove(&key);
+    }
+    write_spenders(e, from, &Vec::new(e));
+    spenders
+}
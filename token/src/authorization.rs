@@ -0,0 +1,38 @@
+use crate::admin::{has_administrator, read_administrator};
+use crate::error::TokenError;
+use crate::nft::{is_operator, read_approval, read_owner};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+pub fn is_admin(e: &Env, addr: &Address) -> bool {
+    has_administrator(e) && read_administrator(e) == *addr
+}
+
+pub fn is_approved_or_owner(e: &Env, spender: &Address, token_id: u32, owner: &Address) -> bool {
+    if spender == owner {
+        return true;
+    }
+    if read_approval(e, token_id) == Some(spender.clone()) {
+        return true;
+    }
+    is_operator(e, owner.clone(), spender.clone())
+}
+
+/// Who may move a token: the admin, the current owner, the address approved
+/// for the token, or an operator of the owner. Unminted tokens can only be
+/// assigned by the admin.
+pub fn can_transfer(e: &Env, spender: &Address, token_id: u32) -> bool {
+    if is_admin(e, spender) {
+        return true;
+    }
+    match read_owner(e, token_id) {
+        Some(owner) => is_approved_or_owner(e, spender, token_id, &owner),
+        None => false,
+    }
+}
+
+pub fn require_can_transfer(e: &Env, spender: &Address, token_id: u32) {
+    spender.require_auth();
+    if !can_transfer(e, spender, token_id) {
+        panic_with_error!(e, TokenError::NotAdminOwnerOrApproved);
+    }
+}
@@ -1,10 +1,15 @@
-use crate::storage_types::{DataKey, BALANCE_BUMP_AMOUNT};
-use soroban_sdk::{Address, Env};
+use crate::error::TokenError;
+use crate::storage_types::{
+    Checkpoint, CheckpointKey, DataKey, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, Address, Env};
 
 pub fn read_balance(e: &Env, addr: Address) -> i128 {
     let key = DataKey::Balance(addr);
     if let Some(balance) = e.storage().persistent().get::<DataKey, i128>(&key) {
-        e.storage().persistent().bump(&key, BALANCE_BUMP_AMOUNT);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
         balance
     } else {
         0
@@ -12,40 +17,129 @@ pub fn read_balance(e: &Env, addr: Address) -> i128 {
 }
 
 fn write_balance(e: &Env, addr: Address, amount: i128) {
-    let key = DataKey::Balance(addr);
+    let key = DataKey::Balance(addr.clone());
     e.storage().persistent().set(&key, &amount);
-    e.storage().persistent().bump(&key, BALANCE_BUMP_AMOUNT);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    if checkpoints_enabled(e, addr.clone()) {
+        write_checkpoint(e, addr, amount);
+    }
 }
 
-pub fn receive_balance(e: &Env, addr: Address, amount: i128) {
+pub fn receive_balance(e: &Env, addr: Address, amount: i128) -> Result<(), TokenError> {
     let balance = read_balance(e, addr.clone());
     if !is_authorized(e, addr.clone()) {
-        panic!("can't receive when deauthorized");
+        return Err(TokenError::ReceiverDeauthorized);
     }
-    write_balance(e, addr, balance + amount);
+    let balance = balance
+        .checked_add(amount)
+        .ok_or(TokenError::BalanceOverflow)?;
+    write_balance(e, addr, balance);
+    Ok(())
 }
 
-pub fn spend_balance(e: &Env, addr: Address, amount: i128) {
+pub fn spend_balance(e: &Env, addr: Address, amount: i128) -> Result<(), TokenError> {
     let balance = read_balance(e, addr.clone());
     if !is_authorized(e, addr.clone()) {
-        panic!("can't spend when deauthorized");
+        return Err(TokenError::SpenderDeauthorized);
     }
     if balance < amount {
-        panic!("insufficient balance");
+        return Err(TokenError::InsufficientBalance);
     }
-    write_balance(e, addr, balance - amount);
+    let balance = balance
+        .checked_sub(amount)
+        .ok_or(TokenError::BalanceOverflow)?;
+    write_balance(e, addr, balance);
+    Ok(())
 }
 
 pub fn is_authorized(e: &Env, addr: Address) -> bool {
     let key = DataKey::State(addr);
-    if let Some(state) = e.storage().persistent().get::<DataKey, bool>(&key) {
-        state
-    } else {
-        true
-    }
+    e.storage()
+        .persistent()
+        .get::<DataKey, bool>(&key)
+        .unwrap_or(true)
 }
 
 pub fn write_authorization(e: &Env, addr: Address, is_authorized: bool) {
     let key = DataKey::State(addr);
     e.storage().persistent().set(&key, &is_authorized);
 }
+
+// Balance history is opt-in per address, since every balance change then
+// costs an extra persistent entry.
+pub fn checkpoints_enabled(e: &Env, addr: Address) -> bool {
+    let key = DataKey::CheckpointCount(addr);
+    e.storage().persistent().has(&key)
+}
+
+pub fn enable_checkpoints(e: &Env, addr: Address) {
+    if checkpoints_enabled(e, addr.clone()) {
+        return;
+    }
+    let balance = read_balance(e, addr.clone());
+    write_checkpoint(e, addr, balance);
+}
+
+fn read_checkpoint_count(e: &Env, addr: Address) -> u32 {
+    let key = DataKey::CheckpointCount(addr);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+fn read_checkpoint(e: &Env, addr: Address, index: u32) -> Checkpoint {
+    let key = DataKey::Checkpoint(CheckpointKey { addr, index });
+    e.storage().persistent().get(&key).unwrap()
+}
+
+// Several changes within one ledger collapse into a single checkpoint.
+fn write_checkpoint(e: &Env, addr: Address, balance: i128) {
+    let ledger = e.ledger().sequence();
+    let count = read_checkpoint_count(e, addr.clone());
+    let index = if count > 0 && read_checkpoint(e, addr.clone(), count - 1).ledger == ledger {
+        count - 1
+    } else {
+        count
+    };
+
+    let key = DataKey::Checkpoint(CheckpointKey {
+        addr: addr.clone(),
+        index,
+    });
+    e.storage()
+        .persistent()
+        .set(&key, &Checkpoint { ledger, balance });
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+
+    let key = DataKey::CheckpointCount(addr);
+    e.storage().persistent().set(&key, &(index + 1));
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Balance at the end of `ledger`, found by binary search over the checkpoints.
+// Ledgers before the first checkpoint report zero.
+pub fn read_balance_at(e: &Env, addr: Address, ledger: u32) -> i128 {
+    if !checkpoints_enabled(e, addr.clone()) {
+        panic_with_error!(e, TokenError::CheckpointsDisabled);
+    }
+
+    let mut low = 0;
+    let mut high = read_checkpoint_count(e, addr.clone());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if read_checkpoint(e, addr.clone(), mid).ledger <= ledger {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    if low == 0 {
+        0
+    } else {
+        read_checkpoint(e, addr, low - 1).balance
+    }
+}
@@ -1,32 +1,101 @@
-#![no_std]
-
-use crate::admin::{has_administrator, read_administrator, write_administrator};
-use crate::allowance::{read_allowance, spend_allowance, write_allowance};
+use crate::admin::{
+    clear_administrator, clear_renounce_request, has_administrator, is_renounced,
+    read_administrator, read_renounceable, request_renounce, write_administrator,
+    write_renounceable,
+};
+use crate::allowance::{
+    purge_expired_allowances, read_active_allowances, read_allowance, revoke_allowances,
+    spend_allowance, write_allowance,
+};
+use crate::authorization::{is_admin, is_approved_or_owner, require_can_transfer};
 use crate::balance::{is_authorized, write_authorization};
-use crate::balance::{read_balance, receive_balance, spend_balance};
+use crate::balance::{
+    enable_checkpoints, read_balance, read_balance_at, receive_balance, spend_balance,
+};
 use crate::custom_token_metadata::CustomTokenMetadata;
-use crate::erc_functions::{exists, owner_of};
+use crate::error::TokenError;
 use crate::event;
-use crate::metadata::{read_decimal, read_name, read_symbol, write_metadata};
+use crate::freeze::{check_not_frozen, is_frozen, write_frozen};
+use crate::nft::{
+    add_to_index, decrement_owned_count, increment_owned_count, is_operator, read_approval,
+    read_owner, read_token_ids, read_token_uri, read_transfer_lock, remove_approval,
+    remove_from_index, remove_owner, remove_token_uri, remove_transfer_lock, write_approval,
+    write_operator, write_owner, write_token_uri, write_transfer_lock,
+};
+use crate::metadata::{
+    read_base_uri, read_decimal, read_metadata, read_name, read_symbol, write_base_uri,
+    write_metadata,
+};
+use crate::permit::{domain_separator, read_permit_nonce, verify_permit};
+use crate::royalty::{
+    remove_token_royalty, royalty_amount, write_default_royalty, write_token_royalty,
+};
 use crate::storage_types::{
-    INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK, INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+    AllowanceEntry, INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK, INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
 };
+use crate::supply::{decrease_supply, increase_supply, read_total_supply, write_max_supply};
+use crate::upgrade::{migrate_if_needed, write_storage_version, CONTRACT_VERSION};
+use petal_common::uri::concat_token_uri;
 use soroban_sdk::{
-    contract, contractimpl, contracttype, log, symbol_short, Address, BytesN, Env, Map, String,
-    Symbol, Vec,
+    contract, contractimpl, log, panic_with_error, symbol_short, Address, BytesN,
+    Env, Map, String, Symbol, Vec,
 };
 
+/// Fungible token interface. The function names, argument order and event
+/// layouts follow SEP-41 so that wallets can treat this contract like any
+/// other Soroban token.
 pub trait TokenTrait {
-    fn initialize(e: Env, admin: Address, token_id: u32);
+    /// Kept for callers written against the old two-step deploy. New
+    /// instances are set up by `__constructor`, so this always panics with
+    /// `AlreadyInitialized` on them.
+    fn initialize(
+        e: Env,
+        admin: Address,
+        decimal: u32,
+        name: String,
+        symbol: String,
+        token_uri: String,
+        max_supply: Option<i128>,
+    );
+
+    fn mint_amount(e: Env, to: Address, amount: i128);
+
+    fn total_supply(e: Env) -> i128;
 
     fn allowance(e: Env, from: Address, spender: Address) -> i128;
 
     fn approve(e: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32);
 
+    #[allow(clippy::too_many_arguments)]
+    fn permit(
+        e: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        deadline: u64,
+        public_key: BytesN<32>,
+        signature: BytesN<64>,
+    );
+
+    fn permit_nonce(e: Env, owner: Address) -> u32;
+
+    fn get_domain_separator(e: Env) -> BytesN<32>;
+
+    fn allowances_of(e: Env, from: Address, page: u32, limit: u32) -> Vec<AllowanceEntry>;
+
+    fn revoke_all_allowances(e: Env, from: Address);
+
+    fn purge_expired_allowances(e: Env, owner: Address, limit: u32) -> u32;
+
     fn balance(e: Env, id: Address) -> i128;
 
     fn spendable_balance(e: Env, id: Address) -> i128;
 
+    fn enable_checkpoints(e: Env, id: Address);
+
+    fn balance_at(e: Env, id: Address, ledger_seq: u32) -> i128;
+
     fn authorized(e: Env, id: Address) -> bool;
 
     fn transfer(e: Env, from: Address, to: Address, amount: i128);
@@ -41,79 +110,213 @@ pub trait TokenTrait {
 
     fn set_authorized(e: Env, id: Address, authorize: bool);
 
-    fn mint(e: Env, token_id: u32, to: Address);
+    fn freeze(e: Env, id: Address);
+
+    fn unfreeze(e: Env, id: Address);
+
+    fn is_frozen(e: Env, id: Address) -> bool;
 
     fn set_admin(e: Env, new_admin: Address);
 
     fn get_admin(e: Env) -> Address;
 
+    fn has_admin(e: Env) -> bool;
+
+    /// Renouncing has to be enabled by the admin before `renounce_admin`
+    /// can drop the admin for good.
+    fn set_admin_renounceable(e: Env, enabled: bool);
+
+    fn is_admin_renounceable(e: Env) -> bool;
+
+    /// First step of renouncing; `renounce_admin` confirms it in a later
+    /// ledger.
+    fn request_renounce_admin(e: Env);
+
+    fn cancel_renounce_admin(e: Env);
+
+    fn renounce_admin(e: Env);
+
+    fn upgrade(e: Env, new_wasm_hash: BytesN<32>);
+
+    fn version(e: Env) -> u32;
+
+    fn get_event_schema_version(e: Env) -> u32;
+
     fn decimals(e: Env) -> u32;
 
     fn name(e: Env) -> String;
 
     fn symbol(e: Env) -> String;
 
-    fn get_owners(e: Env) -> Map<u32, Address>;
+    fn metadata(e: Env) -> CustomTokenMetadata;
 
-    fn set_owners(e: Env, token_id: u32, owner: Address);
+    /// Whether an optional extension is available, so tooling can check
+    /// before calling it. Known interfaces are `nft_transfer`, `royalties`,
+    /// `escrow` and `ordered_signing`; the last two belong to the documents
+    /// contract.
+    fn supports(e: Env, interface: Symbol) -> bool;
+}
 
-    fn set_token_uri(e: Env, token_id: u32, token_uri: String);
+/// ERC721-style extensions exposed next to the SEP-41 interface.
+pub trait NonFungibleTokenTrait {
+    /// Only the admin can mint. When PetalDocuments delegates minting here,
+    /// it has to be the admin so its fees and minter allowlist apply.
+    fn mint(e: Env, token_id: u32, to: Address);
 
     fn require_minted(e: Env, token_id: u32) -> bool;
+
+    /// `None` for tokens never minted or already burned.
+    fn owner_of(e: Env, token_id: u32) -> Option<Address>;
+
+    fn get_owners(e: Env) -> Map<u32, Address>;
+
+    fn set_owners(e: Env, spender: Address, token_id: u32, owner: Address);
+
+    /// Only the token's owner or the admin can change its URI.
+    fn set_token_uri(e: Env, caller: Address, token_id: u32, token_uri: String);
+
+    fn token_uri(e: Env, token_id: u32) -> String;
+
+    fn set_base_uri(e: Env, base_uri: String);
+
+    fn burn_nft(e: Env, spender: Address, token_id: u32);
+
+    /// Lets `approved` transfer or burn `token_id` until the token changes
+    /// hands; `None` clears the approval. Callable by the owner or one of
+    /// their operators.
+    fn approve_nft(e: Env, caller: Address, approved: Option<Address>, token_id: u32);
+
+    fn get_approved(e: Env, token_id: u32) -> Option<Address>;
+
+    /// Lets `operator` transfer or burn every token `owner` holds.
+    fn set_approval_for_all(e: Env, owner: Address, operator: Address, approved: bool);
+
+    fn is_approved_for_all(e: Env, owner: Address, operator: Address) -> bool;
+
+    fn set_default_royalty(e: Env, receiver: Address, bps: u32);
+
+    fn set_token_royalty(e: Env, token_id: u32, receiver: Address, bps: u32);
+
+    fn royalty_info(e: Env, token_id: u32, sale_price: i128) -> (Address, i128);
+
+    /// Keeps `token_id` with its owner until ledger `until_ledger`, e.g. for
+    /// a cooling-off period after a document completes. Only the admin can
+    /// still move it in the meantime.
+    fn lock_transfer(e: Env, token_id: u32, until_ledger: u32);
+
+    fn transferable_at(e: Env, token_id: u32) -> u32;
 }
 
-fn check_nonnegative_amount(amount: i128) {
+fn check_nonnegative_amount(e: &Env, amount: i128) {
     if amount < 0 {
-        panic!("negative amount is not allowed: {}", amount)
+        panic_with_error!(e, TokenError::NegativeAmount)
     }
 }
 
-const OWNERS: Symbol = symbol_short!("OWNERS");
-const URIS: Symbol = symbol_short!("URIS");
-const APPROVALS: Symbol = symbol_short!("approvals");
-const OWNED_TOKEN_COUNT: Symbol = symbol_short!("tCount");
-const OPERATOR_APPROVAL: Symbol = symbol_short!("opApprov");
+fn init_token(
+    e: &Env,
+    admin: Address,
+    decimal: u32,
+    name: String,
+    symbol: String,
+    token_uri: String,
+    max_supply: Option<i128>,
+) {
+    if has_administrator(e) || is_renounced(e) {
+        panic_with_error!(e, TokenError::AlreadyInitialized)
+    }
+
+    write_administrator(e, &admin);
+    write_storage_version(e, CONTRACT_VERSION);
+    event::schema(e);
+
+    let admin = read_administrator(e);
+
+    log!(e, "Admin {}", admin);
+
+    if decimal > u8::MAX.into() {
+        panic_with_error!(e, TokenError::InvalidDecimal);
+    }
+
+    write_metadata(
+        e,
+        CustomTokenMetadata {
+            decimal,
+            name,
+            symbol,
+            token_uri,
+        },
+    );
+
+    if let Some(max_supply) = max_supply {
+        check_nonnegative_amount(e, max_supply);
+        write_max_supply(e, max_supply);
+    }
+
+    log!(e, "Done Initializing");
+}
 
 #[contract]
 pub struct Token;
 
 #[contractimpl]
-impl TokenTrait for Token {
-    fn initialize(e: Env, admin: Address, token_id: u32) {
-        if has_administrator(&e) {
-            panic!("already initialized")
-        }
+impl Token {
+    /// Sets up the instance at deploy time, so no other transaction can
+    /// initialize it first.
+    pub fn __constructor(
+        e: Env,
+        admin: Address,
+        decimal: u32,
+        name: String,
+        symbol: String,
+        token_uri: String,
+        max_supply: Option<i128>,
+    ) {
+        init_token(&e, admin, decimal, name, symbol, token_uri, max_supply);
+    }
+}
 
-        write_administrator(&e, &admin);
+#[contractimpl]
+impl TokenTrait for Token {
+    fn initialize(
+        e: Env,
+        admin: Address,
+        decimal: u32,
+        name: String,
+        symbol: String,
+        token_uri: String,
+        max_supply: Option<i128>,
+    ) {
+        init_token(&e, admin, decimal, name, symbol, token_uri, max_supply);
+    }
 
+    fn mint_amount(e: Env, to: Address, amount: i128) {
+        migrate_if_needed(&e);
+        check_nonnegative_amount(&e, amount);
         let admin = read_administrator(&e);
+        admin.require_auth();
 
-        log!(&e, "Admin {}", admin);
-
-        let mut owners: Map<u32, Address> =
-            e.storage().instance().get(&OWNERS).unwrap_or(Map::new(&e));
-        owners.set(token_id, admin);
-        e.storage().instance().set(&OWNERS, &owners);
-
-        log!(&e, "Done Initializing");
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
 
-        // if decimal > u8::MAX.into() {
-        //     panic!("Decimal must fit in a u8");
-        // }
+        increase_supply(&e, amount);
+        receive_balance(&e, to.clone(), amount)
+            .unwrap_or_else(|error| panic_with_error!(&e, error));
+        event::mint(&e, admin, to, amount);
+    }
 
-        // write_metadata(
-        //     &e,
-        //     CustomTokenMetadata {
-        //         decimal,
-        //         name,
-        //         symbol,
-        //         token_uri
-        //     },
-        // )
+    fn total_supply(e: Env) -> i128 {
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+        read_total_supply(&e)
     }
 
     fn allowance(e: Env, from: Address, spender: Address) -> i128 {
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
@@ -121,11 +324,12 @@ impl TokenTrait for Token {
     }
 
     fn approve(e: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        migrate_if_needed(&e);
         from.require_auth();
 
-        check_nonnegative_amount(amount);
+        check_nonnegative_amount(&e, amount);
 
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
@@ -134,16 +338,113 @@ impl TokenTrait for Token {
         event::approve(&e, from, spender, amount, expiration_ledger);
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn permit(
+        e: Env,
+        owner: Address,
+        spender: Address,
+        amount: i128,
+        expiration_ledger: u32,
+        deadline: u64,
+        public_key: BytesN<32>,
+        signature: BytesN<64>,
+    ) {
+        migrate_if_needed(&e);
+        check_nonnegative_amount(&e, amount);
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        verify_permit(
+            &e,
+            owner.clone(),
+            spender.clone(),
+            amount,
+            expiration_ledger,
+            deadline,
+            public_key,
+            signature,
+        );
+
+        write_allowance(&e, owner.clone(), spender.clone(), amount, expiration_ledger);
+        event::approve(&e, owner, spender, amount, expiration_ledger);
+    }
+
+    fn permit_nonce(e: Env, owner: Address) -> u32 {
+        read_permit_nonce(&e, owner)
+    }
+
+    fn get_domain_separator(e: Env) -> BytesN<32> {
+        domain_separator(&e)
+    }
+
+    fn allowances_of(e: Env, from: Address, page: u32, limit: u32) -> Vec<AllowanceEntry> {
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+        read_active_allowances(&e, from, page, limit)
+    }
+
+    fn revoke_all_allowances(e: Env, from: Address) {
+        migrate_if_needed(&e);
+        from.require_auth();
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        let expiration_ledger = e.ledger().sequence();
+        for spender in revoke_allowances(&e, from.clone()).iter() {
+            event::approve(&e, from.clone(), spender, 0, expiration_ledger);
+        }
+    }
+
+    // Open to anyone: it only drops allowances that can no longer be spent.
+    fn purge_expired_allowances(e: Env, owner: Address, limit: u32) -> u32 {
+        migrate_if_needed(&e);
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        purge_expired_allowances(&e, owner, limit)
+    }
+
     fn balance(e: Env, id: Address) -> i128 {
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
         read_balance(&e, id)
     }
 
+    fn enable_checkpoints(e: Env, id: Address) {
+        migrate_if_needed(&e);
+        id.require_auth();
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        enable_checkpoints(&e, id);
+    }
+
+    fn balance_at(e: Env, id: Address, ledger_seq: u32) -> i128 {
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+        read_balance_at(&e, id, ledger_seq)
+    }
+
     fn spendable_balance(e: Env, id: Address) -> i128 {
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
@@ -151,7 +452,7 @@ impl TokenTrait for Token {
     }
 
     fn authorized(e: Env, id: Address) -> bool {
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
@@ -159,84 +460,106 @@ impl TokenTrait for Token {
     }
 
     fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        migrate_if_needed(&e);
         from.require_auth();
 
-        check_nonnegative_amount(amount);
+        check_nonnegative_amount(&e, amount);
 
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
 
-        spend_balance(&e, from.clone(), amount);
-        receive_balance(&e, to.clone(), amount);
+        check_not_frozen(&e, &from);
+        check_not_frozen(&e, &to);
+
+        spend_balance(&e, from.clone(), amount)
+            .unwrap_or_else(|error| panic_with_error!(&e, error));
+        receive_balance(&e, to.clone(), amount)
+            .unwrap_or_else(|error| panic_with_error!(&e, error));
         event::transfer(&e, from, to, amount);
     }
 
     fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        migrate_if_needed(&e);
         spender.require_auth();
 
-        check_nonnegative_amount(amount);
+        check_nonnegative_amount(&e, amount);
 
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
 
+        check_not_frozen(&e, &from);
+        check_not_frozen(&e, &to);
+
         spend_allowance(&e, from.clone(), spender, amount);
-        spend_balance(&e, from.clone(), amount);
-        receive_balance(&e, to.clone(), amount);
+        spend_balance(&e, from.clone(), amount)
+            .unwrap_or_else(|error| panic_with_error!(&e, error));
+        receive_balance(&e, to.clone(), amount)
+            .unwrap_or_else(|error| panic_with_error!(&e, error));
         event::transfer(&e, from, to, amount)
     }
 
     fn burn(e: Env, from: Address, amount: i128) {
+        migrate_if_needed(&e);
         from.require_auth();
 
-        check_nonnegative_amount(amount);
+        check_nonnegative_amount(&e, amount);
 
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
 
-        spend_balance(&e, from.clone(), amount);
+        spend_balance(&e, from.clone(), amount)
+            .unwrap_or_else(|error| panic_with_error!(&e, error));
+        decrease_supply(&e, amount);
         event::burn(&e, from, amount);
     }
 
     fn burn_from(e: Env, spender: Address, from: Address, amount: i128) {
+        migrate_if_needed(&e);
         spender.require_auth();
 
-        check_nonnegative_amount(amount);
+        check_nonnegative_amount(&e, amount);
 
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
 
         spend_allowance(&e, from.clone(), spender, amount);
-        spend_balance(&e, from.clone(), amount);
+        spend_balance(&e, from.clone(), amount)
+            .unwrap_or_else(|error| panic_with_error!(&e, error));
+        decrease_supply(&e, amount);
         event::burn(&e, from, amount)
     }
 
     fn clawback(e: Env, from: Address, amount: i128) {
-        check_nonnegative_amount(amount);
+        migrate_if_needed(&e);
+        check_nonnegative_amount(&e, amount);
         let admin = read_administrator(&e);
         admin.require_auth();
 
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
 
-        spend_balance(&e, from.clone(), amount);
+        spend_balance(&e, from.clone(), amount)
+            .unwrap_or_else(|error| panic_with_error!(&e, error));
+        decrease_supply(&e, amount);
         event::clawback(&e, admin, from, amount);
     }
 
     fn set_authorized(e: Env, id: Address, authorize: bool) {
+        migrate_if_needed(&e);
         let admin = read_administrator(&e);
         admin.require_auth();
 
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
@@ -245,47 +568,190 @@ impl TokenTrait for Token {
         event::set_authorized(&e, admin, id, authorize);
     }
 
-    fn require_minted(e: Env, token_id: u32) -> bool {
-        let owners: Map<u32, Address> = e.storage().instance().get(&OWNERS).unwrap_or(Map::new(&e));
-        if exists(&e, token_id, &owners) == true {
-            return true;
-        }
-        return false;
+    fn freeze(e: Env, id: Address) {
+        migrate_if_needed(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        write_frozen(&e, id.clone(), true);
+        event::freeze(&e, admin, id);
+    }
+
+    fn unfreeze(e: Env, id: Address) {
+        migrate_if_needed(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        write_frozen(&e, id.clone(), false);
+        event::unfreeze(&e, admin, id);
+    }
+
+    fn is_frozen(e: Env, id: Address) -> bool {
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+        is_frozen(&e, id)
+    }
+
+    fn set_admin(e: Env, new_admin: Address) {
+        migrate_if_needed(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        write_administrator(&e, &new_admin);
+        event::set_admin(&e, admin, new_admin);
+    }
+
+    fn get_admin(e: Env) -> Address {
+        read_administrator(&e)
+    }
+
+    fn has_admin(e: Env) -> bool {
+        has_administrator(&e)
+    }
+
+    fn set_admin_renounceable(e: Env, enabled: bool) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        write_renounceable(&e, enabled);
+    }
+
+    fn is_admin_renounceable(e: Env) -> bool {
+        read_renounceable(&e)
+    }
+
+    fn request_renounce_admin(e: Env) {
+        migrate_if_needed(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        request_renounce(&e);
+        event::renounce_requested(&e, admin);
+    }
+
+    fn cancel_renounce_admin(e: Env) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        clear_renounce_request(&e);
+        event::renounce_cancelled(&e, admin);
+    }
+
+    fn renounce_admin(e: Env) {
+        migrate_if_needed(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        clear_administrator(&e);
+        event::renounce_admin(&e, admin);
+    }
+
+    fn upgrade(e: Env, new_wasm_hash: BytesN<32>) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        e.deployer().update_current_contract_wasm(new_wasm_hash.clone());
+        event::upgrade(&e, admin, new_wasm_hash);
     }
 
+    fn version(_e: Env) -> u32 {
+        CONTRACT_VERSION
+    }
+
+    fn get_event_schema_version(_e: Env) -> u32 {
+        event::EVENT_SCHEMA_VERSION
+    }
+
+    fn decimals(e: Env) -> u32 {
+        read_decimal(&e)
+    }
+
+    fn name(e: Env) -> String {
+        read_name(&e)
+    }
+
+    fn symbol(e: Env) -> String {
+        read_symbol(&e)
+    }
+
+    fn metadata(e: Env) -> CustomTokenMetadata {
+        read_metadata(&e)
+    }
+
+    fn supports(e: Env, interface: Symbol) -> bool {
+        interface == Symbol::new(&e, "nft_transfer") || interface == symbol_short!("royalties")
+    }
+}
+
+#[contractimpl]
+impl NonFungibleTokenTrait for Token {
     fn mint(e: Env, token_id: u32, to: Address) {
+        migrate_if_needed(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
         // SOL: require(to != address(0), "ERC721: mint to the zero address");
         // CHECK IF ADDRESS IS NUL ADDRESS in soroban
 
         // New Token id should be incremented by 1 and not injected as param.
 
-        let mut owners: Map<u32, Address> =
-            e.storage().instance().get(&OWNERS).unwrap_or(Map::new(&e));
-        log!(&e, "Owners {}", owners);
-
-        if exists(&e, token_id, &owners) == true {
-            panic!("Token already minted!");
+        if read_owner(&e, token_id).is_some() {
+            panic_with_error!(&e, TokenError::TokenAlreadyMinted);
         }
         log!(&e, "Token does not exists {}", token_id);
 
         let cloned_to = to.clone();
 
-        owners.set(token_id, to);
-        log!(&e, "Owners set locally {}", owners);
+        write_owner(&e, token_id, &to);
+        add_to_index(&e, token_id);
+        log!(&e, "Owner set {}", to);
 
-        e.storage().instance().set(&OWNERS, &owners);
-        log!(&e, "Owners set instance {}", owners);
+        increment_owned_count(&e, &cloned_to);
 
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
-        event::mint(&e, &cloned_to, token_id);
+        event::mint_nft(&e, cloned_to, token_id);
+    }
+
+    fn require_minted(e: Env, token_id: u32) -> bool {
+        read_owner(&e, token_id).is_some()
+    }
+
+    fn owner_of(e: Env, token_id: u32) -> Option<Address> {
+        read_owner(&e, token_id)
     }
 
     fn get_owners(e: Env) -> Map<u32, Address> {
-        let owners: Map<u32, Address> = e.storage().instance().get(&OWNERS).unwrap_or(Map::new(&e));
-        e.storage().instance().bump(
+        let mut owners: Map<u32, Address> = Map::new(&e);
+        for token_id in read_token_ids(&e).iter() {
+            if let Some(owner) = read_owner(&e, token_id) {
+                owners.set(token_id, owner);
+            }
+        }
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
@@ -293,59 +759,218 @@ impl TokenTrait for Token {
         owners
     }
 
-    fn set_owners(e: Env, token_id: u32, owner: Address) {
-        let mut owners: Map<u32, Address> =
-            e.storage().instance().get(&OWNERS).unwrap_or(Map::new(&e));
-        owners.set(token_id, owner);
-        e.storage().instance().set(&OWNERS, &owners);
+    fn set_owners(e: Env, spender: Address, token_id: u32, owner: Address) {
+        migrate_if_needed(&e);
+        require_can_transfer(&e, &spender, token_id);
+        if !is_admin(&e, &spender) && e.ledger().sequence() < read_transfer_lock(&e, token_id) {
+            panic_with_error!(&e, TokenError::TransferLocked);
+        }
+
+        let previous_owner = read_owner(&e, token_id);
+        if let Some(previous_owner) = &previous_owner {
+            check_not_frozen(&e, previous_owner);
+        }
+        check_not_frozen(&e, &owner);
+        match &previous_owner {
+            Some(previous_owner) => decrement_owned_count(&e, previous_owner),
+            None => add_to_index(&e, token_id),
+        }
+        increment_owned_count(&e, &owner);
+        write_owner(&e, token_id, &owner);
+        // An approval is granted by the owner, so it doesn't survive a change
+        // of ownership.
+        remove_approval(&e, token_id);
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+        event::set_owner(&e, spender, token_id, previous_owner, owner);
     }
 
-    fn set_admin(e: Env, new_admin: Address) {
+    fn set_token_uri(e: Env, caller: Address, token_id: u32, token_uri: String) {
+        migrate_if_needed(&e);
+        caller.require_auth();
+        let owner = match read_owner(&e, token_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, TokenError::NonexistentToken),
+        };
+        if caller != owner && !is_admin(&e, &caller) {
+            panic_with_error!(&e, TokenError::NotAdminOrOwner);
+        }
+
+        let previous_uri = read_token_uri(&e, token_id);
+        write_token_uri(&e, token_id, &token_uri);
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+        event::set_token_uri(&e, token_id, previous_uri, token_uri);
+    }
+
+    fn token_uri(e: Env, token_id: u32) -> String {
+        if read_owner(&e, token_id).is_none() {
+            panic_with_error!(&e, TokenError::NonexistentToken);
+        }
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        match read_token_uri(&e, token_id) {
+            Some(uri) => uri,
+            None => {
+                let base_uri = read_base_uri(&e);
+                if base_uri.is_empty() {
+                    return base_uri;
+                }
+                match concat_token_uri(&e, &base_uri, token_id) {
+                    Some(uri) => uri,
+                    None => panic_with_error!(&e, TokenError::BaseUriTooLong),
+                }
+            }
+        }
+    }
+
+    fn set_base_uri(e: Env, base_uri: String) {
+        migrate_if_needed(&e);
         let admin = read_administrator(&e);
         admin.require_auth();
 
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
 
-        write_administrator(&e, &new_admin);
-        event::set_admin(&e, admin, new_admin);
+        write_base_uri(&e, base_uri);
     }
 
-    fn get_admin(e: Env) -> Address {
+    fn lock_transfer(e: Env, token_id: u32, until_ledger: u32) {
+        migrate_if_needed(&e);
         let admin = read_administrator(&e);
-        admin
-    }
+        admin.require_auth();
+        if read_owner(&e, token_id).is_none() {
+            panic_with_error!(&e, TokenError::NonexistentToken);
+        }
 
-    fn decimals(e: Env) -> u32 {
-        read_decimal(&e)
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        write_transfer_lock(&e, token_id, until_ledger);
+        event::lock_transfer(&e, token_id, until_ledger);
     }
 
-    fn name(e: Env) -> String {
-        read_name(&e)
+    fn transferable_at(e: Env, token_id: u32) -> u32 {
+        read_transfer_lock(&e, token_id)
     }
 
-    fn symbol(e: Env) -> String {
-        read_symbol(&e)
+    fn burn_nft(e: Env, spender: Address, token_id: u32) {
+        migrate_if_needed(&e);
+        spender.require_auth();
+
+        let owner = match read_owner(&e, token_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, TokenError::NonexistentToken),
+        };
+        if !is_approved_or_owner(&e, &spender, token_id, &owner) {
+            panic_with_error!(&e, TokenError::NotOwnerOrApproved);
+        }
+
+        remove_owner(&e, token_id);
+        remove_token_uri(&e, token_id);
+        remove_approval(&e, token_id);
+        remove_from_index(&e, token_id);
+        remove_transfer_lock(&e, token_id);
+
+        decrement_owned_count(&e, &owner);
+        remove_token_royalty(&e, token_id);
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+        event::burn_nft(&e, owner, token_id);
     }
 
-    fn set_token_uri(e: Env, token_id: u32, token_uri: String) {
-        let owners: Map<u32, Address> = e.storage().instance().get(&OWNERS).unwrap_or(Map::new(&e));
+    fn approve_nft(e: Env, caller: Address, approved: Option<Address>, token_id: u32) {
+        migrate_if_needed(&e);
+        caller.require_auth();
+
+        let owner = match read_owner(&e, token_id) {
+            Some(owner) => owner,
+            None => panic_with_error!(&e, TokenError::NonexistentToken),
+        };
+        if caller != owner && !is_operator(&e, owner.clone(), caller) {
+            panic_with_error!(&e, TokenError::NotOwnerOrApproved);
+        }
 
-        if exists(&e, token_id, &owners) == false {
-            panic!("ERC721URIStorage: URI set of nonexistent token");
+        match &approved {
+            Some(approved) => write_approval(&e, token_id, approved),
+            None => remove_approval(&e, token_id),
         }
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+        event::approve_nft(&e, owner, token_id, approved);
+    }
+
+    fn get_approved(e: Env, token_id: u32) -> Option<Address> {
+        read_approval(&e, token_id)
+    }
+
+    fn set_approval_for_all(e: Env, owner: Address, operator: Address, approved: bool) {
+        migrate_if_needed(&e);
+        owner.require_auth();
+
+        write_operator(&e, owner.clone(), operator.clone(), approved);
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+        event::approval_for_all(&e, owner, operator, approved);
+    }
+
+    fn is_approved_for_all(e: Env, owner: Address, operator: Address) -> bool {
+        is_operator(&e, owner, operator)
+    }
 
-        let mut token_uris: Map<u32, String> =
-            e.storage().instance().get(&URIS).unwrap_or(Map::new(&e));
-        token_uris.set(token_id, token_uri);
+    fn set_default_royalty(e: Env, receiver: Address, bps: u32) {
+        migrate_if_needed(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
 
-        e.storage().instance().set(&URIS, &token_uris);
-        e.storage().instance().bump(
+        e.storage().instance().extend_ttl(
             INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
             INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
         );
+
+        write_default_royalty(&e, receiver, bps);
+    }
+
+    fn set_token_royalty(e: Env, token_id: u32, receiver: Address, bps: u32) {
+        migrate_if_needed(&e);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        if read_owner(&e, token_id).is_none() {
+            panic_with_error!(&e, TokenError::NonexistentToken);
+        }
+
+        e.storage().instance().extend_ttl(
+            INSTANCE_BUMP_AMOUNT_LOW_WATERMARK,
+            INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK,
+        );
+
+        write_token_royalty(&e, token_id, receiver, bps);
+    }
+
+    fn royalty_info(e: Env, token_id: u32, sale_price: i128) -> (Address, i128) {
+        check_nonnegative_amount(&e, sale_price);
+        royalty_amount(&e, token_id, sale_price)
     }
 }
 
@@ -392,6 +1017,7 @@ impl TokenTrait for Token {
 //     --network standalone \
 //     -- \
 //     set_owners \
+//     --spender GA3YIJVTHQIH3BXKQHUHAYHBZ7Z5NYPPWIXXT3OHVQO5YE3RKT5ASAFC \
 //     --token_id 4 \
 //     --owner GA3YIJVTHQIH3BXKQHUHAYHBZ7Z5NYPPWIXXT3OHVQO5YE3RKT5ASAFC
 
@@ -1,8 +1,7 @@
-#![no_std]
+use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Env, String};
 
-use soroban_sdk::{contracttype, symbol_short, unwrap::UnwrapOptimized, Env, String, Symbol};
-
-const METADATA_KEY: Symbol = symbol_short!("METADATA");
+use crate::storage_types::DataKey;
+use crate::upgrade::LEGACY_METADATA;
 
 #[derive(Clone)]
 #[contracttype]
@@ -24,15 +23,16 @@ impl CustomTokenUtils {
 
     #[inline(always)]
     pub fn set_metadata(&self, metadata: &CustomTokenMetadata) {
-        self.0.storage().persistent().set(&METADATA_KEY, metadata);
+        self.0.storage().persistent().set(&DataKey::Metadata, metadata);
     }
 
     #[inline(always)]
     pub fn get_metadata(&self) -> CustomTokenMetadata {
-        self.0
-            .storage()
-            .persistent()
-            .get(&METADATA_KEY)
+        // Reads can run before `migrate_if_needed` has moved the legacy entry.
+        let storage = self.0.storage().persistent();
+        storage
+            .get(&DataKey::Metadata)
+            .or_else(|| storage.get(&LEGACY_METADATA))
             .unwrap_optimized()
     }
 }
\ No newline at end of file
@@ -0,0 +1,36 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TokenError {
+    AllowanceExpired = 1,
+    NotInitialized = 2,
+    AlreadyInitialized = 3,
+    InvalidDecimal = 4,
+    NegativeAmount = 5,
+    InvalidExpirationLedger = 6,
+    InsufficientAllowance = 7,
+    InsufficientBalance = 8,
+    ReceiverDeauthorized = 9,
+    SpenderDeauthorized = 10,
+    AddressFrozen = 11,
+    CheckpointsDisabled = 12,
+    SupplyOverflow = 13,
+    MaxSupplyExceeded = 14,
+    TokenAlreadyMinted = 15,
+    NonexistentToken = 16,
+    NotAdminOwnerOrApproved = 17,
+    NotOwnerOrApproved = 18,
+    BaseUriTooLong = 19,
+    InvalidPermitOwner = 20,
+    PublicKeyMismatch = 21,
+    PermitExpired = 22,
+    RoyaltyTooHigh = 23,
+    RoyaltyOverflow = 24,
+    BalanceOverflow = 25,
+    RenounceDisabled = 26,
+    TransferLocked = 27,
+    NotAdminOrOwner = 28,
+    RenounceNotRequested = 29,
+}
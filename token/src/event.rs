@@ -1,36 +1,128 @@
-use soroban_sdk::{symbol_short, Address, Env, Symbol, U256};
+// `publish` is deprecated in favour of `#[contractevent]`, but the macro
+// derives its own topic layout. The layouts here are pinned by SEP-41 and
+// `EVENT_SCHEMA_VERSION`, so keep publishing them by hand.
+#![allow(deprecated)]
 
-pub(crate) fn approve(e: &Env, from: Address, to: Address, amount: i128, expiration_ledger: u32) {
-    let topics = (Symbol::new(e, "approve"), from, to);
-    e.events().publish(topics, (amount, expiration_ledger));
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, String, Symbol};
+
+pub(crate) use petal_common::event::{
+    approve, burn, clawback, mint, set_admin, set_authorized, transfer,
+};
+
+// Bumped whenever a topic layout or data struct below changes. This
+// contract's own events carry it as their last topic, so indexers can pick
+// the right decoder before reading the payload. The SEP-41 events keep the
+// exact topics and payloads the standard defines; their version comes from
+// the `schema` event published on initialization and after a migration.
+pub const EVENT_SCHEMA_VERSION: u32 = 2;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct NftData {
+    pub token_id: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct FreezeData {
+    pub frozen: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[contracttype]
+pub struct UpgradeData {
+    pub new_wasm_hash: BytesN<32>,
+}
+
+pub(crate) fn schema(e: &Env) {
+    let topics = (symbol_short!("schema"),);
+    e.events().publish(topics, EVENT_SCHEMA_VERSION);
+}
+
+pub(crate) fn mint_nft(e: &Env, to: Address, token_id: u32) {
+    let topics = (symbol_short!("mint_nft"), to, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, NftData { token_id });
+}
+
+// Payloads with an optional previous value are plain tuples, as `Option`
+// fields aren't convertible inside a `contracttype` struct.
+pub(crate) fn set_owner(
+    e: &Env,
+    spender: Address,
+    token_id: u32,
+    previous_owner: Option<Address>,
+    new_owner: Address,
+) {
+    let topics = (symbol_short!("set_owner"), spender, EVENT_SCHEMA_VERSION);
+    e.events()
+        .publish(topics, (token_id, previous_owner, new_owner));
+}
+
+pub(crate) fn set_token_uri(e: &Env, token_id: u32, previous_uri: Option<String>, new_uri: String) {
+    let topics = (Symbol::new(e, "set_token_uri"), EVENT_SCHEMA_VERSION);
+    e.events()
+        .publish(topics, (token_id, previous_uri, new_uri));
+}
+
+pub(crate) fn approve_nft(e: &Env, owner: Address, token_id: u32, approved: Option<Address>) {
+    let topics = (Symbol::new(e, "approve_nft"), owner, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, (token_id, approved));
+}
+
+pub(crate) fn approval_for_all(e: &Env, owner: Address, operator: Address, approved: bool) {
+    let topics = (
+        Symbol::new(e, "approval_for_all"),
+        owner,
+        operator,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, approved);
+}
+
+pub(crate) fn freeze(e: &Env, admin: Address, id: Address) {
+    let topics = (symbol_short!("freeze"), admin, id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, FreezeData { frozen: true });
+}
+
+pub(crate) fn unfreeze(e: &Env, admin: Address, id: Address) {
+    let topics = (symbol_short!("unfreeze"), admin, id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, FreezeData { frozen: false });
 }
 
-pub(crate) fn transfer(e: &Env, from: Address, to: Address, amount: i128) {
-    let topics = (symbol_short!("transfer"), from, to);
-    e.events().publish(topics, amount);
+pub(crate) fn renounce_admin(e: &Env, admin: Address) {
+    let topics = (symbol_short!("renounce"), admin, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, ());
 }
 
-pub(crate) fn mint(e: &Env, to: &Address, token_id: u32) {
-    let topics = (symbol_short!("mint"), token_id);
-    e.events().publish(topics, token_id);
+pub(crate) fn renounce_requested(e: &Env, admin: Address) {
+    let topics = (
+        Symbol::new(e, "renounce_requested"),
+        admin,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ());
 }
 
-pub(crate) fn clawback(e: &Env, admin: Address, from: Address, amount: i128) {
-    let topics = (symbol_short!("clawback"), admin, from);
-    e.events().publish(topics, amount);
+pub(crate) fn renounce_cancelled(e: &Env, admin: Address) {
+    let topics = (
+        Symbol::new(e, "renounce_cancelled"),
+        admin,
+        EVENT_SCHEMA_VERSION,
+    );
+    e.events().publish(topics, ());
 }
 
-pub(crate) fn set_authorized(e: &Env, admin: Address, id: Address, authorize: bool) {
-    let topics = (Symbol::new(e, "set_authorized"), admin, id);
-    e.events().publish(topics, authorize);
+pub(crate) fn lock_transfer(e: &Env, token_id: u32, until_ledger: u32) {
+    let topics = (symbol_short!("xfer_lock"), token_id, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, until_ledger);
 }
 
-pub(crate) fn set_admin(e: &Env, admin: Address, new_admin: Address) {
-    let topics = (symbol_short!("set_admin"), admin);
-    e.events().publish(topics, new_admin);
+pub(crate) fn burn_nft(e: &Env, owner: Address, token_id: u32) {
+    let topics = (symbol_short!("burn_nft"), owner, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, NftData { token_id });
 }
 
-pub(crate) fn burn(e: &Env, from: Address, amount: i128) {
-    let topics = (symbol_short!("burn"), from);
-    e.events().publish(topics, amount);
+pub(crate) fn upgrade(e: &Env, admin: Address, new_wasm_hash: BytesN<32>) {
+    let topics = (symbol_short!("upgrade"), admin, EVENT_SCHEMA_VERSION);
+    e.events().publish(topics, UpgradeData { new_wasm_hash });
 }
@@ -0,0 +1,23 @@
+use crate::error::TokenError;
+use crate::storage_types::DataKey;
+use soroban_sdk::{panic_with_error, Address, Env};
+
+pub fn is_frozen(e: &Env, addr: Address) -> bool {
+    let key = DataKey::Frozen(addr);
+    e.storage().persistent().get::<DataKey, bool>(&key).unwrap_or(false)
+}
+
+pub fn write_frozen(e: &Env, addr: Address, frozen: bool) {
+    let key = DataKey::Frozen(addr);
+    if frozen {
+        e.storage().persistent().set(&key, &true);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+pub fn check_not_frozen(e: &Env, addr: &Address) {
+    if is_frozen(e, addr.clone()) {
+        panic_with_error!(e, TokenError::AddressFrozen);
+    }
+}
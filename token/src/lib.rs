@@ -2,13 +2,20 @@
 
 mod admin;
 mod allowance;
+mod authorization;
 mod balance;
 mod contract;
+mod error;
 mod event;
+mod freeze;
 mod metadata;
+mod nft;
+mod permit;
+mod royalty;
 mod storage_types;
+mod supply;
 mod test;
+mod upgrade;
 mod custom_token_metadata;
-mod erc_functions;
 
 pub use crate::contract::TokenClient;
@@ -16,6 +16,23 @@ pub fn read_symbol(e: &Env) -> String {
     util.get_metadata().symbol
 }
 
+pub fn read_base_uri(e: &Env) -> String {
+    let util = CustomTokenUtils::new(e);
+    util.get_metadata().token_uri
+}
+
+pub fn write_base_uri(e: &Env, base_uri: String) {
+    let util = CustomTokenUtils::new(e);
+    let mut metadata = util.get_metadata();
+    metadata.token_uri = base_uri;
+    util.set_metadata(&metadata);
+}
+
+pub fn read_metadata(e: &Env) -> CustomTokenMetadata {
+    let util = CustomTokenUtils::new(e);
+    util.get_metadata()
+}
+
 pub fn write_metadata(e: &Env, metadata: CustomTokenMetadata) {
     let util = CustomTokenUtils::new(e);
     util.set_metadata(&metadata);
@@ -0,0 +1,175 @@
+use crate::storage_types::{DataKey, OperatorDataKey, TOKEN_BUMP_AMOUNT, TOKEN_LIFETIME_THRESHOLD};
+use petal_common::OwnerStore;
+use soroban_sdk::{Address, Env, String, Vec};
+
+// Per-token data lives in persistent entries of its own; instance storage is
+// loaded on every call and has a hard size limit, so it only keeps config.
+
+pub fn read_owner(e: &Env, token_id: u32) -> Option<Address> {
+    OwnerStore::new(e).get(token_id)
+}
+
+pub fn write_owner(e: &Env, token_id: u32, owner: &Address) {
+    OwnerStore::new(e).set(token_id, owner);
+}
+
+pub fn remove_owner(e: &Env, token_id: u32) {
+    OwnerStore::new(e).remove(token_id);
+}
+
+pub fn read_token_uri(e: &Env, token_id: u32) -> Option<String> {
+    let key = DataKey::TokenUri(token_id);
+    let uri = e.storage().persistent().get::<DataKey, String>(&key);
+    if uri.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, TOKEN_LIFETIME_THRESHOLD, TOKEN_BUMP_AMOUNT);
+    }
+    uri
+}
+
+pub fn write_token_uri(e: &Env, token_id: u32, uri: &String) {
+    let key = DataKey::TokenUri(token_id);
+    e.storage().persistent().set(&key, uri);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, TOKEN_LIFETIME_THRESHOLD, TOKEN_BUMP_AMOUNT);
+}
+
+pub fn remove_token_uri(e: &Env, token_id: u32) {
+    let key = DataKey::TokenUri(token_id);
+    e.storage().persistent().remove(&key);
+}
+
+// First ledger at which the token can change hands again; 0 if it was never
+// locked.
+pub fn read_transfer_lock(e: &Env, token_id: u32) -> u32 {
+    let key = DataKey::TransferLock(token_id);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn write_transfer_lock(e: &Env, token_id: u32, until_ledger: u32) {
+    let key = DataKey::TransferLock(token_id);
+    e.storage().persistent().set(&key, &until_ledger);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, TOKEN_LIFETIME_THRESHOLD, TOKEN_BUMP_AMOUNT);
+}
+
+pub fn remove_transfer_lock(e: &Env, token_id: u32) {
+    let key = DataKey::TransferLock(token_id);
+    e.storage().persistent().remove(&key);
+}
+
+pub fn read_approval(e: &Env, token_id: u32) -> Option<Address> {
+    let key = DataKey::Approval(token_id);
+    e.storage().persistent().get(&key)
+}
+
+pub fn write_approval(e: &Env, token_id: u32, approved: &Address) {
+    let key = DataKey::Approval(token_id);
+    e.storage().persistent().set(&key, approved);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, TOKEN_LIFETIME_THRESHOLD, TOKEN_BUMP_AMOUNT);
+}
+
+pub fn remove_approval(e: &Env, token_id: u32) {
+    let key = DataKey::Approval(token_id);
+    e.storage().persistent().remove(&key);
+}
+
+pub fn is_operator(e: &Env, owner: Address, operator: Address) -> bool {
+    let key = DataKey::Operator(OperatorDataKey { owner, operator });
+    e.storage().persistent().get(&key).unwrap_or(false)
+}
+
+pub fn write_operator(e: &Env, owner: Address, operator: Address, approved: bool) {
+    let key = DataKey::Operator(OperatorDataKey { owner, operator });
+    if approved {
+        e.storage().persistent().set(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, TOKEN_LIFETIME_THRESHOLD, TOKEN_BUMP_AMOUNT);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+pub fn read_owned_count(e: &Env, owner: Address) -> u32 {
+    let key = DataKey::OwnedCount(owner);
+    e.storage().persistent().get(&key).unwrap_or(0)
+}
+
+pub fn write_owned_count(e: &Env, owner: Address, count: u32) {
+    let key = DataKey::OwnedCount(owner);
+    e.storage().persistent().set(&key, &count);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, TOKEN_LIFETIME_THRESHOLD, TOKEN_BUMP_AMOUNT);
+}
+
+pub fn increment_owned_count(e: &Env, owner: &Address) {
+    let count = read_owned_count(e, owner.clone());
+    write_owned_count(e, owner.clone(), count + 1);
+}
+
+pub fn decrement_owned_count(e: &Env, owner: &Address) {
+    let count = read_owned_count(e, owner.clone());
+    write_owned_count(e, owner.clone(), count.saturating_sub(1));
+}
+
+// Minted token ids, so `get_owners` can still list every token. Each token
+// remembers its slot so burning can swap-remove it.
+pub fn add_to_index(e: &Env, token_id: u32) {
+    let count = read_token_count(e);
+    let key = DataKey::TokenByIndex(count);
+    e.storage().persistent().set(&key, &token_id);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, TOKEN_LIFETIME_THRESHOLD, TOKEN_BUMP_AMOUNT);
+    let slot_key = DataKey::TokenSlot(token_id);
+    e.storage().persistent().set(&slot_key, &count);
+    e.storage()
+        .persistent()
+        .extend_ttl(&slot_key, TOKEN_LIFETIME_THRESHOLD, TOKEN_BUMP_AMOUNT);
+    e.storage()
+        .instance()
+        .set(&DataKey::TokenCount, &(count + 1));
+}
+
+// Moves the last token into the burned token's slot. Tokens indexed before
+// slots were recorded keep theirs and are skipped by readers.
+pub fn remove_from_index(e: &Env, token_id: u32) {
+    let storage = e.storage().persistent();
+    let index: u32 = match storage.get(&DataKey::TokenSlot(token_id)) {
+        Some(index) => index,
+        None => return,
+    };
+    let last = read_token_count(e) - 1;
+    let last_key = DataKey::TokenByIndex(last);
+    if index != last {
+        let moved: u32 = storage.get(&last_key).unwrap();
+        storage.set(&DataKey::TokenByIndex(index), &moved);
+        storage.set(&DataKey::TokenSlot(moved), &index);
+    }
+    storage.remove(&last_key);
+    storage.remove(&DataKey::TokenSlot(token_id));
+    e.storage().instance().set(&DataKey::TokenCount, &last);
+}
+
+fn read_token_count(e: &Env) -> u32 {
+    let key = DataKey::TokenCount;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn read_token_ids(e: &Env) -> Vec<u32> {
+    let mut token_ids = Vec::new(e);
+    for index in 0..read_token_count(e) {
+        let key = DataKey::TokenByIndex(index);
+        if let Some(token_id) = e.storage().persistent().get(&key) {
+            token_ids.push_back(token_id);
+        }
+    }
+    token_ids
+}
@@ -0,0 +1,86 @@
+use crate::error::TokenError;
+use crate::storage_types::{
+    DataKey, PermitMessage, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{panic_with_error, xdr::ToXdr, Address, Bytes, BytesN, Env};
+
+// XDR of an account address: ScVal::Address (4 bytes), ScAddress::Account
+// (4 bytes), PublicKey::Ed25519 (4 bytes) followed by the raw 32 byte key.
+const ACCOUNT_ADDRESS_XDR_LEN: u32 = 44;
+const ACCOUNT_KEY_OFFSET: u32 = 12;
+
+pub fn read_permit_nonce(e: &Env, owner: Address) -> u32 {
+    let key = DataKey::Nonce(owner);
+    if let Some(nonce) = e.storage().persistent().get::<DataKey, u32>(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+        nonce
+    } else {
+        0
+    }
+}
+
+fn write_permit_nonce(e: &Env, owner: Address, nonce: u32) {
+    let key = DataKey::Nonce(owner);
+    e.storage().persistent().set(&key, &nonce);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+// Permits are ed25519 signatures, so only account owners can use them. An
+// owner that is a custom account contract approves through `approve`, where
+// `require_auth` runs its own `__check_auth`.
+fn check_owner_key(e: &Env, owner: &Address, public_key: &BytesN<32>) {
+    let owner_xdr = owner.clone().to_xdr(e);
+    if owner_xdr.len() != ACCOUNT_ADDRESS_XDR_LEN {
+        panic_with_error!(e, TokenError::InvalidPermitOwner);
+    }
+    let owner_key = owner_xdr.slice(ACCOUNT_KEY_OFFSET..ACCOUNT_ADDRESS_XDR_LEN);
+    if owner_key != Bytes::from(public_key) {
+        panic_with_error!(e, TokenError::PublicKeyMismatch);
+    }
+}
+
+// Binds permits to one network and one deployment, so a signature made for
+// a testnet contract cannot be replayed on mainnet or against another copy of
+// the token. Clients put this into `PermitMessage::domain`.
+pub fn domain_separator(e: &Env) -> BytesN<32> {
+    let mut data = Bytes::from(e.ledger().network_id());
+    data.append(&e.current_contract_address().to_xdr(e));
+    e.crypto().sha256(&data).into()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn verify_permit(
+    e: &Env,
+    owner: Address,
+    spender: Address,
+    amount: i128,
+    expiration_ledger: u32,
+    deadline: u64,
+    public_key: BytesN<32>,
+    signature: BytesN<64>,
+) {
+    if e.ledger().timestamp() > deadline {
+        panic_with_error!(e, TokenError::PermitExpired);
+    }
+
+    check_owner_key(e, &owner, &public_key);
+
+    let nonce = read_permit_nonce(e, owner.clone());
+    let message = PermitMessage {
+        domain: domain_separator(e),
+        owner: owner.clone(),
+        spender,
+        amount,
+        expiration_ledger,
+        deadline,
+        nonce,
+    };
+    e.crypto()
+        .ed25519_verify(&public_key, &message.to_xdr(e), &signature);
+
+    write_permit_nonce(e, owner, nonce + 1);
+}
@@ -0,0 +1,52 @@
+use crate::error::TokenError;
+use crate::storage_types::{DataKey, RoyaltyInfo};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+const FEE_DENOMINATOR: u32 = 10_000;
+
+fn check_bps(e: &Env, bps: u32) {
+    if bps > FEE_DENOMINATOR {
+        panic_with_error!(e, TokenError::RoyaltyTooHigh);
+    }
+}
+
+pub fn write_default_royalty(e: &Env, receiver: Address, bps: u32) {
+    check_bps(e, bps);
+    let key = DataKey::DefaultRoyalty;
+    e.storage().instance().set(&key, &RoyaltyInfo { receiver, bps });
+}
+
+pub fn write_token_royalty(e: &Env, token_id: u32, receiver: Address, bps: u32) {
+    check_bps(e, bps);
+    let key = DataKey::TokenRoyalty(token_id);
+    e.storage().persistent().set(&key, &RoyaltyInfo { receiver, bps });
+}
+
+pub fn remove_token_royalty(e: &Env, token_id: u32) {
+    let key = DataKey::TokenRoyalty(token_id);
+    e.storage().persistent().remove(&key);
+}
+
+pub fn read_royalty(e: &Env, token_id: u32) -> Option<RoyaltyInfo> {
+    let key = DataKey::TokenRoyalty(token_id);
+    if let Some(royalty) = e.storage().persistent().get::<DataKey, RoyaltyInfo>(&key) {
+        return Some(royalty);
+    }
+    e.storage().instance().get(&DataKey::DefaultRoyalty)
+}
+
+/// Returns the royalty receiver and amount owed for a sale. Without any
+/// royalty configured the contract itself is returned with a zero amount,
+/// the closest thing to ERC2981's `(address(0), 0)`.
+pub fn royalty_amount(e: &Env, token_id: u32, sale_price: i128) -> (Address, i128) {
+    match read_royalty(e, token_id) {
+        Some(royalty) => {
+            let amount = sale_price
+                .checked_mul(royalty.bps as i128)
+                .unwrap_or_else(|| panic_with_error!(e, TokenError::RoyaltyOverflow))
+                / FEE_DENOMINATOR as i128;
+            (royalty.receiver, amount)
+        }
+        None => (e.current_contract_address(), 0),
+    }
+}
@@ -1,7 +1,12 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, BytesN};
 
-pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 34560; // 2 days
+pub(crate) const INSTANCE_BUMP_AMOUNT_HIGH_WATERMARK: u32 = 34560; // 2 days
+pub(crate) const INSTANCE_BUMP_AMOUNT_LOW_WATERMARK: u32 = 17280; // 1 day
+pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
 pub(crate) const BALANCE_BUMP_AMOUNT: u32 = 518400; // 30 days
+pub(crate) const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub(crate) const TOKEN_BUMP_AMOUNT: u32 = 518400; // 30 days
+pub(crate) const TOKEN_LIFETIME_THRESHOLD: u32 = TOKEN_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
 #[derive(Clone)]
 #[contracttype]
@@ -16,12 +21,79 @@ pub struct AllowanceValue {
     pub expiration_ledger: u32,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct AllowanceEntry {
+    pub spender: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct PermitMessage {
+    pub domain: BytesN<32>,
+    pub owner: Address,
+    pub spender: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+    pub deadline: u64,
+    pub nonce: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct RoyaltyInfo {
+    pub receiver: Address,
+    pub bps: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct OperatorDataKey {
+    pub owner: Address,
+    pub operator: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct CheckpointKey {
+    pub addr: Address,
+    pub index: u32,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub balance: i128,
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum DataKey {
     Allowance(AllowanceDataKey),
+    AllowanceSpenders(Address),
     Balance(Address),
     Nonce(Address),
     State(Address),
-    Admin,
+    Frozen(Address),
+    // `Admin`, `Owner(u32)`, `AdminRenounced` and `AdminRenounceable` are
+    // `petal_common::SharedKey` entries.
+    TotalSupply,
+    MaxSupply,
+    DefaultRoyalty,
+    TokenRoyalty(u32),
+    StorageVersion,
+    TokenUri(u32),
+    Approval(u32),
+    Operator(OperatorDataKey),
+    OwnedCount(Address),
+    TokenCount,
+    TokenByIndex(u32),
+    CheckpointCount(Address),
+    Checkpoint(CheckpointKey),
+    Metadata,
+    TransferLock(u32),
+    TokenSlot(u32),
 }
@@ -0,0 +1,40 @@
+use crate::error::TokenError;
+use crate::storage_types::DataKey;
+use soroban_sdk::{panic_with_error, Env};
+
+pub fn read_total_supply(e: &Env) -> i128 {
+    let key = DataKey::TotalSupply;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+fn write_total_supply(e: &Env, amount: i128) {
+    let key = DataKey::TotalSupply;
+    e.storage().instance().set(&key, &amount);
+}
+
+pub fn read_max_supply(e: &Env) -> Option<i128> {
+    let key = DataKey::MaxSupply;
+    e.storage().instance().get(&key)
+}
+
+pub fn write_max_supply(e: &Env, max_supply: i128) {
+    let key = DataKey::MaxSupply;
+    e.storage().instance().set(&key, &max_supply);
+}
+
+pub fn increase_supply(e: &Env, amount: i128) {
+    let total = read_total_supply(e)
+        .checked_add(amount)
+        .unwrap_or_else(|| panic_with_error!(e, TokenError::SupplyOverflow));
+    if let Some(max_supply) = read_max_supply(e) {
+        if total > max_supply {
+            panic_with_error!(e, TokenError::MaxSupplyExceeded);
+        }
+    }
+    write_total_supply(e, total);
+}
+
+pub fn decrease_supply(e: &Env, amount: i128) {
+    let total = read_total_supply(e);
+    write_total_supply(e, total - amount);
+}
@@ -1,17 +1,60 @@
 #![cfg(test)]
 extern crate std;
 
+use crate::balance::{receive_balance, spend_balance};
+use crate::custom_token_metadata::CustomTokenMetadata;
+use crate::error::TokenError;
+use crate::event::{NftData, EVENT_SCHEMA_VERSION};
+use crate::nft::{read_approval, read_owned_count, read_token_ids};
+use crate::storage_types::{DataKey, PermitMessage};
+use crate::upgrade::{read_storage_version, CONTRACT_VERSION};
 use crate::{contract::Token, TokenClient};
+use ed25519_dalek::{Signer, SigningKey};
+use petal_common::uri::MAX_TOKEN_URI_LEN;
+use petal_common::SharedKey;
 use soroban_sdk::{
     symbol_short,
-    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
-    Address, Env, IntoVal, Symbol,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events, Ledger},
+    token, vec,
+    xdr::{AccountId, ContractEventBody, PublicKey, ScAddress, ToXdr, Uint256},
+    Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
 };
 
+// Events published by the last invocation, as (contract, topics, data).
+fn all_events(e: &Env) -> Vec<(Address, Vec<Val>, Val)> {
+    let mut out = Vec::new(e);
+    for event in e.events().all().events() {
+        let contract = event.contract_id.clone().unwrap();
+        let ContractEventBody::V0(body) = &event.body;
+        let mut topics = Vec::new(e);
+        for topic in body.topics.iter() {
+            topics.push_back(Val::try_from_val(e, topic).unwrap());
+        }
+        out.push_back((
+            Address::try_from_val(e, &ScAddress::Contract(contract)).unwrap(),
+            topics,
+            Val::try_from_val(e, &body.data).unwrap(),
+        ));
+    }
+    out
+}
+
 fn create_token<'a>(e: &Env, admin: &Address) -> TokenClient<'a> {
-    let token = TokenClient::new(e, &e.register_contract(None, Token {}));
-    token.initialize(admin, &7, &"name".into_val(e), &"symbol".into_val(e));
-    token
+    TokenClient::new(e, &deploy_token(e, admin, 7, None))
+}
+
+fn deploy_token(e: &Env, admin: &Address, decimal: u32, max_supply: Option<i128>) -> Address {
+    e.register(
+        Token,
+        (
+            admin,
+            decimal,
+            String::from_str(e, "name"),
+            String::from_str(e, "symbol"),
+            String::from_str(e, "https://petal.example/"),
+            max_supply,
+        ),
+    )
 }
 
 #[test]
@@ -19,14 +62,14 @@ fn test() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let admin1 = Address::random(&e);
-    let admin2 = Address::random(&e);
-    let user1 = Address::random(&e);
-    let user2 = Address::random(&e);
-    let user3 = Address::random(&e);
+    let admin1 = Address::generate(&e);
+    let admin2 = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let user3 = Address::generate(&e);
     let token = create_token(&e, &admin1);
 
-    token.mint(&user1, &1000);
+    token.mint_amount(&user1, &1000);
     assert_eq!(
         e.auths(),
         std::vec![(
@@ -34,7 +77,7 @@ fn test() {
             AuthorizedInvocation {
                 function: AuthorizedFunction::Contract((
                     token.address.clone(),
-                    symbol_short!("mint"),
+                    Symbol::new(&e, "mint_amount"),
                     (&user1, 1000_i128).into_val(&e),
                 )),
                 sub_invocations: std::vec![]
@@ -131,10 +174,10 @@ fn test() {
             }
         )]
     );
-    assert_eq!(token.authorized(&user2), false);
+    assert!(!token.authorized(&user2));
 
     token.set_authorized(&user3, &true);
-    assert_eq!(token.authorized(&user3), true);
+    assert!(token.authorized(&user3));
 
     token.clawback(&user3, &100);
     assert_eq!(
@@ -174,17 +217,87 @@ fn test() {
     assert_eq!(token.allowance(&user2, &user3), 0);
 }
 
+// Drives the contract through the SDK's standard token client, so a
+// signature drifting from SEP-41 fails here, and checks each standard
+// event's topics and data.
+#[test]
+fn sep41_interface_and_events() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    let sep41 = token::Client::new(&e, &token.address);
+
+    assert_eq!(sep41.decimals(), 7);
+    assert_eq!(sep41.name(), String::from_str(&e, "name"));
+    assert_eq!(sep41.symbol(), String::from_str(&e, "symbol"));
+
+    token.mint_amount(&user1, &1000);
+    let (contract, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(contract, token.address);
+    assert_eq!(
+        topics,
+        (symbol_short!("mint"), admin.clone(), user1.clone()).into_val(&e)
+    );
+    let amount: i128 = data.into_val(&e);
+    assert_eq!(amount, 1000);
+
+    sep41.approve(&user1, &user2, &500, &200);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&e, "approve"), user1.clone(), user2.clone()).into_val(&e)
+    );
+    let data: (i128, u32) = data.into_val(&e);
+    assert_eq!(data, (500, 200));
+    assert_eq!(sep41.allowance(&user1, &user2), 500);
+
+    sep41.transfer(&user1, &user2, &100);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (symbol_short!("transfer"), user1.clone(), user2.clone()).into_val(&e)
+    );
+    let amount: i128 = data.into_val(&e);
+    assert_eq!(amount, 100);
+
+    sep41.transfer_from(&user2, &user1, &user2, &200);
+    assert_eq!(sep41.balance(&user2), 300);
+    sep41.burn_from(&user2, &user1, &100);
+    assert_eq!(sep41.allowance(&user1, &user2), 200);
+
+    sep41.burn(&user1, &50);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(topics, (symbol_short!("burn"), user1.clone()).into_val(&e));
+    let amount: i128 = data.into_val(&e);
+    assert_eq!(amount, 50);
+    assert_eq!(sep41.balance(&user1), 550);
+
+    token.clawback(&user1, &25);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (symbol_short!("clawback"), admin, user1.clone()).into_val(&e)
+    );
+    let amount: i128 = data.into_val(&e);
+    assert_eq!(amount, 25);
+    assert_eq!(sep41.balance(&user1), 525);
+}
+
 #[test]
 fn test_burn() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let admin = Address::random(&e);
-    let user1 = Address::random(&e);
-    let user2 = Address::random(&e);
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
     let token = create_token(&e, &admin);
 
-    token.mint(&user1, &1000);
+    token.mint_amount(&user1, &1000);
     assert_eq!(token.balance(&user1), 1000);
 
     token.approve(&user1, &user2, &500, &200);
@@ -231,34 +344,34 @@ fn test_burn() {
 }
 
 #[test]
-#[should_panic(expected = "insufficient balance")]
+#[should_panic(expected = "Error(Contract, #8)")]
 fn transfer_insufficient_balance() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let admin = Address::random(&e);
-    let user1 = Address::random(&e);
-    let user2 = Address::random(&e);
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
     let token = create_token(&e, &admin);
 
-    token.mint(&user1, &1000);
+    token.mint_amount(&user1, &1000);
     assert_eq!(token.balance(&user1), 1000);
 
     token.transfer(&user1, &user2, &1001);
 }
 
 #[test]
-#[should_panic(expected = "can't receive when deauthorized")]
+#[should_panic(expected = "Error(Contract, #9)")]
 fn transfer_receive_deauthorized() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let admin = Address::random(&e);
-    let user1 = Address::random(&e);
-    let user2 = Address::random(&e);
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
     let token = create_token(&e, &admin);
 
-    token.mint(&user1, &1000);
+    token.mint_amount(&user1, &1000);
     assert_eq!(token.balance(&user1), 1000);
 
     token.set_authorized(&user2, &false);
@@ -266,17 +379,17 @@ fn transfer_receive_deauthorized() {
 }
 
 #[test]
-#[should_panic(expected = "can't spend when deauthorized")]
+#[should_panic(expected = "Error(Contract, #10)")]
 fn transfer_spend_deauthorized() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let admin = Address::random(&e);
-    let user1 = Address::random(&e);
-    let user2 = Address::random(&e);
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
     let token = create_token(&e, &admin);
 
-    token.mint(&user1, &1000);
+    token.mint_amount(&user1, &1000);
     assert_eq!(token.balance(&user1), 1000);
 
     token.set_authorized(&user1, &false);
@@ -284,18 +397,18 @@ fn transfer_spend_deauthorized() {
 }
 
 #[test]
-#[should_panic(expected = "insufficient allowance")]
+#[should_panic(expected = "Error(Contract, #7)")]
 fn transfer_from_insufficient_allowance() {
     let e = Env::default();
     e.mock_all_auths();
 
-    let admin = Address::random(&e);
-    let user1 = Address::random(&e);
-    let user2 = Address::random(&e);
-    let user3 = Address::random(&e);
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let user3 = Address::generate(&e);
     let token = create_token(&e, &admin);
 
-    token.mint(&user1, &1000);
+    token.mint_amount(&user1, &1000);
     assert_eq!(token.balance(&user1), 1000);
 
     token.approve(&user1, &user3, &100, &200);
@@ -305,25 +418,1194 @@ fn transfer_from_insufficient_allowance() {
 }
 
 #[test]
-#[should_panic(expected = "already initialized")]
+#[should_panic(expected = "Error(Contract, #3)")]
 fn initialize_already_initialized() {
     let e = Env::default();
-    let admin = Address::random(&e);
+    let admin = Address::generate(&e);
     let token = create_token(&e, &admin);
 
-    token.initialize(&admin, &10, &"name".into_val(&e), &"symbol".into_val(&e));
+    token.initialize(
+        &admin,
+        &10,
+        &"name".into_val(&e),
+        &"symbol".into_val(&e),
+        &"https://petal.example/".into_val(&e),
+        &None,
+    );
 }
 
 #[test]
-#[should_panic(expected = "Decimal must fit in a u8")]
+#[should_panic(expected = "Error(Contract, #4)")]
 fn decimal_is_over_max() {
     let e = Env::default();
-    let admin = Address::random(&e);
-    let token = TokenClient::new(&e, &e.register_contract(None, Token {}));
+    let admin = Address::generate(&e);
+    deploy_token(&e, &admin, u32::from(u8::MAX) + 1, None);
+}
+
+#[test]
+fn initialize_writes_metadata() {
+    let e = Env::default();
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    assert_eq!(token.decimals(), 7);
+    assert_eq!(token.name(), String::from_str(&e, "name"));
+    assert_eq!(token.symbol(), String::from_str(&e, "symbol"));
+
+    let metadata = token.metadata();
+    assert_eq!(metadata.decimal, 7);
+    assert_eq!(
+        metadata.token_uri,
+        String::from_str(&e, "https://petal.example/")
+    );
+}
+
+#[test]
+fn total_supply_tracks_mint_burn_and_clawback() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint_amount(&user1, &1000);
+    assert_eq!(token.total_supply(), 1000);
+
+    token.burn(&user1, &300);
+    assert_eq!(token.total_supply(), 700);
+
+    token.clawback(&user1, &200);
+    assert_eq!(token.total_supply(), 500);
+    assert_eq!(token.balance(&user1), 500);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn mint_amount_over_max_supply() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = TokenClient::new(&e, &deploy_token(&e, &admin, 7, Some(1000)));
+
+    token.mint_amount(&user1, &1000);
+    assert_eq!(token.total_supply(), 1000);
+
+    token.mint_amount(&user1, &1);
+}
+
+#[test]
+fn allowances_of_and_revoke_all() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let user3 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.approve(&user1, &user2, &500, &200);
+    token.approve(&user1, &user3, &100, &200);
+
+    let allowances = token.allowances_of(&user1, &0, &10);
+    assert_eq!(allowances.len(), 2);
+    assert_eq!(allowances.get(0).unwrap().spender, user2);
+    assert_eq!(allowances.get(0).unwrap().amount, 500);
+    assert_eq!(allowances.get(1).unwrap().spender, user3);
+
+    let second_page = token.allowances_of(&user1, &1, &1);
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page.get(0).unwrap().spender, user3);
+
+    token.revoke_all_allowances(&user1);
+    assert_eq!(token.allowance(&user1, &user2), 0);
+    assert_eq!(token.allowance(&user1, &user3), 0);
+    assert_eq!(token.allowances_of(&user1, &0, &10).len(), 0);
+}
+
+#[test]
+fn expired_allowances_are_purged() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let user3 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.approve(&user1, &user2, &500, &200);
+    token.approve(&user1, &user3, &100, &1000);
+    e.ledger().with_mut(|li| li.sequence_number = 300);
+
+    assert_eq!(token.purge_expired_allowances(&user1, &10), 1);
+    assert_eq!(token.allowance(&user1, &user2), 0);
+    let allowances = token.allowances_of(&user1, &0, &10);
+    assert_eq!(allowances.len(), 1);
+    assert_eq!(allowances.get(0).unwrap().spender, user3);
+    assert_eq!(token.purge_expired_allowances(&user1, &10), 0);
+}
+
+#[test]
+// The host drops the temporary allowance entry once its TTL lapses, so an
+// expired allowance reads back as empty.
+#[should_panic(expected = "Error(Contract, #7)")]
+fn transfer_from_expired_allowance() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint_amount(&user1, &1000);
+    token.approve(&user1, &user2, &500, &200);
+    e.ledger().with_mut(|li| li.sequence_number = 300);
+
+    token.transfer_from(&user2, &user1, &user2, &100);
+}
+
+#[test]
+fn balance_at_uses_checkpoints() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    e.ledger().with_mut(|li| li.sequence_number = 10);
+    token.mint_amount(&user1, &1000);
+    token.enable_checkpoints(&user1);
+
+    e.ledger().with_mut(|li| li.sequence_number = 20);
+    token.transfer(&user1, &user2, &300);
+    token.transfer(&user1, &user2, &100);
+
+    e.ledger().with_mut(|li| li.sequence_number = 30);
+    token.mint_amount(&user1, &50);
+
+    assert_eq!(token.balance_at(&user1, &5), 0);
+    assert_eq!(token.balance_at(&user1, &10), 1000);
+    assert_eq!(token.balance_at(&user1, &25), 600);
+    assert_eq!(token.balance_at(&user1, &30), 650);
+    assert_eq!(token.balance_at(&user1, &100), 650);
+}
+
+#[test]
+fn freeze_and_unfreeze() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint_amount(&user1, &1000);
+
+    token.freeze(&user2);
+    assert!(token.is_frozen(&user2));
+
+    token.unfreeze(&user2);
+    assert!(!token.is_frozen(&user2));
+
+    token.transfer(&user1, &user2, &100);
+    assert_eq!(token.balance(&user2), 100);
+}
+
+#[test]
+fn renounce_admin() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    assert!(token.has_admin());
+    assert!(!token.is_admin_renounceable());
+
+    token.set_admin_renounceable(&true);
+    token.request_renounce_admin();
+    let (_, topics, _) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&e, "renounce_requested"),
+            admin.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+
+    // The confirmation can't come in the ledger of the request.
+    assert_eq!(
+        token.try_renounce_admin(),
+        Err(Ok(TokenError::RenounceNotRequested.into()))
+    );
+    assert!(token.has_admin());
+
+    e.ledger().with_mut(|li| li.sequence_number += 1);
+    token.renounce_admin();
+    assert!(!token.has_admin());
+}
+
+#[test]
+fn renounce_admin_cancelled() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    token.set_admin_renounceable(&true);
+
+    // Confirming without a request fails.
+    assert_eq!(
+        token.try_renounce_admin(),
+        Err(Ok(TokenError::RenounceNotRequested.into()))
+    );
+
+    token.request_renounce_admin();
+    token.cancel_renounce_admin();
+    e.ledger().with_mut(|li| li.sequence_number += 1);
+    assert_eq!(
+        token.try_renounce_admin(),
+        Err(Ok(TokenError::RenounceNotRequested.into()))
+    );
+    assert_eq!(token.get_admin(), admin);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn renounce_admin_disabled() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    token.request_renounce_admin();
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn renounce_admin_disabled_after_request() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    token.set_admin_renounceable(&true);
+    token.request_renounce_admin();
+    token.set_admin_renounceable(&false);
+    e.ledger().with_mut(|li| li.sequence_number += 1);
+    token.renounce_admin();
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn initialize_after_renounce() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    token.set_admin_renounceable(&true);
+    token.request_renounce_admin();
+    e.ledger().with_mut(|li| li.sequence_number += 1);
+    token.renounce_admin();
     token.initialize(
         &admin,
-        &(u32::from(u8::MAX) + 1),
+        &7,
         &"name".into_val(&e),
         &"symbol".into_val(&e),
+        &"uri".into_val(&e),
+        &None,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn transfer_to_frozen() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint_amount(&user1, &1000);
+    token.freeze(&user2);
+    token.transfer(&user1, &user2, &1);
+}
+
+#[test]
+fn royalty_info_prefers_token_royalty() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let artist = Address::generate(&e);
+    let studio = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &artist);
+    token.mint(&2, &artist);
+
+    token.set_default_royalty(&studio, &250);
+    token.set_token_royalty(&2, &artist, &1000);
+
+    assert_eq!(token.royalty_info(&1, &10_000), (studio, 250));
+    assert_eq!(token.royalty_info(&2, &10_000), (artist, 1000));
+}
+
+#[test]
+fn version_and_migration_hook() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    assert_eq!(token.version(), CONTRACT_VERSION);
+
+    // Simulate state left behind by a wasm that predates versioning.
+    e.as_contract(&token.address, || {
+        e.storage().instance().remove(&DataKey::StorageVersion);
+        assert_eq!(read_storage_version(&e), 0);
+    });
+
+    token.mint_amount(&user, &10);
+    e.as_contract(&token.address, || {
+        assert_eq!(read_storage_version(&e), CONTRACT_VERSION);
+    });
+}
+
+#[test]
+fn token_maps_move_out_of_instance_storage() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    // Simulate a version 1 contract holding its tokens in instance maps.
+    e.as_contract(&token.address, || {
+        let mut owners: Map<u32, Address> = Map::new(&e);
+        owners.set(1, owner.clone());
+        let mut uris: Map<u32, String> = Map::new(&e);
+        uris.set(1, String::from_str(&e, "ipfs://one"));
+        e.storage()
+            .instance()
+            .set(&symbol_short!("OWNERS"), &owners);
+        e.storage().instance().set(&symbol_short!("URIS"), &uris);
+        e.storage().instance().set(&DataKey::StorageVersion, &1u32);
+    });
+
+    token.mint(&2, &owner);
+    assert_eq!(token.get_owners().len(), 2);
+    assert_eq!(token.token_uri(&1), String::from_str(&e, "ipfs://one"));
+    e.as_contract(&token.address, || {
+        assert!(!e.storage().instance().has(&symbol_short!("OWNERS")));
+        assert!(e.storage().persistent().has(&SharedKey::Owner(1)));
+        assert_eq!(read_storage_version(&e), CONTRACT_VERSION);
+    });
+}
+
+#[test]
+fn metadata_moves_to_data_key() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    let name = token.name();
+
+    // Simulate a version 2 contract holding its metadata under a symbol.
+    e.as_contract(&token.address, || {
+        let persistent = e.storage().persistent();
+        let metadata: CustomTokenMetadata = persistent.get(&DataKey::Metadata).unwrap();
+        persistent.remove(&DataKey::Metadata);
+        persistent.set(&symbol_short!("METADATA"), &metadata);
+        e.storage().instance().set(&DataKey::StorageVersion, &2u32);
+    });
+
+    // Reads still find it before the migration has run.
+    assert_eq!(token.name(), name);
+    token.mint_amount(&user, &10);
+    e.as_contract(&token.address, || {
+        assert!(!e.storage().persistent().has(&symbol_short!("METADATA")));
+        assert!(e.storage().persistent().has(&DataKey::Metadata));
+    });
+    assert_eq!(token.name(), name);
+}
+
+#[test]
+fn events_carry_schema_version() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    let (_, topics, data) = all_events(&e).first().unwrap();
+    assert_eq!(topics, (symbol_short!("schema"),).into_val(&e));
+    let version: u32 = data.into_val(&e);
+    assert_eq!(version, EVENT_SCHEMA_VERSION);
+    assert_eq!(token.get_event_schema_version(), version);
+
+    token.mint_amount(&user1, &100);
+    token.transfer(&user1, &user2, &40);
+    let (contract, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(contract, token.address);
+    // SEP-41 events keep their standard topics.
+    assert_eq!(
+        topics,
+        (symbol_short!("transfer"), user1.clone(), user2.clone()).into_val(&e)
+    );
+    let amount: i128 = data.into_val(&e);
+    assert_eq!(amount, 40);
+
+    token.mint(&1, &user1);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (symbol_short!("mint_nft"), user1, version).into_val(&e)
+    );
+    let data: NftData = data.into_val(&e);
+    assert_eq!(data, NftData { token_id: 1 });
+}
+
+#[test]
+fn domain_separator_binds_network_and_contract() {
+    let e = Env::default();
+
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    let other = create_token(&e, &admin);
+
+    let mut data = Bytes::from(e.ledger().network_id());
+    data.append(&token.address.clone().to_xdr(&e));
+    assert_eq!(token.get_domain_separator(), e.crypto().sha256(&data).to_bytes());
+    assert_ne!(token.get_domain_separator(), other.get_domain_separator());
+}
+
+// The account address whose ed25519 key is `key`; permits only work for
+// those.
+fn account_of(e: &Env, key: &SigningKey) -> Address {
+    let public_key = Uint256(key.verifying_key().to_bytes());
+    let account = AccountId(PublicKey::PublicKeyTypeEd25519(public_key));
+    Address::try_from_val(e, &ScAddress::Account(account)).unwrap()
+}
+
+// Signs the permit `token` expects for `owner`'s next nonce.
+fn sign_permit(
+    e: &Env,
+    token: &TokenClient,
+    key: &SigningKey,
+    spender: &Address,
+    amount: i128,
+    deadline: u64,
+) -> BytesN<64> {
+    let owner = account_of(e, key);
+    let message = PermitMessage {
+        domain: token.get_domain_separator(),
+        nonce: token.permit_nonce(&owner),
+        owner,
+        spender: spender.clone(),
+        amount,
+        expiration_ledger: 1000,
+        deadline,
+    };
+    let payload: std::vec::Vec<u8> = message.to_xdr(e).iter().collect();
+    BytesN::from_array(e, &key.sign(&payload).to_bytes())
+}
+
+#[test]
+fn permit_sets_allowance() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let spender = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let owner = account_of(&e, &key);
+    let public_key = BytesN::from_array(&e, &key.verifying_key().to_bytes());
+    token.mint_amount(&owner, &1000);
+
+    let signature = sign_permit(&e, &token, &key, &spender, 300, 100);
+    token.permit(&owner, &spender, &300, &1000, &100, &public_key, &signature);
+    assert_eq!(token.allowance(&owner, &spender), 300);
+    assert_eq!(token.permit_nonce(&owner), 1);
+
+    token.transfer_from(&spender, &owner, &spender, &300);
+    assert_eq!(token.balance(&spender), 300);
+
+    // The same signature can't be used for the next nonce.
+    assert!(token
+        .try_permit(&owner, &spender, &300, &1000, &100, &public_key, &signature)
+        .is_err());
+    assert_eq!(token.allowance(&owner, &spender), 0);
+    assert_eq!(token.permit_nonce(&owner), 1);
+}
+
+#[test]
+fn permit_rejects_bad_signature() {
+    let e = Env::default();
+
+    let admin = Address::generate(&e);
+    let spender = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let owner = account_of(&e, &key);
+    let public_key = BytesN::from_array(&e, &key.verifying_key().to_bytes());
+
+    // Signed for a different amount than the one submitted.
+    let signature = sign_permit(&e, &token, &key, &spender, 300, 100);
+    assert!(token
+        .try_permit(&owner, &spender, &301, &1000, &100, &public_key, &signature)
+        .is_err());
+
+    // Signed by a key that isn't the owner's.
+    let other = SigningKey::from_bytes(&[8; 32]);
+    let other_key = BytesN::from_array(&e, &other.verifying_key().to_bytes());
+    let signature = sign_permit(&e, &token, &other, &spender, 300, 100);
+    assert_eq!(
+        token.try_permit(&owner, &spender, &300, &1000, &100, &other_key, &signature),
+        Err(Ok(TokenError::PublicKeyMismatch.into()))
+    );
+    assert_eq!(token.allowance(&owner, &spender), 0);
+    assert_eq!(token.permit_nonce(&owner), 0);
+}
+
+#[test]
+fn permit_rejects_expired_deadline() {
+    let e = Env::default();
+
+    let admin = Address::generate(&e);
+    let spender = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let owner = account_of(&e, &key);
+    let public_key = BytesN::from_array(&e, &key.verifying_key().to_bytes());
+
+    let signature = sign_permit(&e, &token, &key, &spender, 300, 100);
+    e.ledger().with_mut(|li| li.timestamp = 101);
+    assert_eq!(
+        token.try_permit(&owner, &spender, &300, &1000, &100, &public_key, &signature),
+        Err(Ok(TokenError::PermitExpired.into()))
+    );
+
+    // Still good on the deadline itself.
+    e.ledger().with_mut(|li| li.timestamp = 100);
+    token.permit(&owner, &spender, &300, &1000, &100, &public_key, &signature);
+    assert_eq!(token.allowance(&owner, &spender), 300);
+}
+
+#[test]
+fn set_owners_and_token_uri_emit_events() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &owner);
+    token.set_owners(&owner, &1, &buyer);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("set_owner"),
+            owner.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+    let data: (u32, Option<Address>, Address) = data.into_val(&e);
+    assert_eq!(data, (1, Some(owner.clone()), buyer.clone()));
+    assert_eq!(token.get_owners().get(1), Some(buyer.clone()));
+
+    // The admin can still reassign any token.
+    token.set_owners(&admin, &1, &owner);
+    assert_eq!(token.get_owners().get(1), Some(owner.clone()));
+
+    let uri = String::from_str(&e, "ipfs://one");
+    token.set_token_uri(&owner, &1, &uri);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (Symbol::new(&e, "set_token_uri"), EVENT_SCHEMA_VERSION).into_val(&e)
+    );
+    let data: (u32, Option<String>, String) = data.into_val(&e);
+    assert_eq!(data, (1, None, uri));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")]
+fn set_owners_requires_owner_or_admin() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let thief = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &owner);
+    token.set_owners(&thief, &1, &thief);
+}
+
+#[test]
+fn transfer_lock_expires() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &owner);
+    e.ledger().with_mut(|li| li.sequence_number = 10);
+    token.lock_transfer(&1, &20);
+    assert_eq!(token.transferable_at(&1), 20);
+
+    // The admin isn't held by the lock.
+    token.set_owners(&admin, &1, &owner);
+
+    e.ledger().with_mut(|li| li.sequence_number = 20);
+    token.set_owners(&owner, &1, &buyer);
+    assert_eq!(token.get_owners().get(1), Some(buyer));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #27)")]
+fn transfer_lock_blocks_owner() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &owner);
+    token.lock_transfer(&1, &20);
+    token.set_owners(&owner, &1, &buyer);
+}
+
+#[test]
+fn supports_optional_interfaces() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let token = create_token(&e, &Address::generate(&e));
+
+    assert!(token.supports(&Symbol::new(&e, "nft_transfer")));
+    assert!(token.supports(&symbol_short!("royalties")));
+    assert!(!token.supports(&symbol_short!("escrow")));
+    assert!(!token.supports(&Symbol::new(&e, "ordered_signing")));
+}
+
+#[test]
+fn mint_nft_and_query_owners() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    assert!(!token.require_minted(&1));
+    token.mint(&1, &user1);
+    let (contract, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(contract, token.address);
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("mint_nft"),
+            user1.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+    let data: NftData = data.into_val(&e);
+    assert_eq!(data, NftData { token_id: 1 });
+    token.mint(&2, &user2);
+    token.mint(&3, &user1);
+
+    assert!(token.require_minted(&1));
+    let owners = token.get_owners();
+    assert_eq!(owners.len(), 3);
+    assert_eq!(owners.get(1), Some(user1.clone()));
+    assert_eq!(owners.get(2), Some(user2));
+    assert_eq!(owners.get(3), Some(user1.clone()));
+    assert_eq!(token.owner_of(&1), Some(user1));
+    assert_eq!(token.owner_of(&4), None);
+}
+
+#[test]
+fn mint_nft_requires_admin() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &user1);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    symbol_short!("mint"),
+                    (1_u32, &user1).into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn mint_nft_twice() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &user1);
+    token.mint(&1, &user1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn token_uri_of_nonexistent_token() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.token_uri(&1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn transfer_negative_amount() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint_amount(&user1, &10);
+    token.transfer(&user1, &user2, &-1);
+}
+
+#[test]
+fn try_transfer_insufficient_balance() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint_amount(&user1, &10);
+    assert_eq!(
+        token.try_transfer(&user1, &user2, &11),
+        Err(Ok(TokenError::InsufficientBalance.into()))
+    );
+    assert_eq!(token.balance(&user1), 10);
+    assert_eq!(token.balance(&user2), 0);
+}
+
+#[test]
+fn balance_arithmetic_is_checked() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint_amount(&user1, &10);
+    e.as_contract(&token.address, || {
+        assert_eq!(
+            spend_balance(&e, user1.clone(), 11),
+            Err(TokenError::InsufficientBalance)
+        );
+        assert_eq!(
+            receive_balance(&e, user1.clone(), i128::MAX),
+            Err(TokenError::BalanceOverflow)
+        );
+        assert_eq!(spend_balance(&e, user1.clone(), 10), Ok(()));
+    });
+    assert_eq!(token.balance(&user1), 0);
+}
+
+#[test]
+fn token_uri_falls_back_to_base_uri() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&42, &user1);
+    token.set_base_uri(&String::from_str(&e, "https://petal.example/"));
+    assert_eq!(
+        token.token_uri(&42),
+        String::from_str(&e, "https://petal.example/42")
+    );
+
+    let uri = String::from_str(&e, "ipfs://doc42");
+    token.set_token_uri(&user1, &42, &uri);
+    assert_eq!(token.token_uri(&42), uri);
+
+    let next_uri = String::from_str(&e, "ipfs://doc42-v2");
+    token.set_token_uri(&admin, &42, &next_uri);
+    let (_, _, data) = all_events(&e).last().unwrap();
+    let data: (u32, Option<String>, String) = data.into_val(&e);
+    assert_eq!(data, (42, Some(uri), next_uri.clone()));
+    assert_eq!(token.token_uri(&42), next_uri);
+}
+
+#[test]
+fn token_uri_without_base_uri() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    // No explicit URI and no base URI leaves the URI empty.
+    token.mint(&u32::MAX, &user1);
+    token.set_base_uri(&String::from_str(&e, ""));
+    assert_eq!(token.token_uri(&u32::MAX), String::from_str(&e, ""));
+
+    // The base URI is read on every call, so changing it moves the fallback.
+    token.set_base_uri(&String::from_str(&e, "ar://"));
+    assert_eq!(
+        token.token_uri(&u32::MAX),
+        String::from_str(&e, "ar://4294967295")
+    );
+    token.set_base_uri(&String::from_str(&e, "https://petal.example/v2/"));
+    assert_eq!(
+        token.token_uri(&u32::MAX),
+        String::from_str(&e, "https://petal.example/v2/4294967295")
+    );
+}
+
+#[test]
+fn token_uri_base_uri_too_long() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+    token.mint(&7, &user1);
+    token.mint(&10, &user1);
+
+    // One byte short of the limit still fits a single digit id, not two.
+    let base = "a".repeat(MAX_TOKEN_URI_LEN - 1);
+    token.set_base_uri(&String::from_str(&e, &base));
+    assert_eq!(token.token_uri(&7).len() as usize, MAX_TOKEN_URI_LEN);
+    assert_eq!(
+        token.try_token_uri(&10),
+        Err(Ok(TokenError::BaseUriTooLong.into()))
+    );
+
+    // An explicit URI doesn't depend on the base URI.
+    let uri = String::from_str(&e, "ipfs://doc10");
+    token.set_token_uri(&user1, &10, &uri);
+    assert_eq!(token.token_uri(&10), uri);
+}
+
+#[test]
+fn set_base_uri_requires_admin() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    let base = String::from_str(&e, "https://petal.example/");
+    token.set_base_uri(&base);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            admin.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    Symbol::new(&e, "set_base_uri"),
+                    (base,).into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn set_token_uri_of_nonexistent_token() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.set_token_uri(&admin, &1, &String::from_str(&e, "ipfs://doc1"));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #28)")]
+fn set_token_uri_requires_owner_or_admin() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &user1);
+    token.set_token_uri(&user2, &1, &String::from_str(&e, "ipfs://forged"));
+}
+
+#[test]
+fn approved_and_operator_can_transfer_nft() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let approved = Address::generate(&e);
+    let operator = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &owner);
+    token.mint(&2, &owner);
+    token.mint(&3, &owner);
+    token.approve_nft(&owner, &Some(approved.clone()), &1);
+    assert_eq!(token.get_approved(&1), Some(approved.clone()));
+    token.set_approval_for_all(&owner, &operator, &true);
+    assert!(token.is_approved_for_all(&owner, &operator));
+
+    token.set_owners(&approved, &1, &approved);
+    assert_eq!(
+        e.auths(),
+        std::vec![(
+            approved.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    token.address.clone(),
+                    Symbol::new(&e, "set_owners"),
+                    (&approved, 1_u32, &approved).into_val(&e),
+                )),
+                sub_invocations: std::vec![]
+            }
+        )]
+    );
+    assert_eq!(token.get_owners().get(1), Some(approved.clone()));
+    e.as_contract(&token.address, || {
+        assert_eq!(read_approval(&e, 1), None);
+    });
+
+    token.set_owners(&operator, &2, &operator);
+    assert_eq!(token.get_owners().get(2), Some(operator.clone()));
+
+    // An operator can approve on the owner's behalf, and the approved
+    // address can burn.
+    token.approve_nft(&operator, &Some(approved.clone()), &3);
+    let (_, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&e, "approve_nft"),
+            owner.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+    let data: (u32, Option<Address>) = data.into_val(&e);
+    assert_eq!(data, (3, Some(approved.clone())));
+    token.burn_nft(&approved, &3);
+    assert!(!token.require_minted(&3));
+
+    token.set_approval_for_all(&owner, &operator, &false);
+    assert!(!token.is_approved_for_all(&owner, &operator));
+}
+
+#[test]
+fn burn_nft_removes_token() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &user1);
+    token.set_token_uri(&user1, &1, &String::from_str(&e, "ipfs://doc1"));
+    token.burn_nft(&user1, &1);
+    let (contract, topics, data) = all_events(&e).last().unwrap();
+    assert_eq!(contract, token.address);
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("burn_nft"),
+            user1.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+    let data: NftData = data.into_val(&e);
+    assert_eq!(data, NftData { token_id: 1 });
+    assert!(!token.require_minted(&1));
+    assert_eq!(token.owner_of(&1), None);
+    assert_eq!(token.get_owners().len(), 0);
+}
+
+#[test]
+fn burn_nft_clears_index_and_lock() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &user1);
+    token.mint(&2, &user1);
+    token.mint(&3, &user1);
+    token.lock_transfer(&1, &20);
+    token.burn_nft(&user1, &1);
+
+    // The last token takes the burned one's slot.
+    e.as_contract(&token.address, || {
+        assert_eq!(read_token_ids(&e), vec![&e, 3, 2]);
+    });
+    assert_eq!(token.transferable_at(&1), 0);
+
+    // Minting the id again starts without the old lock.
+    token.mint(&1, &user1);
+    token.set_owners(&user1, &1, &admin);
+    assert_eq!(token.get_owners().len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn burn_nft_by_stranger() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let user1 = Address::generate(&e);
+    let user2 = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &user1);
+    token.burn_nft(&user2, &1);
+}
+
+#[test]
+fn burn_nft_by_approved_and_operator() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let approved = Address::generate(&e);
+    let operator = Address::generate(&e);
+    let studio = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    token.mint(&1, &owner);
+    token.mint(&2, &owner);
+    token.set_default_royalty(&studio, &250);
+    token.set_token_royalty(&1, &owner, &1000);
+    token.approve_nft(&owner, &Some(approved.clone()), &1);
+    token.set_approval_for_all(&owner, &operator, &true);
+    e.as_contract(&token.address, || {
+        assert_eq!(read_owned_count(&e, owner.clone()), 2);
+    });
+
+    // The event names the owner, not the approved address that burned.
+    token.burn_nft(&approved, &1);
+    let (_, topics, _) = all_events(&e).last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("burn_nft"),
+            owner.clone(),
+            EVENT_SCHEMA_VERSION
+        )
+            .into_val(&e)
+    );
+    assert_eq!(token.get_approved(&1), None);
+    assert_eq!(
+        token.try_token_uri(&1),
+        Err(Ok(TokenError::NonexistentToken.into()))
+    );
+    e.as_contract(&token.address, || {
+        assert_eq!(read_owned_count(&e, owner.clone()), 1);
+    });
+
+    token.burn_nft(&operator, &2);
+    e.as_contract(&token.address, || {
+        assert_eq!(read_owned_count(&e, owner.clone()), 0);
+        assert_eq!(read_token_ids(&e).len(), 0);
+    });
+
+    // A token minted again under a burned id starts without its royalty.
+    token.mint(&1, &owner);
+    assert_eq!(token.royalty_info(&1, &10_000), (studio, 250));
+}
+
+#[test]
+fn burn_nft_twice() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let token = create_token(&e, &admin);
+
+    assert_eq!(
+        token.try_burn_nft(&owner, &1),
+        Err(Ok(TokenError::NonexistentToken.into()))
+    );
+    token.mint(&1, &owner);
+    token.burn_nft(&owner, &1);
+    assert_eq!(
+        token.try_burn_nft(&owner, &1),
+        Err(Ok(TokenError::NonexistentToken.into()))
     );
 }
@@ -0,0 +1,103 @@
+use crate::nft::{
+    add_to_index, write_approval, write_operator, write_owned_count, write_owner, write_token_uri,
+};
+use crate::custom_token_metadata::CustomTokenMetadata;
+use crate::event;
+use crate::storage_types::DataKey;
+use soroban_sdk::{symbol_short, Address, Env, Map, String, Symbol};
+
+/// Version of the contract logic. Bump it together with a new step in
+/// `migrate_if_needed` whenever an upgrade changes the storage layout.
+pub(crate) const CONTRACT_VERSION: u32 = 3;
+
+pub fn read_storage_version(e: &Env) -> u32 {
+    let key = DataKey::StorageVersion;
+    e.storage().instance().get(&key).unwrap_or(0)
+}
+
+pub fn write_storage_version(e: &Env, version: u32) {
+    let key = DataKey::StorageVersion;
+    e.storage().instance().set(&key, &version);
+}
+
+/// Brings storage written by an older wasm up to date. Called at the start of
+/// every state-changing entrypoint, so the first such call after `upgrade`
+/// runs the pending steps once.
+pub fn migrate_if_needed(e: &Env) {
+    let version = read_storage_version(e);
+    if version >= CONTRACT_VERSION {
+        return;
+    }
+
+    // Contracts deployed before versioning was introduced need no changes to
+    // reach version 1.
+    if version < 2 {
+        move_token_maps_to_persistent(e);
+    }
+    if version < 3 {
+        move_metadata_to_data_key(e);
+    }
+
+    write_storage_version(e, CONTRACT_VERSION);
+    event::schema(e);
+}
+
+// Raw symbol keys written by older versions. Everything else lives under
+// `DataKey`; these are only read to migrate them.
+
+// Token metadata, keyed by symbol up to version 2.
+pub(crate) const LEGACY_METADATA: Symbol = symbol_short!("METADATA");
+
+// Maps that version 1 kept in instance storage.
+const OWNERS: Symbol = symbol_short!("OWNERS");
+const URIS: Symbol = symbol_short!("URIS");
+const APPROVALS: Symbol = symbol_short!("approvals");
+const OWNED_TOKEN_COUNT: Symbol = symbol_short!("tCount");
+const OPERATOR_APPROVAL: Symbol = symbol_short!("opApprov");
+
+fn move_token_maps_to_persistent(e: &Env) {
+    let instance = e.storage().instance();
+
+    if let Some(owners) = instance.get::<Symbol, Map<u32, Address>>(&OWNERS) {
+        for (token_id, owner) in owners.iter() {
+            write_owner(e, token_id, &owner);
+            add_to_index(e, token_id);
+        }
+        instance.remove(&OWNERS);
+    }
+    if let Some(uris) = instance.get::<Symbol, Map<u32, String>>(&URIS) {
+        for (token_id, uri) in uris.iter() {
+            write_token_uri(e, token_id, &uri);
+        }
+        instance.remove(&URIS);
+    }
+    if let Some(approvals) = instance.get::<Symbol, Map<u32, Address>>(&APPROVALS) {
+        for (token_id, approved) in approvals.iter() {
+            write_approval(e, token_id, &approved);
+        }
+        instance.remove(&APPROVALS);
+    }
+    if let Some(counts) = instance.get::<Symbol, Map<Address, u32>>(&OWNED_TOKEN_COUNT) {
+        for (owner, count) in counts.iter() {
+            write_owned_count(e, owner, count);
+        }
+        instance.remove(&OWNED_TOKEN_COUNT);
+    }
+    let operators = instance.get::<Symbol, Map<Address, Map<Address, bool>>>(&OPERATOR_APPROVAL);
+    if let Some(operators) = operators {
+        for (owner, approved) in operators.iter() {
+            for (operator, approved) in approved.iter() {
+                write_operator(e, owner.clone(), operator, approved);
+            }
+        }
+        instance.remove(&OPERATOR_APPROVAL);
+    }
+}
+
+fn move_metadata_to_data_key(e: &Env) {
+    let persistent = e.storage().persistent();
+    if let Some(metadata) = persistent.get::<Symbol, CustomTokenMetadata>(&LEGACY_METADATA) {
+        persistent.set(&DataKey::Metadata, &metadata);
+        persistent.remove(&LEGACY_METADATA);
+    }
+}